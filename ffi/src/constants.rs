@@ -21,6 +21,7 @@ use libc::c_uint;
 
 use loadorder::GameId;
 use loadorder::LoadOrderMethod;
+use loadorder::PluginTier;
 
 /// Success return code.
 #[no_mangle]
@@ -146,6 +147,22 @@ pub static LIBLO_GAME_TES5VR: c_uint = GameId::SkyrimVR as c_uint;
 #[no_mangle]
 pub static LIBLO_GAME_FO4VR: c_uint = GameId::Fallout4VR as c_uint;
 
+/// Game code for Nehrim: At Fate's Edge.
+#[no_mangle]
+pub static LIBLO_GAME_NEHRIM: c_uint = GameId::Nehrim as c_uint;
+
+/// Tier code for plugins that count towards the full plugin limit.
+#[no_mangle]
+pub static LIBLO_TIER_FULL: c_uint = PluginTier::Full as c_uint;
+
+/// Tier code for medium plugins. No currently supported game has medium plugins.
+#[no_mangle]
+pub static LIBLO_TIER_MEDIUM: c_uint = PluginTier::Medium as c_uint;
+
+/// Tier code for light plugins, which count towards the light plugin limit.
+#[no_mangle]
+pub static LIBLO_TIER_LIGHT: c_uint = PluginTier::Light as c_uint;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -161,5 +178,13 @@ mod tests {
         assert_eq!(7, LIBLO_GAME_TES5SE);
         assert_eq!(8, LIBLO_GAME_FO4VR);
         assert_eq!(9, LIBLO_GAME_TES5VR);
+        assert_eq!(10, LIBLO_GAME_NEHRIM);
+    }
+
+    #[test]
+    fn tier_constants_should_have_expected_integer_values() {
+        assert_eq!(0, LIBLO_TIER_FULL);
+        assert_eq!(1, LIBLO_TIER_MEDIUM);
+        assert_eq!(2, LIBLO_TIER_LIGHT);
     }
 }
@@ -57,6 +57,7 @@ fn map_game_id(game_id: u32) -> Result<GameId, u32> {
     match game_id {
         x if x == LIBLO_GAME_TES3 => Ok(GameId::Morrowind),
         x if x == LIBLO_GAME_TES4 => Ok(GameId::Oblivion),
+        x if x == LIBLO_GAME_NEHRIM => Ok(GameId::Nehrim),
         x if x == LIBLO_GAME_TES5 => Ok(GameId::Skyrim),
         x if x == LIBLO_GAME_TES5SE => Ok(GameId::SkyrimSE),
         x if x == LIBLO_GAME_TES5VR => Ok(GameId::SkyrimVR),
@@ -249,6 +250,7 @@ pub unsafe extern "C" fn lo_fix_plugin_lists(handle: lo_game_handle) -> c_uint {
 ///
 /// The order of Creation Club plugins as listed in `Fallout4.ccc` or
 /// `Skyrim.ccc` is as their hardcoded load order for libloadorder's purposes.
+/// Creation Club plugins that are listed but not installed are not included.
 ///
 /// If the list is empty, the `plugins` pointer will be null and `num_plugins`
 /// will be `0`.
@@ -26,6 +26,7 @@ use loadorder::Error;
 
 use super::ERROR_MESSAGE;
 use constants::*;
+use load_order::lo_load_order_entry;
 
 pub fn error(code: c_uint, message: &str) -> c_uint {
     ERROR_MESSAGE.with(|f| {
@@ -61,14 +62,23 @@ fn map_error(err: &Error) -> c_uint {
         &EncodeError(_) => LIBLO_ERROR_TEXT_ENCODE_FAIL,
         &PluginParsingError => LIBLO_ERROR_FILE_PARSE_FAIL,
         &PluginNotFound(_) => LIBLO_ERROR_INVALID_ARGS,
-        &TooManyActivePlugins => LIBLO_ERROR_INVALID_ARGS,
-        &InvalidRegex => LIBLO_ERROR_INTERNAL_LOGIC_ERROR,
+        &TooManyActivePlugins { .. } => LIBLO_ERROR_INVALID_ARGS,
+        &InvalidRegex(_) => LIBLO_ERROR_INTERNAL_LOGIC_ERROR,
         &DuplicatePlugin => LIBLO_ERROR_INVALID_ARGS,
         &NonMasterBeforeMaster => LIBLO_ERROR_INVALID_ARGS,
         &GameMasterMustLoadFirst => LIBLO_ERROR_INVALID_ARGS,
         &InvalidPlugin(_) => LIBLO_ERROR_INVALID_ARGS,
         &ImplicitlyActivePlugin(_) => LIBLO_ERROR_INVALID_ARGS,
         &NoLocalAppData => LIBLO_ERROR_INVALID_ARGS,
+        &InvalidPluginName(_) => LIBLO_ERROR_INVALID_ARGS,
+        &GameNotInstalled(_) => LIBLO_ERROR_FILE_NOT_FOUND,
+        &IncompleteLoadOrder(_) => LIBLO_ERROR_INVALID_ARGS,
+        &PluginLocked(_) => LIBLO_ERROR_INVALID_ARGS,
+        &IndexOutOfRange(_) => LIBLO_ERROR_INVALID_ARGS,
+        &ReadOnly => LIBLO_ERROR_IO_PERMISSION_DENIED,
+        &PermissionDenied(_) => LIBLO_ERROR_IO_PERMISSION_DENIED,
+        &LoadOrderLocked(_) => LIBLO_ERROR_IO_PERMISSION_DENIED,
+        &SubsetLoaded => LIBLO_ERROR_INVALID_ARGS,
     }
 }
 
@@ -103,6 +113,14 @@ pub fn to_c_string_array<S: AsRef<str>>(strings: &[S]) -> Result<(*mut *mut c_ch
     Ok((pointer, size))
 }
 
+pub unsafe fn free_load_order_entries(entries: Vec<lo_load_order_entry>) {
+    for entry in entries {
+        if !entry.name.is_null() {
+            drop(CString::from_raw(entry.name));
+        }
+    }
+}
+
 pub unsafe fn to_str_vec<'a>(
     array: *const *const c_char,
     array_size: usize,
@@ -30,6 +30,7 @@
 //! - Fallout: New Vegas
 //! - Fallout 4
 //! - Fallout 4 VR
+//! - Nehrim: At Fate's Edge
 //!
 //! ## Variable Types
 //!
@@ -122,7 +123,7 @@ mod load_order;
 pub use active_plugins::*;
 pub use constants::*;
 pub use handle::*;
-use helpers::error;
+use helpers::{error, free_load_order_entries};
 pub use load_order::*;
 
 thread_local!(static ERROR_MESSAGE: RefCell<CString> = RefCell::new(CString::default()));
@@ -143,39 +144,29 @@ pub unsafe extern "C" fn lo_get_version(
         if major.is_null() || minor.is_null() || patch.is_null() {
             error(LIBLO_ERROR_INVALID_ARGS, "Null pointer(s) passed")
         } else {
-            match env!("CARGO_PKG_VERSION_MAJOR").parse::<c_uint>() {
-                Ok(x) => *major = x,
-                Err(_) => {
-                    return error(
-                        LIBLO_ERROR_INVALID_ARGS,
-                        "Failed to parse major version number",
-                    )
-                }
-            }
-            match env!("CARGO_PKG_VERSION_MINOR").parse::<c_uint>() {
-                Ok(x) => *minor = x,
-                Err(_) => {
-                    return error(
-                        LIBLO_ERROR_INVALID_ARGS,
-                        "Failed to parse minor version number",
-                    )
-                }
-            }
-            match env!("CARGO_PKG_VERSION_PATCH").parse::<c_uint>() {
-                Ok(x) => *patch = x,
-                Err(_) => {
-                    return error(
-                        LIBLO_ERROR_INVALID_ARGS,
-                        "Failed to parse patch version number",
-                    )
-                }
-            }
+            let (major_version, minor_version, patch_version) = loadorder::version();
+            *major = major_version;
+            *minor = minor_version;
+            *patch = patch_version;
 
             LIBLO_OK
         }
     }).unwrap_or(LIBLO_ERROR_PANICKED)
 }
 
+/// Checks for library compatibility.
+///
+/// Checks whether the loaded libloadorder is compatible with the given version of libloadorder,
+/// abstracting library stability policy away from clients. The version numbering used is
+/// major.minor.patch.
+///
+/// Returns true if the library versions are compatible, false otherwise, including if the check
+/// panics.
+#[no_mangle]
+pub extern "C" fn lo_is_compatible(major: c_uint, minor: c_uint) -> bool {
+    catch_unwind(|| loadorder::is_compatible(major, minor)).unwrap_or(false)
+}
+
 /// Get the message for the last error or warning encountered.
 ///
 /// Outputs a string giving a message containing the details of the last error or warning
@@ -213,6 +204,22 @@ pub unsafe extern "C" fn lo_free_string(string: *mut c_char) {
     }
 }
 
+/// Free memory allocated to load order entry array output.
+///
+/// This function should be called to free memory allocated by `lo_get_load_order_entries()`,
+/// including the memory allocated to the entries' names.
+#[no_mangle]
+pub unsafe extern "C" fn lo_free_load_order_entries(
+    entries: *mut lo_load_order_entry,
+    num_entries: size_t,
+) {
+    if entries.is_null() || num_entries == 0 {
+        return;
+    }
+
+    free_load_order_entries(Vec::from_raw_parts(entries, num_entries, num_entries));
+}
+
 /// Free memory allocated to string array output.
 ///
 /// This function should be called to free memory allocated by any API function that outputs an
@@ -18,6 +18,7 @@
  */
 
 use std::error::Error;
+use std::mem;
 use std::panic::catch_unwind;
 use std::ptr;
 
@@ -26,7 +27,10 @@ use loadorder::LoadOrderMethod;
 
 use super::lo_game_handle;
 use constants::*;
-use helpers::{error, handle_error, to_c_string, to_c_string_array, to_str, to_str_vec};
+use helpers::{
+    error, free_load_order_entries, handle_error, to_c_string, to_c_string_array, to_str,
+    to_str_vec,
+};
 
 /// Get which method is used for the load order.
 ///
@@ -57,6 +61,76 @@ pub unsafe extern "C" fn lo_get_load_order_method(
     }).unwrap_or(LIBLO_ERROR_PANICKED)
 }
 
+/// A plugin's position, name, tier and active state in the load order.
+///
+/// `name` is a NUL-terminated UTF-8 string, and `tier` is one of the `LIBLO_TIER_*` codes.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+pub struct lo_load_order_entry {
+    pub index: u32,
+    pub name: *mut c_char,
+    pub tier: c_uint,
+    pub active: bool,
+}
+
+/// Get the current load order as an array of entries that give each plugin's position, name, tier
+/// and active state.
+///
+/// Each entry's name is a copy that is NUL-terminated and remains valid until the array is freed
+/// using `lo_free_load_order_entries()`. If no plugins are in the current order, the value pointed
+/// to by `entries` will be null and `num_entries` will point to zero.
+///
+/// Returns `LIBLO_OK` if successful, otherwise a `LIBLO_ERROR_*` code is returned.
+#[no_mangle]
+pub unsafe extern "C" fn lo_get_load_order_entries(
+    handle: lo_game_handle,
+    entries: *mut *mut lo_load_order_entry,
+    num_entries: *mut size_t,
+) -> c_uint {
+    catch_unwind(|| {
+        if handle.is_null() || entries.is_null() || num_entries.is_null() {
+            return error(LIBLO_ERROR_INVALID_ARGS, "Null pointer passed");
+        }
+        let handle = match (*handle).read() {
+            Err(e) => return error(LIBLO_ERROR_POISONED_THREAD_LOCK, e.description()),
+            Ok(h) => h,
+        };
+
+        *entries = ptr::null_mut();
+        *num_entries = 0;
+
+        let flat_entries = handle.flat_entries();
+
+        if flat_entries.is_empty() {
+            return LIBLO_OK;
+        }
+
+        let mut c_entries = Vec::with_capacity(flat_entries.len());
+        for entry in flat_entries {
+            let name = match to_c_string(entry.name) {
+                Ok(x) => x,
+                Err(x) => {
+                    free_load_order_entries(c_entries);
+                    return error(x, "A filename contained a null byte");
+                }
+            };
+            c_entries.push(lo_load_order_entry {
+                index: entry.index as u32,
+                name,
+                tier: entry.tier as c_uint,
+                active: entry.active,
+            });
+        }
+
+        c_entries.shrink_to_fit();
+        *num_entries = c_entries.len();
+        *entries = c_entries.as_mut_ptr();
+        mem::forget(c_entries);
+
+        LIBLO_OK
+    }).unwrap_or(LIBLO_ERROR_PANICKED)
+}
+
 /// Get the current load order.
 ///
 /// If no plugins are in the current order, the value pointed to by `plugins` will be null and
@@ -225,6 +225,23 @@ void test_lo_get_load_order() {
   lo_destroy_handle(handle);
 }
 
+void test_lo_get_load_order_entries() {
+  printf("testing lo_get_load_order_entries()...\n");
+  lo_game_handle handle = create_handle();
+
+  lo_load_order_entry * entries = NULL;
+  size_t num_entries = 0;
+  unsigned int return_code = lo_get_load_order_entries(handle, &entries, &num_entries);
+
+  assert(return_code == 0);
+  assert(num_entries == 10);
+  assert(entries[4].index == 4);
+  assert(strcmp(entries[4].name, "Blank.esp") == 0);
+  assert(entries[4].tier == LIBLO_TIER_FULL);
+  lo_free_load_order_entries(entries, num_entries);
+  lo_destroy_handle(handle);
+}
+
 void test_lo_set_plugin_position() {
   printf("testing lo_set_plugin_position()...\n");
   lo_game_handle handle = create_handle();
@@ -279,6 +296,7 @@ int main(void) {
   test_lo_get_load_order_method();
   test_lo_set_load_order();
   test_lo_get_load_order();
+  test_lo_get_load_order_entries();
   test_lo_set_plugin_position();
   test_lo_get_plugin_position();
   test_lo_get_indexed_plugin();
@@ -20,6 +20,7 @@ void test_game_id_values() {
   assert(LIBLO_GAME_TES5SE == 7);
   assert(LIBLO_GAME_FO4VR == 8);
   assert(LIBLO_GAME_TES5VR == 9);
+  assert(LIBLO_GAME_NEHRIM == 10);
 }
 
 void test_lo_get_version() {
@@ -35,6 +36,19 @@ void test_lo_get_version() {
   assert(patch == 0);
 }
 
+void test_lo_is_compatible() {
+  printf("testing lo_is_compatible()...\n");
+  unsigned int major;
+  unsigned int minor;
+  unsigned int patch;
+  unsigned int return_code = lo_get_version(&major, &minor, &patch);
+
+  assert(return_code == 0);
+  assert(lo_is_compatible(major, minor));
+  assert(lo_is_compatible(major, minor + 1));
+  assert(!lo_is_compatible(major + 1, minor));
+}
+
 void test_lo_get_error_message() {
   printf("testing lo_get_error_message()...\n");
   const char * message = nullptr;
@@ -228,6 +242,23 @@ void test_lo_get_load_order() {
   lo_destroy_handle(handle);
 }
 
+void test_lo_get_load_order_entries() {
+  printf("testing lo_get_load_order_entries()...\n");
+  lo_game_handle handle = create_handle();
+
+  lo_load_order_entry * entries = nullptr;
+  size_t num_entries = 0;
+  unsigned int return_code = lo_get_load_order_entries(handle, &entries, &num_entries);
+
+  assert(return_code == 0);
+  assert(num_entries == 10);
+  assert(entries[4].index == 4);
+  assert(strcmp(entries[4].name, "Blank.esp") == 0);
+  assert(entries[4].tier == LIBLO_TIER_FULL);
+  lo_free_load_order_entries(entries, num_entries);
+  lo_destroy_handle(handle);
+}
+
 void test_lo_set_plugin_position() {
   printf("testing lo_set_plugin_position()...\n");
   lo_game_handle handle = create_handle();
@@ -287,6 +318,7 @@ void test_thread_safety() {
 
 int main(void) {
   test_game_id_values();
+  test_lo_is_compatible();
 
   test_lo_get_error_message();
   test_lo_free_string();
@@ -304,6 +336,7 @@ int main(void) {
   test_lo_get_load_order_method();
   test_lo_set_load_order();
   test_lo_get_load_order();
+  test_lo_get_load_order_entries();
   test_lo_set_plugin_position();
   test_lo_get_plugin_position();
   test_lo_get_indexed_plugin();
@@ -40,6 +40,26 @@ pub enum LoadOrderMethod {
     Asterisk,
 }
 
+/// How to order plugins that have the same timestamp when loading a
+/// timestamp-based load order.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum TimestampTiebreak {
+    Filename,
+    ActiveFileOrder,
+    CreationTime,
+}
+
+/// How far apart saving spaces the timestamps that it adds to keep plugins'
+/// timestamps unique. `Minimal` uses the smallest increment that the usual
+/// filesystem for the OS can store: 100 nanoseconds on Windows, as NTFS is
+/// assumed, and one second elsewhere.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+pub enum TimestampSpacing {
+    #[default]
+    Minute,
+    Minimal,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
 pub enum GameId {
     Morrowind = 1,
@@ -51,13 +71,73 @@ pub enum GameId {
     SkyrimSE,
     Fallout4VR,
     SkyrimVR,
+    Nehrim,
+}
+
+/// The store that the game was bought from, as some stores' versions of a
+/// game keep their local app data in a different folder.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Default)]
+pub enum Distribution {
+    #[default]
+    Steam,
+    GOG,
+    GamePass,
+    Epic,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
 }
 
+impl LineEnding {
+    pub fn as_bytes(&self) -> &'static [u8] {
+        match *self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+        }
+    }
+}
+
+const ALL_GAME_IDS: &[GameId] = &[
+    GameId::Morrowind,
+    GameId::Oblivion,
+    GameId::Skyrim,
+    GameId::Fallout3,
+    GameId::FalloutNV,
+    GameId::Fallout4,
+    GameId::SkyrimSE,
+    GameId::Fallout4VR,
+    GameId::SkyrimVR,
+    GameId::Nehrim,
+];
+
 impl GameId {
+    pub fn all() -> &'static [GameId] {
+        ALL_GAME_IDS
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            GameId::Morrowind => "The Elder Scrolls III: Morrowind",
+            GameId::Oblivion => "The Elder Scrolls IV: Oblivion",
+            GameId::Skyrim => "The Elder Scrolls V: Skyrim",
+            GameId::SkyrimSE => "The Elder Scrolls V: Skyrim Special Edition",
+            GameId::SkyrimVR => "The Elder Scrolls V: Skyrim VR",
+            GameId::Fallout3 => "Fallout 3",
+            GameId::FalloutNV => "Fallout: New Vegas",
+            GameId::Fallout4 => "Fallout 4",
+            GameId::Fallout4VR => "Fallout 4 VR",
+            GameId::Nehrim => "Nehrim: At Fate's Edge",
+        }
+    }
+
     pub fn to_esplugin_id(&self) -> EspmId {
         match *self {
             GameId::Morrowind => EspmId::Morrowind,
             GameId::Oblivion => EspmId::Oblivion,
+            GameId::Nehrim => EspmId::Oblivion,
             GameId::Skyrim => EspmId::Skyrim,
             GameId::SkyrimSE => EspmId::SkyrimSE,
             GameId::SkyrimVR => EspmId::SkyrimSE,
@@ -75,6 +155,54 @@ impl GameId {
             _ => false,
         }
     }
+
+    /// Blueprint masters were introduced by Starfield, so no game that is
+    /// currently supported has them.
+    pub fn supports_blueprint_masters(&self) -> bool {
+        false
+    }
+}
+
+/// Where a plugin is, in the load order and on disk. A plugin in the load
+/// order is `Loaded`, whether or not its file is ghosted. Otherwise, it is
+/// `Ghosted` if it is installed with a .ghost extension, and `NotLoaded` if it
+/// is installed without one. `NotLoaded` is needed because installed plugins
+/// can be missing from the load order, e.g. after `load_subset()`, if they're
+/// quarantined or if they were installed since the last load, and neither
+/// `Loaded` nor `NotInstalled` would be true of them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum PluginStatus {
+    Loaded,
+    Ghosted,
+    NotLoaded,
+    NotInstalled,
+}
+
+/// How a plugin counts towards the game's active plugin limits. Medium
+/// plugins were introduced by Starfield, so no plugin for a game that is
+/// currently supported is `Medium`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum PluginTier {
+    Full,
+    Medium,
+    Light,
+}
+
+/// An advisory problem with a load order that the game will still accept.
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+pub enum ValidationWarning {
+    MastersNotAdjacent { first: String, second: String },
+}
+
+impl fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ValidationWarning::MastersNotAdjacent {
+                ref first,
+                ref second,
+            } => write!(f, "\"{}\" should load immediately after \"{}\"", second, first),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -88,14 +216,26 @@ pub enum Error {
     EncodeError(Cow<'static, str>),
     PluginParsingError,
     PluginNotFound(String),
-    TooManyActivePlugins,
-    InvalidRegex,
+    TooManyActivePlugins {
+        light_count: usize,
+        normal_count: usize,
+    },
+    InvalidRegex(regex::Error),
     DuplicatePlugin,
     NonMasterBeforeMaster,
     GameMasterMustLoadFirst,
     InvalidPlugin(String),
     ImplicitlyActivePlugin(String),
     NoLocalAppData,
+    InvalidPluginName(String),
+    GameNotInstalled(PathBuf),
+    IncompleteLoadOrder(Vec<String>),
+    PluginLocked(String),
+    IndexOutOfRange(usize),
+    ReadOnly,
+    PermissionDenied(PathBuf),
+    LoadOrderLocked(PathBuf),
+    SubsetLoaded,
 }
 
 #[cfg(windows)]
@@ -121,8 +261,8 @@ impl From<time::SystemTimeError> for Error {
 }
 
 impl From<regex::Error> for Error {
-    fn from(_: regex::Error) -> Self {
-        Error::InvalidRegex
+    fn from(error: regex::Error) -> Self {
+        Error::InvalidRegex(error)
     }
 }
 
@@ -161,10 +301,18 @@ impl fmt::Display for Error {
             Error::PluginNotFound(ref x) => {
                 write!(f, "The plugin \"{}\" is not in the load order", x)
             }
-            Error::TooManyActivePlugins => write!(f, "Maximum number of active plugins exceeded"),
-            Error::InvalidRegex => write!(
+            Error::TooManyActivePlugins {
+                light_count,
+                normal_count,
+            } => write!(
                 f,
-                "Internal error: regex used to parse Morrowind.ini is invalid"
+                "Maximum number of active plugins exceeded ({} normal plugins, {} light masters)",
+                normal_count, light_count
+            ),
+            Error::InvalidRegex(ref x) => write!(
+                f,
+                "Internal error: regex used to parse Morrowind.ini is invalid: {}",
+                x
             ),
             Error::DuplicatePlugin => write!(f, "The given plugin list contains duplicates"),
             Error::NonMasterBeforeMaster => write!(
@@ -184,6 +332,38 @@ impl fmt::Display for Error {
             Error::NoLocalAppData => {
                 write!(f, "The game's local app data folder could not be detected")
             }
+            Error::InvalidPluginName(ref x) => write!(
+                f,
+                "The plugin name \"{}\" contains a path separator or control character",
+                x
+            ),
+            Error::GameNotInstalled(ref x) => write!(
+                f,
+                "The game does not appear to be installed: \"{:?}\" does not exist",
+                x
+            ),
+            Error::IncompleteLoadOrder(ref x) => write!(
+                f,
+                "The load order is missing installed plugins: {}",
+                x.join(", ")
+            ),
+            Error::PluginLocked(ref x) => {
+                write!(f, "The plugin \"{}\" is locked and cannot be moved", x)
+            }
+            Error::IndexOutOfRange(x) => {
+                write!(f, "The index {} is outside the load order's bounds", x)
+            }
+            Error::ReadOnly => write!(f, "The load order is read-only and cannot be saved"),
+            Error::PermissionDenied(ref x) => {
+                write!(f, "Permission was denied when reading \"{:?}\"", x)
+            }
+            Error::LoadOrderLocked(ref x) => {
+                write!(f, "The load order is locked by the lock file \"{:?}\"", x)
+            }
+            Error::SubsetLoaded => write!(
+                f,
+                "Only a subset of the installed plugins was loaded, so it cannot be saved"
+            ),
         }
     }
 }
@@ -202,8 +382,10 @@ impl error::Error for Error {
             Error::EncodeError(_) => "Text could not be represented in Windows-1252",
             Error::PluginParsingError => "An error was encountered while parsing a plugin",
             Error::PluginNotFound(_) => "The plugin is not in the load order",
-            Error::TooManyActivePlugins => "Active plugins limit exceeded",
-            Error::InvalidRegex => "Internal error: regex used to parse Morrowind.ini is invalid",
+            Error::TooManyActivePlugins { .. } => "Active plugins limit exceeded",
+            Error::InvalidRegex(_) => {
+                "Internal error: regex used to parse Morrowind.ini is invalid"
+            }
             Error::DuplicatePlugin => "The given plugin list contains duplicates",
             Error::NonMasterBeforeMaster => {
                 "Attempted to load a non-master plugin before a master plugin"
@@ -214,13 +396,25 @@ impl error::Error for Error {
             Error::InvalidPlugin(_) => "The plugin file is invalid",
             Error::ImplicitlyActivePlugin(_) => "Implicitly active plugins cannot be deactivated",
             Error::NoLocalAppData => "The game's local app data folder could not be detected",
+            Error::InvalidPluginName(_) => {
+                "The plugin name contains a path separator or control character"
+            }
+            Error::GameNotInstalled(_) => "The game does not appear to be installed",
+            Error::IncompleteLoadOrder(_) => "The load order is missing installed plugins",
+            Error::PluginLocked(_) => "Locked plugins cannot be moved",
+            Error::IndexOutOfRange(_) => "The index is outside the load order's bounds",
+            Error::ReadOnly => "The load order is read-only and cannot be saved",
+            Error::PermissionDenied(_) => "Permission was denied when reading a path",
+            Error::LoadOrderLocked(_) => "The load order is locked by another process",
+            Error::SubsetLoaded => "A load order with only a subset of plugins cannot be saved",
         }
     }
 
-    fn cause(&self) -> Option<&error::Error> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match *self {
             Error::IoError(ref x) => Some(x),
             Error::SystemTimeError(ref x) => Some(x),
+            Error::InvalidRegex(ref x) => Some(x),
             _ => None,
         }
     }
@@ -230,10 +424,29 @@ impl error::Error for Error {
 mod tests {
     use super::*;
 
+    #[test]
+    fn game_id_all_should_contain_every_variant_once() {
+        assert_eq!(GameId::Nehrim as usize, GameId::all().len());
+
+        for (index, game_id) in GameId::all().iter().enumerate() {
+            assert_eq!(index + 1, *game_id as usize);
+        }
+    }
+
+    #[test]
+    fn game_id_names_should_be_unique() {
+        let mut names: Vec<&str> = GameId::all().iter().map(|g| g.name()).collect();
+        names.sort();
+        names.dedup();
+
+        assert_eq!(GameId::all().len(), names.len());
+    }
+
     #[test]
     fn game_id_should_map_to_libespm_id_correctly() {
         assert_eq!(EspmId::Morrowind, GameId::Morrowind.to_esplugin_id());
         assert_eq!(EspmId::Oblivion, GameId::Oblivion.to_esplugin_id());
+        assert_eq!(EspmId::Oblivion, GameId::Nehrim.to_esplugin_id());
         assert_eq!(EspmId::Skyrim, GameId::Skyrim.to_esplugin_id());
         assert_eq!(EspmId::SkyrimSE, GameId::SkyrimSE.to_esplugin_id());
         assert_eq!(EspmId::SkyrimSE, GameId::SkyrimVR.to_esplugin_id());
@@ -247,6 +460,7 @@ mod tests {
     fn game_id_supports_light_masters_should_be_false_until_fallout_4() {
         assert!(!GameId::Morrowind.supports_light_masters());
         assert!(!GameId::Oblivion.supports_light_masters());
+        assert!(!GameId::Nehrim.supports_light_masters());
         assert!(!GameId::Skyrim.supports_light_masters());
         assert!(GameId::SkyrimSE.supports_light_masters());
         assert!(GameId::SkyrimVR.supports_light_masters());
@@ -255,4 +469,53 @@ mod tests {
         assert!(GameId::Fallout4.supports_light_masters());
         assert!(GameId::Fallout4VR.supports_light_masters());
     }
+
+    #[test]
+    fn validation_warning_display_should_name_both_masters() {
+        let warning = ValidationWarning::MastersNotAdjacent {
+            first: "Skyrim.esm".to_string(),
+            second: "Update.esm".to_string(),
+        };
+
+        assert_eq!(
+            "\"Update.esm\" should load immediately after \"Skyrim.esm\"",
+            warning.to_string()
+        );
+    }
+
+    #[test]
+    fn error_display_should_include_the_underlying_io_error_message() {
+        let io_error = io::Error::new(io::ErrorKind::NotFound, "plugins.txt is missing");
+        let error = Error::from(io_error);
+
+        assert!(error.to_string().contains("plugins.txt is missing"));
+    }
+
+    #[test]
+    fn error_source_should_be_the_underlying_io_error() {
+        use std::error::Error as StdError;
+
+        let io_error = io::Error::new(io::ErrorKind::NotFound, "plugins.txt is missing");
+        let error = Error::from(io_error);
+
+        let source = error.source().unwrap();
+        assert_eq!("plugins.txt is missing", source.to_string());
+    }
+
+    #[test]
+    fn error_should_be_convertible_from_a_regex_error() {
+        use std::error::Error as StdError;
+
+        let unclosed_group = String::from("(");
+        let regex_error = regex::Regex::new(&unclosed_group).unwrap_err();
+
+        let error = Error::from(regex_error);
+        match error {
+            Error::InvalidRegex(_) => {}
+            ref e => panic!("Wrong error type: {:?}", e),
+        }
+
+        let source = error.source().unwrap();
+        assert!(source.is::<regex::Error>());
+    }
 }
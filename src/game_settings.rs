@@ -17,7 +17,8 @@
  * along with libloadorder. If not, see <http://www.gnu.org/licenses/>.
  */
 
-use std::fs::File;
+use std::collections::BTreeMap;
+use std::fs::{read_dir, File};
 use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
 use std::path::PathBuf;
@@ -28,19 +29,35 @@ use app_dirs;
 use encoding::all::WINDOWS_1252;
 use encoding::{DecoderTrap, Encoding};
 
-use enums::{Error, GameId, LoadOrderMethod};
+use enums::{Distribution, Error, GameId, LineEnding, LoadOrderMethod};
+use ghostable_path::GhostablePath;
 use load_order::AsteriskBasedLoadOrder;
 use load_order::TextfileBasedLoadOrder;
 use load_order::TimestampBasedLoadOrder;
 use load_order::WritableLoadOrder;
+use load_order::MAX_ACTIVE_NORMAL_PLUGINS;
+use plugin::{has_invalid_characters, has_valid_extension, trim_dot_ghost};
+use unicase::eq;
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct GameSettings {
     id: GameId,
     game_path: PathBuf,
+    plugins_directory: PathBuf,
+    additional_plugins_directories: Vec<PathBuf>,
+    additional_plugin_paths: BTreeMap<String, PathBuf>,
     plugins_file_path: PathBuf,
     load_order_path: Option<PathBuf>,
+    ccc_plugins: Vec<String>,
     implicitly_active_plugins: Vec<String>,
+    line_ending: LineEnding,
+    game_version: Option<(u32, u32, u32)>,
+    read_only: bool,
+    lock_on_save: bool,
+    active_plugins_warning_threshold: Option<usize>,
+    active_plugins_limit: Option<usize>,
+    master_file_override: Option<String>,
+    quarantined_plugins: Vec<String>,
 }
 
 const SKYRIM_HARDCODED_PLUGINS: &[&str] = &["Skyrim.esm", "Update.esm"];
@@ -77,30 +94,77 @@ const FALLOUT4VR_HARDCODED_PLUGINS: &[&str] = &["Fallout4.esm", "Fallout4_VR.esm
 impl GameSettings {
     #[cfg(windows)]
     pub fn new(game_id: GameId, game_path: &Path) -> Result<GameSettings, Error> {
+        GameSettings::with_distribution(game_id, game_path, Distribution::default())
+    }
+
+    /// Create settings for the given store's version of the game, which
+    /// determines where its active plugins file is kept.
+    #[cfg(windows)]
+    pub fn with_distribution(
+        game_id: GameId,
+        game_path: &Path,
+        distribution: Distribution,
+    ) -> Result<GameSettings, Error> {
         let local_app_data_path = app_dirs::get_data_root(app_dirs::AppDataType::UserCache)?;
-        let local_path = match appdata_folder_name(&game_id) {
+        let local_path = match appdata_folder_name(&game_id, distribution) {
             Some(x) => local_app_data_path.join(x),
             None => local_app_data_path,
         };
         GameSettings::with_local_path(game_id, game_path, &local_path)
     }
 
+    #[cfg(windows)]
+    pub fn new_validated(game_id: GameId, game_path: &Path) -> Result<GameSettings, Error> {
+        let settings = GameSettings::new(game_id, game_path)?;
+        settings.validate_installation()?;
+
+        Ok(settings)
+    }
+
+    pub fn with_local_path_validated(
+        game_id: GameId,
+        game_path: &Path,
+        local_path: &Path,
+    ) -> Result<GameSettings, Error> {
+        let settings = GameSettings::with_local_path(game_id, game_path, local_path)?;
+        settings.validate_installation()?;
+
+        Ok(settings)
+    }
+
     pub fn with_local_path(
         game_id: GameId,
         game_path: &Path,
         local_path: &Path,
     ) -> Result<GameSettings, Error> {
+        let plugins_directory = ini_plugins_directory(game_id, game_path, local_path)
+            .unwrap_or_else(|| game_path.join(plugins_folder_name(game_id)));
         let plugins_file_path = plugins_file_path(&game_id, game_path, local_path);
         let load_order_path = load_order_path(&game_id, local_path);
-        let implicitly_active_plugins = implicitly_active_plugins(game_id, game_path)?;
+        let ccc_plugins = read_ccc_plugins(game_id, game_path)?;
 
-        Ok(GameSettings {
+        let mut settings = GameSettings {
             id: game_id,
             game_path: game_path.to_path_buf(),
+            plugins_directory,
+            additional_plugins_directories: Vec::new(),
+            additional_plugin_paths: BTreeMap::new(),
             plugins_file_path,
             load_order_path,
-            implicitly_active_plugins,
-        })
+            ccc_plugins,
+            implicitly_active_plugins: Vec::new(),
+            line_ending: default_line_ending(game_id),
+            game_version: None,
+            read_only: false,
+            lock_on_save: false,
+            active_plugins_warning_threshold: None,
+            active_plugins_limit: None,
+            master_file_override: None,
+            quarantined_plugins: Vec::new(),
+        };
+        settings.update_implicitly_active_plugins();
+
+        Ok(settings)
     }
 
     pub fn id(&self) -> GameId {
@@ -110,7 +174,7 @@ impl GameSettings {
     pub fn load_order_method(&self) -> LoadOrderMethod {
         use enums::GameId::*;
         match self.id {
-            Morrowind | Oblivion | Fallout3 | FalloutNV => LoadOrderMethod::Timestamp,
+            Morrowind | Oblivion | Nehrim | Fallout3 | FalloutNV => LoadOrderMethod::Timestamp,
             Skyrim => LoadOrderMethod::Textfile,
             SkyrimSE | SkyrimVR | Fallout4 | Fallout4VR => LoadOrderMethod::Asterisk,
         }
@@ -124,31 +188,169 @@ impl GameSettings {
         }
     }
 
-    pub fn master_file(&self) -> &'static str {
+    /// Get the plugin that is treated as the game's main master file, which
+    /// is the game's own master file unless it has been overridden.
+    pub fn master_file(&self) -> &str {
+        match self.master_file_override {
+            Some(ref master_file) => master_file,
+            None => default_master_file(self.id),
+        }
+    }
+
+    /// Get the plugin that the game always loads first and always activates,
+    /// whatever the active plugins file says, if it has one. This is the
+    /// main master file for games that hardcode the loading of plugins.
+    pub fn hardcoded_first_plugin(&self) -> Option<&str> {
+        if hardcoded_plugins(self.id).is_empty() {
+            None
+        } else {
+            Some(self.master_file())
+        }
+    }
+
+    /// Check if the given plugin is the game's master file or one of the
+    /// plugins that the game hardcodes as implicitly active, which load before
+    /// all other plugins.
+    pub fn is_hardcoded_plugin(&self, plugin: &str) -> bool {
+        let plugin = trim_dot_ghost(plugin);
+        eq(plugin, self.master_file())
+            || hardcoded_plugins(self.id)
+                .iter()
+                .any(|p| eq(*p, plugin) && !eq(*p, default_master_file(self.id)))
+    }
+
+    /// Treat the given plugin as the game's main master file in place of the
+    /// game's own master file, e.g. for a total conversion. The override is
+    /// also hardcoded as implicitly active in place of the game's master file.
+    /// Passing `None` restores the game's own master file.
+    pub fn set_master_file_override(&mut self, master_file: Option<String>) {
+        self.master_file_override = master_file;
+        self.update_implicitly_active_plugins();
+    }
+
+    pub fn archive_extension(&self) -> &'static str {
         use enums::GameId::*;
         match self.id {
-            Morrowind => "Morrowind.esm",
-            Oblivion => "Oblivion.esm",
-            Skyrim | SkyrimSE | SkyrimVR => "Skyrim.esm",
-            Fallout3 => "Fallout3.esm",
-            FalloutNV => "FalloutNV.esm",
-            Fallout4 | Fallout4VR => "Fallout4.esm",
+            Fallout4 | Fallout4VR => ".ba2",
+            _ => ".bsa",
         }
     }
 
+    /// Get the file extensions that the game recognises as plugins, not
+    /// including any ghost extension.
+    pub fn plugin_extensions(&self) -> &'static [&'static str] {
+        if self.supports_light_masters() {
+            &[".esp", ".esm", ".esl"]
+        } else {
+            &[".esp", ".esm"]
+        }
+    }
+
+    /// Check if the given name is a well-formed plugin filename for the game,
+    /// i.e. it has one of the game's plugin extensions (optionally ghosted)
+    /// and doesn't contain any path separators or control characters.
+    pub fn is_valid_plugin_name(&self, name: &str) -> bool {
+        !has_invalid_characters(name) && has_valid_extension(name, self)
+    }
+
+    /// Get the (major, minor, patch) version of the game executable, if it
+    /// has been set or read using `refresh_game_version()`.
+    pub fn game_version(&self) -> Option<(u32, u32, u32)> {
+        self.game_version
+    }
+
+    pub fn set_game_version(&mut self, game_version: Option<(u32, u32, u32)>) {
+        self.game_version = game_version;
+    }
+
+    /// Read the game version from the game executable. This reads the whole
+    /// executable, so it's only done when requested.
+    pub fn refresh_game_version(&mut self) {
+        self.game_version = read_game_version(&self.game_path.join(executable_name(self.id)));
+    }
+
+    /// Check if the installed game supports light masters. Skyrim Special
+    /// Edition only gained support in version 1.5, so earlier versions don't.
+    pub fn supports_light_masters(&self) -> bool {
+        if self.id == GameId::SkyrimSE {
+            if let Some(version) = self.game_version() {
+                return version >= (1, 5, 0);
+            }
+        }
+
+        self.id.supports_light_masters()
+    }
+
     pub fn implicitly_active_plugins(&self) -> &[String] {
         &self.implicitly_active_plugins
     }
 
+    /// Get all the plugins listed in the game's Creation Club content file,
+    /// including any that aren't installed and so aren't implicitly active.
+    pub fn ccc_plugins(&self) -> &[String] {
+        &self.ccc_plugins
+    }
+
+    pub fn refresh_implicitly_active_plugins(&mut self) -> Result<(), Error> {
+        self.ccc_plugins = read_ccc_plugins(self.id, &self.game_path)?;
+        self.update_implicitly_active_plugins();
+
+        Ok(())
+    }
+
+    fn update_implicitly_active_plugins(&mut self) {
+        let plugin_names =
+            implicitly_active_plugins(self.id, self.master_file(), &self.ccc_plugins, |p| {
+                self.plugin_path(p).resolve_path().is_ok()
+            });
+        self.implicitly_active_plugins = plugin_names;
+    }
+
     pub fn is_implicitly_active(&self, plugin: &str) -> bool {
-        use unicase::eq;
         self.implicitly_active_plugins()
             .iter()
             .any(|p| eq(p.as_str(), plugin))
     }
 
     pub fn plugins_directory(&self) -> PathBuf {
-        self.game_path.join(self.plugins_folder_name())
+        self.plugins_directory.clone()
+    }
+
+    /// Borrowing equivalent of `plugins_directory()`.
+    pub fn plugins_directory_path(&self) -> &Path {
+        &self.plugins_directory
+    }
+
+    pub fn additional_plugins_directories(&self) -> &[PathBuf] {
+        &self.additional_plugins_directories
+    }
+
+    /// Set directories that are overlaid on top of the plugins directory, in
+    /// increasing order of priority, as in a virtual filesystem. A plugin in a
+    /// later directory shadows any plugin with the same filename in an earlier
+    /// directory or in the plugins directory.
+    pub fn set_additional_plugins_directories(&mut self, directories: Vec<PathBuf>) {
+        self.additional_plugins_directories = directories;
+        self.refresh_additional_plugins_directories();
+    }
+
+    /// Re-read the files in the additional plugins directories, which are
+    /// otherwise only read when the directories are set. Loading a load order
+    /// does this, so that plugins added since are found.
+    pub fn refresh_additional_plugins_directories(&mut self) {
+        self.additional_plugin_paths = index_files(&self.additional_plugins_directories);
+        self.update_implicitly_active_plugins();
+    }
+
+    /// Get the path to the given plugin (or other) file in the highest-priority
+    /// directory that contains it, falling back to the plugins directory.
+    /// Filenames are matched case-insensitively in the additional directories,
+    /// as they are on Windows.
+    pub fn plugin_path(&self, filename: &str) -> PathBuf {
+        self.additional_plugin_paths
+            .get(&trim_dot_ghost(filename).to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| self.plugins_directory.join(filename))
     }
 
     pub fn active_plugins_file(&self) -> &PathBuf {
@@ -159,26 +361,289 @@ impl GameSettings {
         self.load_order_path.as_ref()
     }
 
-    fn plugins_folder_name(&self) -> &'static str {
-        match self.id {
-            GameId::Morrowind => "Data Files",
-            _ => "Data",
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
+    }
+
+    /// Check if the load order may be saved. Read-only settings still allow
+    /// loading and changing the load order in memory, but nothing is written
+    /// to the game's files: activated plugins aren't unghosted, and the
+    /// `ActivePluginsFileLock` can't be taken.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// Check if saving the load order takes an `ActivePluginsFileLock`, if
+    /// the load order doesn't already hold it. If this is disabled, saving
+    /// ignores any lock held by another process.
+    pub fn lock_on_save(&self) -> bool {
+        self.lock_on_save
+    }
+
+    pub fn set_lock_on_save(&mut self, lock_on_save: bool) {
+        self.lock_on_save = lock_on_save;
+    }
+
+    pub fn quarantined_plugins(&self) -> &[String] {
+        &self.quarantined_plugins
+    }
+
+    /// Set plugins that are treated as if they aren't installed, without
+    /// changing their files. Quarantined plugins are left out of the load
+    /// order when it is loaded and can't be activated.
+    pub fn set_quarantined_plugins(&mut self, plugin_names: Vec<String>) {
+        self.quarantined_plugins = plugin_names;
+    }
+
+    pub fn is_quarantined(&self, plugin: &str) -> bool {
+        let plugin = trim_dot_ghost(plugin);
+        self.quarantined_plugins.iter().any(|p| eq(trim_dot_ghost(p), plugin))
+    }
+
+    /// Get the number of active normal plugins above which a load order is
+    /// considered nearly full, if it has been set.
+    pub fn active_plugins_warning_threshold(&self) -> Option<usize> {
+        self.active_plugins_warning_threshold
+    }
+
+    pub fn set_active_plugins_warning_threshold(&mut self, threshold: Option<usize>) {
+        self.active_plugins_warning_threshold = threshold;
+    }
+
+    /// Get the limit on active normal plugins set by a `# max=` directive in
+    /// the active plugins file, if one was read when the load order was last
+    /// loaded.
+    pub fn active_plugins_limit(&self) -> Option<usize> {
+        self.active_plugins_limit
+    }
+
+    pub fn set_active_plugins_limit(&mut self, limit: Option<usize>) {
+        self.active_plugins_limit = limit;
+    }
+
+    /// Get the maximum number of normal plugins that may be active, taking
+    /// any `# max=` directive into account.
+    pub fn max_active_normal_plugins(&self) -> usize {
+        self.active_plugins_limit
+            .map_or(MAX_ACTIVE_NORMAL_PLUGINS, |l| l.min(MAX_ACTIVE_NORMAL_PLUGINS))
+    }
+
+    fn validate_installation(&self) -> Result<(), Error> {
+        if !self.plugins_directory().is_dir() {
+            return Err(Error::GameNotInstalled(self.plugins_directory().to_path_buf()));
+        }
+
+        let master_file_path = self.plugins_directory().join(self.master_file());
+        if master_file_path.resolve_path().is_err() {
+            return Err(Error::GameNotInstalled(master_file_path));
         }
+
+        Ok(())
     }
 }
 
-fn appdata_folder_name(game_id: &GameId) -> Option<&str> {
+fn default_master_file(game_id: GameId) -> &'static str {
     use enums::GameId::*;
-    match *game_id {
+    match game_id {
+        Morrowind => "Morrowind.esm",
+        Oblivion => "Oblivion.esm",
+        Nehrim => "Nehrim.esm",
+        Skyrim | SkyrimSE | SkyrimVR => "Skyrim.esm",
+        Fallout3 => "Fallout3.esm",
+        FalloutNV => "FalloutNV.esm",
+        Fallout4 | Fallout4VR => "Fallout4.esm",
+    }
+}
+
+fn plugins_folder_name(game_id: GameId) -> &'static str {
+    match game_id {
+        GameId::Morrowind => "Data Files",
+        _ => "Data",
+    }
+}
+
+fn ini_file_name(game_id: GameId) -> Option<&'static str> {
+    use enums::GameId::*;
+    match game_id {
         Morrowind => None,
-        Oblivion => Some("Oblivion"),
-        Skyrim => Some("Skyrim"),
-        SkyrimSE => Some("Skyrim Special Edition"),
-        SkyrimVR => Some("Skyrim VR"),
-        Fallout3 => Some("Fallout3"),
-        FalloutNV => Some("FalloutNV"),
-        Fallout4 => Some("Fallout4"),
-        Fallout4VR => Some("Fallout4VR"),
+        Oblivion | Nehrim => Some("Oblivion.ini"),
+        Skyrim | SkyrimSE | SkyrimVR => Some("Skyrim.ini"),
+        Fallout3 | FalloutNV => Some("Fallout.ini"),
+        Fallout4 | Fallout4VR => Some("Fallout4.ini"),
+    }
+}
+
+/// Get the game's My Games folder, which holds its INI files. Its name is the
+/// same as the local app data folder's, and the Documents folder it's in is
+/// found relative to the local app data folder, so that a relocated or
+/// emulated user profile is handled consistently.
+fn my_games_path(local_path: &Path) -> Option<PathBuf> {
+    let folder_name = local_path.file_name()?;
+    local_path
+        .parent()
+        .and_then(Path::parent)
+        .and_then(Path::parent)
+        .map(|p| p.join("Documents").join("My Games").join(folder_name))
+}
+
+/// Get the plugins directory that the game's INI file sets using the
+/// SLocalMasterPath or SMasterPath keys, if it sets one. The INI file in the
+/// game's My Games folder is used if it exists, otherwise the INI file in the
+/// game path is used. Relative paths are relative to the game path.
+fn ini_plugins_directory(
+    game_id: GameId,
+    game_path: &Path,
+    local_path: &Path,
+) -> Option<PathBuf> {
+    let ini_file_name = ini_file_name(game_id)?;
+    let ini_path = my_games_path(local_path)
+        .map(|p| p.join(ini_file_name))
+        .filter(|p| p.exists())
+        .unwrap_or_else(|| game_path.join(ini_file_name));
+
+    let mut content = Vec::new();
+    File::open(ini_path)
+        .and_then(|mut f| f.read_to_end(&mut content))
+        .ok()?;
+    let content = WINDOWS_1252.decode(&content, DecoderTrap::Replace).ok()?;
+
+    let value = ["SLocalMasterPath", "SMasterPath"]
+        .iter()
+        .filter_map(|key| ini_value(&content, key))
+        .next()?;
+
+    if Path::new(value).is_absolute() {
+        Some(PathBuf::from(value))
+    } else {
+        Some(
+            value
+                .split(&['\\', '/'][..])
+                .filter(|c| !c.is_empty())
+                .fold(game_path.to_path_buf(), |path, c| path.join(c)),
+        )
+    }
+}
+
+/// Map the lowercased filenames (without any ghost extension) of the files in
+/// the given directories to their paths, with files in later directories
+/// replacing those with the same filename in earlier directories.
+fn index_files(directories: &[PathBuf]) -> BTreeMap<String, PathBuf> {
+    let mut paths = BTreeMap::new();
+    for directory in directories {
+        let entries = match read_dir(directory) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            if let Some(name) = entry.file_name().to_str() {
+                paths.insert(trim_dot_ghost(name).to_lowercase(), entry.path());
+            }
+        }
+    }
+
+    paths
+}
+
+fn ini_value<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(k), Some(v)) if k.trim().eq_ignore_ascii_case(key) => Some(v.trim()),
+                _ => None,
+            }
+        })
+        .find(|v| !v.is_empty())
+}
+
+fn default_line_ending(game_id: GameId) -> LineEnding {
+    // Morrowind's active plugins are stored in Morrowind.ini, which the game
+    // and its launcher write with CRLF line endings. The other games accept
+    // either ending in plugins.txt, and libloadorder has always written LF.
+    match game_id {
+        GameId::Morrowind => LineEnding::CrLf,
+        _ => LineEnding::Lf,
+    }
+}
+
+fn executable_name(game_id: GameId) -> &'static str {
+    match game_id {
+        GameId::Morrowind => "Morrowind.exe",
+        GameId::Oblivion | GameId::Nehrim => "Oblivion.exe",
+        GameId::Skyrim => "TESV.exe",
+        GameId::SkyrimSE => "SkyrimSE.exe",
+        GameId::SkyrimVR => "SkyrimVR.exe",
+        GameId::Fallout3 => "Fallout3.exe",
+        GameId::FalloutNV => "FalloutNV.exe",
+        GameId::Fallout4 => "Fallout4.exe",
+        GameId::Fallout4VR => "Fallout4VR.exe",
+    }
+}
+
+fn read_game_version(executable_path: &Path) -> Option<(u32, u32, u32)> {
+    let mut content = Vec::new();
+    File::open(executable_path)
+        .and_then(|mut f| f.read_to_end(&mut content))
+        .ok()?;
+
+    parse_file_version(&content)
+}
+
+/// Find the VS_FIXEDFILEINFO structure in an executable's version resource
+/// by its signature, and read the file version from it. The version's build
+/// number is ignored.
+fn parse_file_version(content: &[u8]) -> Option<(u32, u32, u32)> {
+    const SIGNATURE: [u8; 4] = [0xBD, 0x04, 0xEF, 0xFE];
+
+    let position = content
+        .windows(SIGNATURE.len())
+        .position(|w| w == SIGNATURE)?;
+    let version = content.get(position + 8..position + 16)?;
+
+    let most_significant = read_u32(&version[..4]);
+    let least_significant = read_u32(&version[4..]);
+
+    Some((
+        most_significant >> 16,
+        most_significant & 0xFFFF,
+        least_significant >> 16,
+    ))
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .rev()
+        .fold(0, |value, byte| (value << 8) | u32::from(*byte))
+}
+
+fn appdata_folder_name(game_id: &GameId, distribution: Distribution) -> Option<&str> {
+    use enums::GameId::*;
+    match (*game_id, distribution) {
+        (Morrowind, _) => None,
+        (Oblivion, _) => Some("Oblivion"),
+        (Nehrim, _) => Some("Nehrim"),
+        (Skyrim, _) => Some("Skyrim"),
+        (SkyrimSE, Distribution::GOG) => Some("Skyrim Special Edition GOG"),
+        (SkyrimSE, Distribution::GamePass) => Some("Skyrim Special Edition MS"),
+        (SkyrimSE, Distribution::Epic) => Some("Skyrim Special Edition EPIC"),
+        (SkyrimSE, Distribution::Steam) => Some("Skyrim Special Edition"),
+        (SkyrimVR, _) => Some("Skyrim VR"),
+        (Fallout3, _) => Some("Fallout3"),
+        (FalloutNV, _) => Some("FalloutNV"),
+        (Fallout4, Distribution::GamePass) => Some("Fallout4 MS"),
+        (Fallout4, _) => Some("Fallout4"),
+        (Fallout4VR, _) => Some("Fallout4VR"),
     }
 }
 
@@ -192,11 +657,13 @@ fn load_order_path(game_id: &GameId, local_path: &Path) -> Option<PathBuf> {
 fn plugins_file_path(game_id: &GameId, game_path: &Path, local_path: &Path) -> PathBuf {
     let ini_path = game_path.join("Oblivion.ini");
     match *game_id {
-        GameId::Oblivion if ini_path.exists() => if use_my_games_directory(&ini_path) {
-            local_path
-        } else {
-            game_path
-        }.join("plugins.txt"),
+        GameId::Oblivion | GameId::Nehrim if ini_path.exists() => {
+            if use_my_games_directory(&ini_path) {
+                local_path
+            } else {
+                game_path
+            }.join("plugins.txt")
+        }
         GameId::Morrowind => game_path.join("Morrowind.ini"),
         _ => local_path.join("plugins.txt"),
     }
@@ -245,34 +712,61 @@ fn hardcoded_plugins(game_id: GameId) -> &'static [&'static str] {
     }
 }
 
-fn implicitly_active_plugins(game_id: GameId, game_path: &Path) -> Result<Vec<String>, Error> {
-    let mut plugin_names: Vec<String> = hardcoded_plugins(game_id)
-        .iter()
-        .map(|s| s.to_string())
-        .collect();
-
-    if let Some(file_path) = ccc_file_path(game_id, game_path) {
-        if file_path.exists() {
+fn read_ccc_plugins(game_id: GameId, game_path: &Path) -> Result<Vec<String>, Error> {
+    match ccc_file_path(game_id, game_path) {
+        Some(ref file_path) if file_path.exists() => {
             let reader = BufReader::new(File::open(file_path)?);
 
-            let lines = reader.lines().filter_map(|line| {
-                line.ok()
-                    .and_then(|l| if l.is_empty() { None } else { Some(l) })
-            });
-
-            plugin_names.extend(lines);
+            Ok(reader
+                .lines()
+                .filter_map(|line| {
+                    line.ok()
+                        .and_then(|l| if l.is_empty() { None } else { Some(l) })
+                })
+                .collect())
         }
+        _ => Ok(Vec::new()),
     }
+}
 
-    Ok(plugin_names)
+fn implicitly_active_plugins<F: Fn(&str) -> bool>(
+    game_id: GameId,
+    master_file: &str,
+    ccc_plugins: &[String],
+    is_installed: F,
+) -> Vec<String> {
+    let default_master_file = default_master_file(game_id);
+    let mut plugin_names: Vec<String> = hardcoded_plugins(game_id)
+        .iter()
+        .map(|s| {
+            if *s == default_master_file {
+                master_file.to_string()
+            } else {
+                s.to_string()
+            }
+        })
+        .collect();
+
+    // Creation Club content can be listed without having been downloaded, and
+    // only content that is installed is loaded.
+    plugin_names.extend(
+        ccc_plugins
+            .iter()
+            .filter(|p| is_installed(p))
+            .cloned(),
+    );
+
+    plugin_names
 }
 
 #[cfg(test)]
 mod tests {
     #[cfg(windows)]
     use std::env;
+    use std::fs::{create_dir, create_dir_all, OpenOptions};
     use std::io::Write;
     use tempfile::tempdir;
+    use tests::copy_to_test_dir;
 
     use super::*;
 
@@ -283,8 +777,14 @@ mod tests {
     ) -> GameSettings {
         let mut file = File::create(ccc_file_path(game_id, &game_path).unwrap()).unwrap();
 
+        let plugins_directory = game_path.join(plugins_folder_name(game_id));
+        if !plugins_directory.exists() {
+            create_dir(&plugins_directory).unwrap();
+        }
+
         for plugin_name in plugin_names {
             writeln!(file, "{}", plugin_name).unwrap();
+            File::create(plugins_directory.join(plugin_name)).unwrap();
         }
 
         GameSettings::with_local_path(game_id, &game_path, &Path::new("local")).unwrap()
@@ -403,6 +903,11 @@ mod tests {
         ).unwrap();
         assert_eq!("Oblivion.esm", settings.master_file());
 
+        settings =
+            GameSettings::with_local_path(GameId::Nehrim, &PathBuf::default(), &PathBuf::default())
+                .unwrap();
+        assert_eq!("Nehrim.esm", settings.master_file());
+
         settings =
             GameSettings::with_local_path(GameId::Skyrim, &PathBuf::default(), &PathBuf::default())
                 .unwrap();
@@ -453,33 +958,113 @@ mod tests {
 
     #[test]
     fn appdata_folder_name_should_be_mapped_from_game_id() {
-        assert!(appdata_folder_name(&GameId::Morrowind).is_none());
+        assert!(appdata_folder_name(&GameId::Morrowind, Distribution::Steam).is_none());
 
-        let mut folder = appdata_folder_name(&GameId::Oblivion).unwrap();
+        let mut folder = appdata_folder_name(&GameId::Oblivion, Distribution::Steam).unwrap();
         assert_eq!("Oblivion", folder);
 
-        folder = appdata_folder_name(&GameId::Skyrim).unwrap();
+        folder = appdata_folder_name(&GameId::Nehrim, Distribution::Steam).unwrap();
+        assert_eq!("Nehrim", folder);
+
+        folder = appdata_folder_name(&GameId::Skyrim, Distribution::Steam).unwrap();
         assert_eq!("Skyrim", folder);
 
-        folder = appdata_folder_name(&GameId::SkyrimSE).unwrap();
+        folder = appdata_folder_name(&GameId::SkyrimSE, Distribution::Steam).unwrap();
         assert_eq!("Skyrim Special Edition", folder);
 
-        folder = appdata_folder_name(&GameId::SkyrimVR).unwrap();
+        folder = appdata_folder_name(&GameId::SkyrimVR, Distribution::Steam).unwrap();
         assert_eq!("Skyrim VR", folder);
 
-        folder = appdata_folder_name(&GameId::Fallout3).unwrap();
+        folder = appdata_folder_name(&GameId::Fallout3, Distribution::Steam).unwrap();
         assert_eq!("Fallout3", folder);
 
-        folder = appdata_folder_name(&GameId::FalloutNV).unwrap();
+        folder = appdata_folder_name(&GameId::FalloutNV, Distribution::Steam).unwrap();
         assert_eq!("FalloutNV", folder);
 
-        folder = appdata_folder_name(&GameId::Fallout4).unwrap();
+        folder = appdata_folder_name(&GameId::Fallout4, Distribution::Steam).unwrap();
         assert_eq!("Fallout4", folder);
 
-        folder = appdata_folder_name(&GameId::Fallout4VR).unwrap();
+        folder = appdata_folder_name(&GameId::Fallout4VR, Distribution::Steam).unwrap();
         assert_eq!("Fallout4VR", folder);
     }
 
+    #[test]
+    fn appdata_folder_name_should_differ_for_some_distributions() {
+        let steam = appdata_folder_name(&GameId::SkyrimSE, Distribution::Steam);
+        let gog = appdata_folder_name(&GameId::SkyrimSE, Distribution::GOG);
+        let game_pass = appdata_folder_name(&GameId::SkyrimSE, Distribution::GamePass);
+        let epic = appdata_folder_name(&GameId::SkyrimSE, Distribution::Epic);
+
+        assert_eq!(Some("Skyrim Special Edition"), steam);
+        assert_eq!(Some("Skyrim Special Edition GOG"), gog);
+        assert_eq!(Some("Skyrim Special Edition MS"), game_pass);
+        assert_eq!(Some("Skyrim Special Edition EPIC"), epic);
+
+        assert_eq!(
+            Some("Fallout4 MS"),
+            appdata_folder_name(&GameId::Fallout4, Distribution::GamePass)
+        );
+        assert_eq!(
+            appdata_folder_name(&GameId::Oblivion, Distribution::Steam),
+            appdata_folder_name(&GameId::Oblivion, Distribution::GamePass)
+        );
+    }
+
+    #[test]
+    fn with_local_path_should_use_the_plugins_directory_set_in_the_game_ini() {
+        let tmp_dir = tempdir().unwrap();
+        let game_path = tmp_dir.path();
+
+        let mut file = File::create(game_path.join("Oblivion.ini")).unwrap();
+        writeln!(file, "[General]").unwrap();
+        writeln!(file, "SLocalMasterPath=Other Data\\").unwrap();
+
+        let settings =
+            GameSettings::with_local_path(GameId::Oblivion, game_path, &PathBuf::default())
+                .unwrap();
+
+        assert_eq!(game_path.join("Other Data"), settings.plugins_directory());
+    }
+
+    #[test]
+    fn with_local_path_should_prefer_the_game_ini_in_the_my_games_folder() {
+        let tmp_dir = tempdir().unwrap();
+        let game_path = tmp_dir.path().join("game");
+        let local_path = tmp_dir.path().join("AppData").join("Local").join("Skyrim");
+        let my_games_path = tmp_dir.path().join("Documents").join("My Games").join("Skyrim");
+        create_dir_all(&game_path).unwrap();
+        create_dir_all(&my_games_path).unwrap();
+
+        let mut file = File::create(game_path.join("Skyrim.ini")).unwrap();
+        writeln!(file, "[General]").unwrap();
+        writeln!(file, "SLocalMasterPath=Game Data\\").unwrap();
+
+        let mut file = File::create(my_games_path.join("Skyrim.ini")).unwrap();
+        writeln!(file, "[General]").unwrap();
+        writeln!(file, "SLocalMasterPath=Other Data\\").unwrap();
+
+        let settings = GameSettings::with_local_path(GameId::Skyrim, &game_path, &local_path)
+            .unwrap();
+
+        assert_eq!(game_path.join("Other Data"), settings.plugins_directory());
+    }
+
+    #[test]
+    fn with_local_path_should_use_the_default_plugins_directory_if_the_ini_does_not_set_one() {
+        let tmp_dir = tempdir().unwrap();
+        let game_path = tmp_dir.path();
+
+        let mut file = File::create(game_path.join("Skyrim.ini")).unwrap();
+        writeln!(file, "[General]").unwrap();
+        writeln!(file, "sLanguage=ENGLISH").unwrap();
+
+        let settings =
+            GameSettings::with_local_path(GameId::Skyrim, game_path, &PathBuf::default())
+                .unwrap();
+
+        assert_eq!(game_path.join("Data"), settings.plugins_directory());
+    }
+
     #[test]
     fn plugins_folder_name_should_be_mapped_from_game_id() {
         let mut settings = GameSettings::with_local_path(
@@ -487,61 +1072,61 @@ mod tests {
             &PathBuf::default(),
             &PathBuf::default(),
         ).unwrap();
-        assert_eq!("Data Files", settings.plugins_folder_name());
+        assert_eq!("Data Files", plugins_folder_name(settings.id()));
 
         settings = GameSettings::with_local_path(
             GameId::Oblivion,
             &PathBuf::default(),
             &PathBuf::default(),
         ).unwrap();
-        assert_eq!("Data", settings.plugins_folder_name());
+        assert_eq!("Data", plugins_folder_name(settings.id()));
 
         settings =
             GameSettings::with_local_path(GameId::Skyrim, &PathBuf::default(), &PathBuf::default())
                 .unwrap();
-        assert_eq!("Data", settings.plugins_folder_name());
+        assert_eq!("Data", plugins_folder_name(settings.id()));
 
         settings = GameSettings::with_local_path(
             GameId::SkyrimSE,
             &PathBuf::default(),
             &PathBuf::default(),
         ).unwrap();
-        assert_eq!("Data", settings.plugins_folder_name());
+        assert_eq!("Data", plugins_folder_name(settings.id()));
 
         settings = GameSettings::with_local_path(
             GameId::SkyrimVR,
             &PathBuf::default(),
             &PathBuf::default(),
         ).unwrap();
-        assert_eq!("Data", settings.plugins_folder_name());
+        assert_eq!("Data", plugins_folder_name(settings.id()));
 
         settings = GameSettings::with_local_path(
             GameId::Fallout3,
             &PathBuf::default(),
             &PathBuf::default(),
         ).unwrap();
-        assert_eq!("Data", settings.plugins_folder_name());
+        assert_eq!("Data", plugins_folder_name(settings.id()));
 
         settings = GameSettings::with_local_path(
             GameId::FalloutNV,
             &PathBuf::default(),
             &PathBuf::default(),
         ).unwrap();
-        assert_eq!("Data", settings.plugins_folder_name());
+        assert_eq!("Data", plugins_folder_name(settings.id()));
 
         settings = GameSettings::with_local_path(
             GameId::Fallout4,
             &PathBuf::default(),
             &PathBuf::default(),
         ).unwrap();
-        assert_eq!("Data", settings.plugins_folder_name());
+        assert_eq!("Data", plugins_folder_name(settings.id()));
 
         settings = GameSettings::with_local_path(
             GameId::Fallout4VR,
             &PathBuf::default(),
             &PathBuf::default(),
         ).unwrap();
-        assert_eq!("Data", settings.plugins_folder_name());
+        assert_eq!("Data", plugins_folder_name(settings.id()));
     }
 
     #[test]
@@ -653,6 +1238,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn nehrim_settings_should_use_nehrim_paths_and_oblivion_ini_handling() {
+        let tmp_dir = tempdir().unwrap();
+        let game_path = tmp_dir.path();
+
+        let settings =
+            GameSettings::with_local_path(GameId::Nehrim, game_path, Path::new("local"))
+                .unwrap();
+        assert_eq!(LoadOrderMethod::Timestamp, settings.load_order_method());
+        assert_eq!("Nehrim.esm", settings.master_file());
+        assert_eq!(game_path.join("Data"), settings.plugins_directory());
+        assert_eq!(
+            Path::new("local/plugins.txt"),
+            settings.active_plugins_file()
+        );
+
+        let mut file = File::create(game_path.join("Oblivion.ini")).unwrap();
+        file.write_all("...\nbUseMyGamesDirectory=0\n...".as_bytes())
+            .unwrap();
+
+        let settings =
+            GameSettings::with_local_path(GameId::Nehrim, game_path, Path::new("local"))
+                .unwrap();
+        assert_eq!(
+            game_path.join("plugins.txt"),
+            *settings.active_plugins_file()
+        );
+    }
+
     #[test]
     fn implicitly_active_plugins_should_be_mapped_from_game_id() {
         let mut settings =
@@ -806,6 +1420,54 @@ mod tests {
         assert_eq!(plugins, settings.implicitly_active_plugins());
     }
 
+    #[test]
+    fn implicitly_active_plugins_should_not_include_ccc_plugins_that_are_not_installed() {
+        let tmp_dir = tempdir().unwrap();
+        let game_path = tmp_dir.path();
+
+        game_with_ccc_plugins(GameId::SkyrimSE, game_path, &["ccBGSSSE001-Fish.esm"]);
+
+        {
+            let mut file = OpenOptions::new()
+                .append(true)
+                .open(game_path.join("Skyrim.ccc"))
+                .unwrap();
+            writeln!(file, "ccBGSSSE025-AdvDSGS.esm").unwrap();
+        }
+
+        let settings =
+            GameSettings::with_local_path(GameId::SkyrimSE, game_path, Path::new("local"))
+                .unwrap();
+
+        assert_eq!(
+            vec!["ccBGSSSE001-Fish.esm", "ccBGSSSE025-AdvDSGS.esm"],
+            settings.ccc_plugins()
+        );
+        assert!(settings.is_implicitly_active("ccBGSSSE001-Fish.esm"));
+        assert!(!settings.is_implicitly_active("ccBGSSSE025-AdvDSGS.esm"));
+    }
+
+    #[test]
+    fn implicitly_active_plugins_should_include_ccc_plugins_installed_in_additional_directories() {
+        let tmp_dir = tempdir().unwrap();
+        let game_path = tmp_dir.path().join("game");
+        let other_path = tmp_dir.path().join("other");
+        create_dir_all(&game_path).unwrap();
+        create_dir_all(&other_path).unwrap();
+
+        let mut file = File::create(game_path.join("Skyrim.ccc")).unwrap();
+        writeln!(file, "ccBGSSSE001-Fish.esm").unwrap();
+        File::create(other_path.join("ccBGSSSE001-Fish.esm")).unwrap();
+
+        let mut settings =
+            GameSettings::with_local_path(GameId::SkyrimSE, &game_path, Path::new("local"))
+                .unwrap();
+        assert!(!settings.is_implicitly_active("ccBGSSSE001-Fish.esm"));
+
+        settings.set_additional_plugins_directories(vec![other_path]);
+        assert!(settings.is_implicitly_active("ccBGSSSE001-Fish.esm"));
+    }
+
     #[test]
     fn is_implicitly_active_should_return_true_iff_the_plugin_is_implicitly_active() {
         let settings =
@@ -815,6 +1477,58 @@ mod tests {
         assert!(!settings.is_implicitly_active("Test.esm"));
     }
 
+    #[test]
+    fn set_master_file_override_should_replace_the_game_master_file_as_implicitly_active() {
+        let mut settings =
+            GameSettings::with_local_path(GameId::Skyrim, &PathBuf::default(), &PathBuf::default())
+                .unwrap();
+
+        settings.set_master_file_override(Some("Conversion.esm".to_string()));
+        assert_eq!("Conversion.esm", settings.master_file());
+        assert_eq!(
+            vec!["Conversion.esm", "Update.esm"],
+            settings.implicitly_active_plugins()
+        );
+        assert!(settings.is_implicitly_active("Conversion.esm"));
+        assert!(!settings.is_implicitly_active("Skyrim.esm"));
+
+        settings.set_master_file_override(None);
+        assert_eq!("Skyrim.esm", settings.master_file());
+        assert!(settings.is_implicitly_active("Skyrim.esm"));
+        assert!(!settings.is_implicitly_active("Conversion.esm"));
+    }
+
+    #[test]
+    fn hardcoded_first_plugin_should_be_the_master_file_for_games_with_hardcoded_plugins() {
+        let mut settings =
+            GameSettings::with_local_path(GameId::Skyrim, &PathBuf::default(), &PathBuf::default())
+                .unwrap();
+        assert_eq!(Some("Skyrim.esm"), settings.hardcoded_first_plugin());
+
+        settings.set_master_file_override(Some("Conversion.esm".to_string()));
+        assert_eq!(Some("Conversion.esm"), settings.hardcoded_first_plugin());
+
+        let settings = GameSettings::with_local_path(
+            GameId::Oblivion,
+            &PathBuf::default(),
+            &PathBuf::default(),
+        ).unwrap();
+        assert_eq!(None, settings.hardcoded_first_plugin());
+    }
+
+    #[test]
+    fn is_quarantined_should_match_case_insensitively_and_ignore_ghost_extensions() {
+        let mut settings =
+            GameSettings::with_local_path(GameId::Skyrim, &PathBuf::default(), &PathBuf::default())
+                .unwrap();
+        assert!(!settings.is_quarantined("Blank.esp"));
+
+        settings.set_quarantined_plugins(vec!["Blank.esp".to_string()]);
+        assert!(settings.is_quarantined("blank.esp"));
+        assert!(settings.is_quarantined("Blank.esp.ghost"));
+        assert!(!settings.is_quarantined("Blank.esm"));
+    }
+
     #[test]
     fn is_implicitly_active_should_match_case_insensitively() {
         let settings =
@@ -823,6 +1537,268 @@ mod tests {
         assert!(settings.is_implicitly_active("update.esm"));
     }
 
+    #[test]
+    fn with_local_path_validated_should_succeed_for_an_installed_game() {
+        let tmp_dir = tempdir().unwrap();
+        let game_path = tmp_dir.path();
+
+        let settings =
+            GameSettings::with_local_path(GameId::Skyrim, game_path, &PathBuf::default()).unwrap();
+        copy_to_test_dir("Blank.esm", "Skyrim.esm", &settings);
+
+        let validated_settings =
+            GameSettings::with_local_path_validated(GameId::Skyrim, game_path, &PathBuf::default())
+                .unwrap();
+
+        assert_eq!(settings, validated_settings);
+    }
+
+    #[test]
+    fn with_local_path_validated_should_error_for_an_empty_game_directory() {
+        let tmp_dir = tempdir().unwrap();
+        let game_path = tmp_dir.path();
+
+        let result =
+            GameSettings::with_local_path_validated(GameId::Skyrim, game_path, &PathBuf::default());
+
+        match result.unwrap_err() {
+            Error::GameNotInstalled(ref x) => assert_eq!(&game_path.join("Data"), x),
+            e => panic!("Wrong error type: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn with_local_path_validated_should_error_if_the_game_master_file_is_missing() {
+        let tmp_dir = tempdir().unwrap();
+        let game_path = tmp_dir.path();
+        create_dir(game_path.join("Data")).unwrap();
+
+        let result =
+            GameSettings::with_local_path_validated(GameId::Skyrim, game_path, &PathBuf::default());
+
+        match result.unwrap_err() {
+            Error::GameNotInstalled(ref x) => {
+                assert_eq!(&game_path.join("Data").join("Skyrim.esm"), x)
+            }
+            e => panic!("Wrong error type: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn archive_extension_should_be_ba2_for_fallout_4_and_bsa_for_other_games() {
+        let archive_extension = |game_id| {
+            GameSettings::with_local_path(game_id, &PathBuf::default(), &PathBuf::default())
+                .unwrap()
+                .archive_extension()
+        };
+
+        assert_eq!(".bsa", archive_extension(GameId::Morrowind));
+        assert_eq!(".bsa", archive_extension(GameId::Oblivion));
+        assert_eq!(".bsa", archive_extension(GameId::Skyrim));
+        assert_eq!(".bsa", archive_extension(GameId::SkyrimSE));
+        assert_eq!(".bsa", archive_extension(GameId::SkyrimVR));
+        assert_eq!(".bsa", archive_extension(GameId::Fallout3));
+        assert_eq!(".bsa", archive_extension(GameId::FalloutNV));
+        assert_eq!(".ba2", archive_extension(GameId::Fallout4));
+        assert_eq!(".ba2", archive_extension(GameId::Fallout4VR));
+    }
+
+    #[test]
+    fn line_ending_should_be_crlf_for_morrowind_and_lf_for_other_games() {
+        let line_ending = |game_id| {
+            GameSettings::with_local_path(game_id, &PathBuf::default(), &PathBuf::default())
+                .unwrap()
+                .line_ending()
+        };
+
+        assert_eq!(LineEnding::CrLf, line_ending(GameId::Morrowind));
+        assert_eq!(LineEnding::Lf, line_ending(GameId::Oblivion));
+        assert_eq!(LineEnding::Lf, line_ending(GameId::Skyrim));
+        assert_eq!(LineEnding::Lf, line_ending(GameId::SkyrimSE));
+        assert_eq!(LineEnding::Lf, line_ending(GameId::SkyrimVR));
+        assert_eq!(LineEnding::Lf, line_ending(GameId::Fallout3));
+        assert_eq!(LineEnding::Lf, line_ending(GameId::FalloutNV));
+        assert_eq!(LineEnding::Lf, line_ending(GameId::Fallout4));
+        assert_eq!(LineEnding::Lf, line_ending(GameId::Fallout4VR));
+    }
+
+    #[test]
+    fn plugin_path_should_use_the_last_additional_plugins_directory_containing_the_plugin() {
+        let tmp_dir = tempdir().unwrap();
+        let mut settings =
+            GameSettings::with_local_path(GameId::Oblivion, tmp_dir.path(), &PathBuf::default())
+                .unwrap();
+
+        let directories = vec![tmp_dir.path().join("mod1"), tmp_dir.path().join("mod2")];
+        for directory in &directories {
+            create_dir(directory).unwrap();
+            File::create(directory.join("Blank.esp")).unwrap();
+        }
+        settings.set_additional_plugins_directories(directories.clone());
+
+        assert_eq!(directories[1].join("Blank.esp"), settings.plugin_path("Blank.esp"));
+        assert_eq!(
+            settings.plugins_directory().join("Blank.esm"),
+            settings.plugin_path("Blank.esm")
+        );
+    }
+
+    #[test]
+    fn plugin_path_should_match_filenames_in_additional_directories_case_insensitively() {
+        let tmp_dir = tempdir().unwrap();
+        let mut settings =
+            GameSettings::with_local_path(GameId::Oblivion, tmp_dir.path(), &PathBuf::default())
+                .unwrap();
+
+        let directory = tmp_dir.path().join("mod1");
+        create_dir(&directory).unwrap();
+        File::create(directory.join("blank.esp")).unwrap();
+        File::create(directory.join("blank.esm.ghost")).unwrap();
+        settings.set_additional_plugins_directories(vec![directory.clone()]);
+
+        assert_eq!(directory.join("blank.esp"), settings.plugin_path("Blank.esp"));
+        assert_eq!(directory.join("blank.esm.ghost"), settings.plugin_path("Blank.esm"));
+    }
+
+    #[test]
+    fn plugin_path_should_only_find_plugins_added_to_additional_directories_after_a_refresh() {
+        let tmp_dir = tempdir().unwrap();
+        let mut settings =
+            GameSettings::with_local_path(GameId::Oblivion, tmp_dir.path(), &PathBuf::default())
+                .unwrap();
+
+        let directory = tmp_dir.path().join("mod1");
+        create_dir(&directory).unwrap();
+        settings.set_additional_plugins_directories(vec![directory.clone()]);
+        File::create(directory.join("blank.esp")).unwrap();
+
+        assert_eq!(
+            settings.plugins_directory().join("Blank.esp"),
+            settings.plugin_path("Blank.esp")
+        );
+
+        settings.refresh_additional_plugins_directories();
+        assert_eq!(directory.join("blank.esp"), settings.plugin_path("Blank.esp"));
+    }
+
+    #[test]
+    fn game_version_should_be_none_if_the_game_executable_does_not_exist() {
+        let settings =
+            GameSettings::with_local_path(GameId::SkyrimSE, Path::new("game"), &PathBuf::default())
+                .unwrap();
+
+        assert!(settings.game_version().is_none());
+    }
+
+    #[test]
+    fn game_version_should_be_read_from_the_game_executable() {
+        let tmp_dir = tempdir().unwrap();
+        let game_path = tmp_dir.path();
+
+        let mut content = vec![0; 32];
+        content.extend(&[0xBD, 0x04, 0xEF, 0xFE, 0x00, 0x00, 0x01, 0x00]);
+        content.extend(&[0x05, 0x00, 0x01, 0x00, 0x00, 0x00, 0x61, 0x00]);
+        content.extend(&[0; 32]);
+        File::create(game_path.join("SkyrimSE.exe"))
+            .unwrap()
+            .write_all(&content)
+            .unwrap();
+
+        let mut settings =
+            GameSettings::with_local_path(GameId::SkyrimSE, game_path, &PathBuf::default())
+                .unwrap();
+        settings.refresh_game_version();
+
+        assert_eq!(Some((1, 5, 97)), settings.game_version());
+    }
+
+    #[test]
+    fn game_version_should_not_be_read_until_it_is_refreshed() {
+        let tmp_dir = tempdir().unwrap();
+        let game_path = tmp_dir.path();
+
+        let mut content = vec![0; 32];
+        content.extend(&[0xBD, 0x04, 0xEF, 0xFE, 0x00, 0x00, 0x01, 0x00]);
+        content.extend(&[0x04, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00]);
+        content.extend(&[0; 32]);
+        File::create(game_path.join("SkyrimSE.exe"))
+            .unwrap()
+            .write_all(&content)
+            .unwrap();
+
+        let mut settings =
+            GameSettings::with_local_path(GameId::SkyrimSE, game_path, &PathBuf::default())
+                .unwrap();
+        assert_eq!(None, settings.game_version());
+        assert!(settings.supports_light_masters());
+
+        settings.refresh_game_version();
+        assert_eq!(Some((1, 4, 2)), settings.game_version());
+        assert!(!settings.supports_light_masters());
+    }
+
+    #[test]
+    fn plugin_extensions_should_only_include_esl_for_games_that_support_light_masters() {
+        let plugin_extensions = |game_id| {
+            GameSettings::with_local_path(game_id, &PathBuf::default(), &PathBuf::default())
+                .unwrap()
+                .plugin_extensions()
+        };
+
+        assert_eq!(&[".esp", ".esm"], plugin_extensions(GameId::Morrowind));
+        assert_eq!(&[".esp", ".esm"], plugin_extensions(GameId::Skyrim));
+        assert_eq!(&[".esp", ".esm", ".esl"], plugin_extensions(GameId::SkyrimSE));
+        assert_eq!(&[".esp", ".esm", ".esl"], plugin_extensions(GameId::Fallout4));
+    }
+
+    #[test]
+    fn is_valid_plugin_name_should_check_the_extension_for_the_game() {
+        let settings = |game_id| {
+            GameSettings::with_local_path(game_id, &PathBuf::default(), &PathBuf::default())
+                .unwrap()
+        };
+
+        let skyrim = settings(GameId::Skyrim);
+        assert!(skyrim.is_valid_plugin_name("Blank.esp"));
+        assert!(skyrim.is_valid_plugin_name("Blank.ESM"));
+        assert!(skyrim.is_valid_plugin_name("Blank.esm.ghost"));
+        assert!(!skyrim.is_valid_plugin_name("Blank.esl"));
+        assert!(!skyrim.is_valid_plugin_name("Blank.bsa"));
+
+        let skyrim_se = settings(GameId::SkyrimSE);
+        assert!(skyrim_se.is_valid_plugin_name("Blank.esl"));
+        assert!(skyrim_se.is_valid_plugin_name("Blank.esl.ghost"));
+        assert!(!skyrim_se.is_valid_plugin_name("Blank.txt"));
+    }
+
+    #[test]
+    fn is_valid_plugin_name_should_reject_path_separators_and_control_characters() {
+        let settings = GameSettings::with_local_path(
+            GameId::Oblivion,
+            &PathBuf::default(),
+            &PathBuf::default(),
+        ).unwrap();
+
+        assert!(!settings.is_valid_plugin_name("../Blank.esp"));
+        assert!(!settings.is_valid_plugin_name("Data\\Blank.esp"));
+        assert!(!settings.is_valid_plugin_name("Bla\nnk.esp"));
+        assert!(settings.is_valid_plugin_name("Blàñk.esp"));
+    }
+
+    #[test]
+    fn supports_light_masters_should_be_false_for_skyrim_se_before_1_5() {
+        let mut settings =
+            GameSettings::with_local_path(GameId::SkyrimSE, Path::new("game"), &PathBuf::default())
+                .unwrap();
+        assert!(settings.supports_light_masters());
+
+        settings.set_game_version(Some((1, 4, 2)));
+        assert!(!settings.supports_light_masters());
+
+        settings.set_game_version(Some((1, 5, 3)));
+        assert!(settings.supports_light_masters());
+    }
+
     #[test]
     fn plugins_folder_should_be_a_child_of_the_game_path() {
         let settings =
@@ -831,6 +1807,14 @@ mod tests {
         assert_eq!(Path::new("game/Data"), settings.plugins_directory());
     }
 
+    #[test]
+    fn plugins_directory_path_should_borrow_the_plugins_directory() {
+        let settings =
+            GameSettings::with_local_path(GameId::Skyrim, Path::new("game"), &PathBuf::default())
+                .unwrap();
+        assert_eq!(settings.plugins_directory(), settings.plugins_directory_path());
+    }
+
     #[test]
     fn load_order_file_should_be_in_local_path_for_skyrim_and_none_for_other_games() {
         let mut settings =
@@ -35,8 +35,21 @@ mod load_order;
 mod plugin;
 #[cfg(test)]
 mod tests;
+mod version;
 
-pub use enums::{Error, GameId, LoadOrderMethod};
+pub use enums::{
+    Distribution, Error, GameId, LineEnding, LoadOrderMethod, PluginStatus, PluginTier,
+    TimestampSpacing, TimestampTiebreak, ValidationWarning,
+};
 pub use game_settings::GameSettings;
+pub use load_order::ActiveCountStatus;
+pub use load_order::ActivePluginsFileLock;
+pub use load_order::ActiveStateToken;
+pub use load_order::LoadOrderEntry;
+pub use load_order::MemoryLoadOrder;
 pub use load_order::ReadableLoadOrder;
+pub use load_order::StateFingerprint;
+pub use load_order::TimestampBasedLoadOrder;
 pub use load_order::WritableLoadOrder;
+pub use plugin::Plugin;
+pub use version::{is_compatible, version};
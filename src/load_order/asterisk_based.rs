@@ -16,22 +16,43 @@
  * You should have received a copy of the GNU General Public License
  * along with libloadorder. If not, see <http://www.gnu.org/licenses/>.
  */
-use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::collections::HashSet;
+use std::io::Write;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
 
-use encoding::all::WINDOWS_1252;
-use encoding::{EncoderTrap, Encoding};
 use unicase::eq;
 
 use super::insertable::InsertableLoadOrder;
-use super::mutable::{read_plugin_names, MutableLoadOrder};
+use super::lock::{lock_for_save, ActivePluginsFileLock};
+use super::mutable::{read_active_plugins_limit, read_plugin_names, MutableLoadOrder};
 use super::readable::{
-    active_plugin_names, index_of, is_active, plugin_at, plugin_names, ReadableLoadOrder,
-    ReadableLoadOrderExt,
+    active_count_status, active_index_of, active_master_names, active_non_master_names,
+    active_plugin_names, content_hash, duplicate_content_plugins, effective_load_order,
+    excess_active_plugins, flat_entries, form_version, group_by_archive, index_of, is_active,
+    is_blueprint_master, is_master_plugin, is_ordering_valid, is_override_only, light_count,
+    master_count, master_dependency_cycles, master_file_present, master_indices,
+    mismatched_extension_plugins, missing_implicit_plugins, move_delta, non_master_count,
+    orphaned_plugins, plugin_at, plugin_has_associated_archive, plugin_names, plugin_names_reversed,
+    plugin_status, plugin_tier, read_active_plugins_file_raw, record_types, remaining_slots, report,
+    valid_index_range, validate, write_mo2_modlist, ActiveCountStatus, LoadOrderEntry,
+    ReadableLoadOrder, ReadableLoadOrderExt,
 };
-use super::writable::{activate, deactivate, set_active_plugins, WritableLoadOrder};
-use super::{create_parent_dirs, find_first_non_master_position};
-use enums::Error;
+use super::writable::{
+    activate, activate_many, activate_with_masters, active_plugins_file_content,
+    active_plugins_file_text, active_plugins_file_would_change, apply_loot_order,
+    apply_order_from_index, changed_plugins_since_load, clear, deactivate, deactivate_temporarily,
+    is_in_subset, is_locked, load_and_report_unghosted_plugins, lock_plugin, merge_new_plugins,
+    move_to_tier_end, move_to_tier_start, reorder_masters, restore_active_state, set_active_plugins,
+    set_active_plugins_prioritized, set_active_range, set_complete_load_order,
+    set_load_order_appending_rest, stale_active_entries, state_fingerprint, unlock_plugin,
+    validate_active_plugin_counts, validate_active_plugins_exist,
+    validate_hardcoded_first_plugin_index, verify_active_file_matches, ActiveStateToken,
+    StateFingerprint, WritableLoadOrder,
+};
+use super::{create_file, ensure_writable, find_first_non_master_position};
+use enums::{Error, PluginStatus, PluginTier, ValidationWarning};
 use game_settings::GameSettings;
 use plugin::Plugin;
 
@@ -39,6 +60,9 @@ use plugin::Plugin;
 pub struct AsteriskBasedLoadOrder {
     game_settings: GameSettings,
     plugins: Vec<Plugin>,
+    locked_plugins: Vec<String>,
+    subset_loaded: bool,
+    held_lock: Option<Arc<ActivePluginsFileLock>>,
 }
 
 impl AsteriskBasedLoadOrder {
@@ -46,6 +70,9 @@ impl AsteriskBasedLoadOrder {
         Self {
             game_settings,
             plugins: Vec::new(),
+            locked_plugins: Vec::new(),
+            subset_loaded: false,
+            held_lock: None,
         }
     }
 }
@@ -55,14 +82,38 @@ impl ReadableLoadOrder for AsteriskBasedLoadOrder {
         &self.game_settings
     }
 
+    fn plugins_directory(&self) -> &Path {
+        self.game_settings().plugins_directory_path()
+    }
+
     fn plugin_names(&self) -> Vec<&str> {
         plugin_names(self.plugins())
     }
 
+    fn plugins_slice(&self) -> &[Plugin] {
+        self.plugins()
+    }
+
+    fn plugin_names_reversed(&self) -> Vec<&str> {
+        plugin_names_reversed(self.plugins())
+    }
+
+    fn effective_load_order(&self) -> Vec<String> {
+        effective_load_order(self.plugins(), self.game_settings())
+    }
+
     fn index_of(&self, plugin_name: &str) -> Option<usize> {
         index_of(self.plugins(), plugin_name)
     }
 
+    fn active_index_of(&self, plugin_name: &str) -> Option<usize> {
+        active_index_of(self.plugins(), plugin_name)
+    }
+
+    fn move_delta(&self, plugin_name: &str, target: usize) -> Option<isize> {
+        move_delta(&self.plugins, plugin_name, target)
+    }
+
     fn plugin_at(&self, index: usize) -> Option<&str> {
         plugin_at(self.plugins(), index)
     }
@@ -71,9 +122,155 @@ impl ReadableLoadOrder for AsteriskBasedLoadOrder {
         active_plugin_names(self.plugins())
     }
 
+    fn active_master_names(&self) -> Vec<&str> {
+        active_master_names(self.plugins())
+    }
+
+    fn active_non_master_names(&self) -> Vec<&str> {
+        active_non_master_names(self.plugins())
+    }
+
+    fn master_count(&self) -> usize {
+        master_count(self.plugins())
+    }
+
+    fn non_master_count(&self) -> usize {
+        non_master_count(self.plugins())
+    }
+
+    fn light_count(&self) -> usize {
+        light_count(self.plugins())
+    }
+
+    fn active_count_status(&self) -> ActiveCountStatus {
+        active_count_status(self.plugins(), self.game_settings())
+    }
+
+    fn remaining_slots(&self) -> (usize, usize) {
+        remaining_slots(self.plugins(), self.game_settings())
+    }
+
+    fn active_plugins_as_written(&self) -> Vec<String> {
+        self.plugins()
+            .iter()
+            .filter(|p| !self.game_settings().is_implicitly_active(p.name()))
+            .map(|p| {
+                if p.is_active() {
+                    format!("*{}", p.name())
+                } else {
+                    p.name().to_string()
+                }
+            })
+            .collect()
+    }
+
+    fn active_plugins_file_content(&self) -> Result<String, Error> {
+        active_plugins_file_text(self, Vec::new())
+    }
+
     fn is_active(&self, plugin_name: &str) -> bool {
         is_active(self.plugins(), plugin_name)
     }
+
+    fn plugin_status(&self, plugin_name: &str) -> PluginStatus {
+        plugin_status(self.plugins(), self.game_settings(), plugin_name)
+    }
+
+    fn plugin_tier(&self, plugin_name: &str) -> Option<PluginTier> {
+        plugin_tier(self.plugins(), self.game_settings(), plugin_name)
+    }
+
+    fn flat_entries(&self) -> Vec<LoadOrderEntry<'_>> {
+        flat_entries(self.plugins())
+    }
+
+    fn excess_active_plugins(&self) -> Vec<&str> {
+        excess_active_plugins(self.plugins(), self.game_settings())
+    }
+
+    fn master_file_present(&self) -> bool {
+        master_file_present(self.plugins(), self.game_settings().master_file())
+    }
+
+    fn valid_index_range(&self, plugin_name: &str) -> Result<Range<usize>, Error> {
+        valid_index_range(self.plugins(), plugin_name, self.game_settings())
+    }
+
+    fn is_override_only(&self, plugin_name: &str) -> Result<bool, Error> {
+        is_override_only(self.plugins(), plugin_name)
+    }
+
+    fn master_indices(&self, plugin_name: &str) -> Result<Vec<(String, Option<usize>)>, Error> {
+        master_indices(self.plugins(), plugin_name)
+    }
+
+    fn master_dependency_cycles(&self) -> Vec<Vec<String>> {
+        master_dependency_cycles(self.plugins())
+    }
+
+    fn orphaned_plugins(&self) -> Result<Vec<String>, Error> {
+        orphaned_plugins(self.plugins())
+    }
+
+    fn mismatched_extension_plugins(&self) -> Vec<String> {
+        mismatched_extension_plugins(self.plugins())
+    }
+
+    fn is_ordering_valid(&self) -> bool {
+        is_ordering_valid(self.plugins())
+    }
+
+    fn plugin_has_associated_archive(&self, plugin_name: &str) -> bool {
+        plugin_has_associated_archive(plugin_name, self.game_settings())
+    }
+
+    fn content_hash(&self) -> u64 {
+        content_hash(self.plugins())
+    }
+
+    fn group_by_archive(&self) -> Vec<(String, Vec<String>)> {
+        group_by_archive(self.plugins(), self.game_settings())
+    }
+
+    fn duplicate_content_plugins(&self) -> Result<Vec<Vec<String>>, Error> {
+        duplicate_content_plugins(&self.plugins)
+    }
+
+    fn read_active_plugins_file_raw(&self) -> Result<Vec<u8>, Error> {
+        read_active_plugins_file_raw(self.game_settings())
+    }
+
+    fn write_mo2_modlist(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        write_mo2_modlist(&self.plugins, writer)
+    }
+
+    fn is_master_plugin(&self, plugin_name: &str) -> Option<bool> {
+        is_master_plugin(&self.plugins, self.game_settings(), plugin_name)
+    }
+
+    fn form_version(&self, plugin_name: &str) -> Option<u16> {
+        form_version(&self.plugins, plugin_name)
+    }
+
+    fn is_blueprint_master(&self, plugin_name: &str) -> Option<bool> {
+        is_blueprint_master(self.plugins(), plugin_name)
+    }
+
+    fn record_types(&self, plugin_name: &str) -> Result<HashSet<[u8; 4]>, Error> {
+        record_types(self.plugins(), plugin_name)
+    }
+
+    fn validate(&self) -> Vec<ValidationWarning> {
+        validate(&self.plugins, self.game_settings().id())
+    }
+
+    fn missing_implicit_plugins(&self) -> Vec<String> {
+        missing_implicit_plugins(self.game_settings())
+    }
+
+    fn report(&self) -> String {
+        report(self.plugins(), self.game_settings())
+    }
 }
 
 impl ReadableLoadOrderExt for AsteriskBasedLoadOrder {
@@ -86,6 +283,14 @@ impl MutableLoadOrder for AsteriskBasedLoadOrder {
     fn plugins_mut(&mut self) -> &mut Vec<Plugin> {
         &mut self.plugins
     }
+
+    fn locked_plugins(&self) -> &[String] {
+        &self.locked_plugins
+    }
+
+    fn locked_plugins_mut(&mut self) -> &mut Vec<String> {
+        &mut self.locked_plugins
+    }
 }
 
 impl InsertableLoadOrder for AsteriskBasedLoadOrder {
@@ -118,43 +323,66 @@ impl InsertableLoadOrder for AsteriskBasedLoadOrder {
 }
 
 impl WritableLoadOrder for AsteriskBasedLoadOrder {
-    fn load(&mut self) -> Result<(), Error> {
-        self.plugins_mut().clear();
+    fn load_and_report_excess_plugins_with_progress(
+        &mut self,
+        progress: &(dyn Fn(usize, usize) + Sync),
+    ) -> Result<Vec<String>, Error> {
+        self.load_filtered(|_| true, progress)
+    }
 
-        let plugin_tuples = self.read_from_active_plugins_file()?;
-        let filenames = self.find_plugins_in_dir_sorted();
+    fn load_and_report_unghosted_plugins(&mut self) -> Result<Vec<String>, Error> {
+        load_and_report_unghosted_plugins(self)
+    }
 
-        self.load_unique_plugins(plugin_tuples, filenames);
+    fn load_subset(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        let game_settings = self.game_settings().clone();
+        self.load_filtered(
+            |filename| is_in_subset(&game_settings, plugin_names, filename),
+            &|_, _| {},
+        )?;
+        self.subset_loaded = true;
 
-        self.add_implicitly_active_plugins()?;
+        Ok(())
+    }
+
+    fn save(&mut self) -> Result<(), Error> {
+        ensure_writable(self.game_settings())?;
+        if self.subset_loaded {
+            return Err(Error::SubsetLoaded);
+        }
+        let _lock = lock_for_save(self.game_settings(), self.held_lock.as_deref())?;
 
-        self.deactivate_excess_plugins();
+        validate_active_plugin_counts(self)?;
+        validate_active_plugins_exist(self)?;
+
+        let content = active_plugins_file_content(self, Vec::new())?;
+        create_file(self.game_settings().active_plugins_file())?.write_all(&content)?;
 
         Ok(())
     }
 
-    fn save(&mut self) -> Result<(), Error> {
-        create_parent_dirs(self.game_settings().active_plugins_file())?;
+    fn save_load_order_only(&mut self) -> Result<(), Error> {
+        self.save()
+    }
 
-        let file = File::create(self.game_settings().active_plugins_file())?;
-        let mut writer = BufWriter::new(file);
-        for plugin in self.plugins() {
-            if self.game_settings().is_implicitly_active(plugin.name()) {
-                continue;
-            }
+    fn save_would_change(&self) -> Result<bool, Error> {
+        let content = active_plugins_file_content(self, Vec::new())?;
+        active_plugins_file_would_change(self.game_settings(), &content)
+    }
 
-            if plugin.is_active() {
-                write!(writer, "*")?;
-            }
-            writer.write_all(&WINDOWS_1252
-                .encode(plugin.name(), EncoderTrap::Strict)
-                .map_err(Error::EncodeError)?)?;
-            writeln!(writer)?;
+    fn lock_active_plugins_file(&mut self) -> Result<(), Error> {
+        if self.held_lock.is_none() {
+            let lock = ActivePluginsFileLock::acquire(self.game_settings())?;
+            self.held_lock = Some(Arc::new(lock));
         }
 
         Ok(())
     }
 
+    fn unlock_active_plugins_file(&mut self) {
+        self.held_lock = None;
+    }
+
     fn set_load_order(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
         if plugin_names.is_empty() || !eq(plugin_names[0], self.game_settings().master_file()) {
             return Err(Error::GameMasterMustLoadFirst);
@@ -179,20 +407,36 @@ impl WritableLoadOrder for AsteriskBasedLoadOrder {
         self.replace_plugins(plugin_names)
     }
 
+    fn set_complete_load_order(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        set_complete_load_order(self, plugin_names)
+    }
+
+    fn set_load_order_appending_rest(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        set_load_order_appending_rest(self, plugin_names)
+    }
+
+    fn apply_order_from_index(&mut self, start: usize, plugin_names: &[&str]) -> Result<(), Error> {
+        apply_order_from_index(self, start, plugin_names)
+    }
+
+    fn apply_loot_order(&mut self, sorted_plugin_names: &[&str]) -> Result<Vec<String>, Error> {
+        apply_loot_order(self, sorted_plugin_names)
+    }
+
     fn set_plugin_index(&mut self, plugin_name: &str, position: usize) -> Result<(), Error> {
-        if position != 0
-            && !self.plugins().is_empty()
-            && eq(plugin_name, self.game_settings().master_file())
-        {
-            return Err(Error::GameMasterMustLoadFirst);
-        }
-        if position == 0 && !eq(plugin_name, self.game_settings().master_file()) {
-            return Err(Error::GameMasterMustLoadFirst);
-        }
+        validate_hardcoded_first_plugin_index(self, plugin_name, position)?;
 
         self.move_or_insert_plugin_with_index(plugin_name, position)
     }
 
+    fn move_to_tier_start(&mut self, plugin_name: &str) -> Result<(), Error> {
+        move_to_tier_start(self, plugin_name)
+    }
+
+    fn move_to_tier_end(&mut self, plugin_name: &str) -> Result<(), Error> {
+        move_to_tier_end(self, plugin_name)
+    }
+
     fn is_self_consistent(&self) -> Result<bool, Error> {
         Ok(true)
     }
@@ -201,16 +445,125 @@ impl WritableLoadOrder for AsteriskBasedLoadOrder {
         activate(self, plugin_name)
     }
 
+    fn activate_many(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        activate_many(self, plugin_names)
+    }
+
+    fn activate_with_masters(&mut self, plugin_name: &str) -> Result<Vec<String>, Error> {
+        activate_with_masters(self, plugin_name)
+    }
+
     fn deactivate(&mut self, plugin_name: &str) -> Result<(), Error> {
         deactivate(self, plugin_name)
     }
 
+    fn deactivate_temporarily(&mut self, plugin_names: &[&str]) -> Result<ActiveStateToken, Error> {
+        deactivate_temporarily(self, plugin_names)
+    }
+
+    fn restore_active_state(&mut self, token: ActiveStateToken) -> Result<(), Error> {
+        restore_active_state(self, token)
+    }
+
+    fn set_active_range(
+        &mut self,
+        start: usize,
+        end: usize,
+        active: bool,
+    ) -> Result<Vec<String>, Error> {
+        set_active_range(self, start, end, active)
+    }
+
     fn set_active_plugins(&mut self, active_plugin_names: &[&str]) -> Result<(), Error> {
         set_active_plugins(self, active_plugin_names)
     }
+
+    fn set_active_plugins_prioritized(
+        &mut self,
+        active_plugin_names: &[&str],
+    ) -> Result<Vec<String>, Error> {
+        set_active_plugins_prioritized(self, active_plugin_names)
+    }
+
+    fn reorder_masters(&mut self, master_order: &[&str]) -> Result<(), Error> {
+        reorder_masters(self, master_order)
+    }
+
+    fn changed_plugins_since_load(&self) -> Result<Vec<String>, Error> {
+        changed_plugins_since_load(self)
+    }
+
+    fn refresh_implicitly_active(&mut self) -> Result<(), Error> {
+        self.game_settings.refresh_implicitly_active_plugins()?;
+
+        self.add_implicitly_active_plugins()
+    }
+
+    fn state_fingerprint(&self) -> Result<StateFingerprint, Error> {
+        state_fingerprint(self)
+    }
+
+    fn lock_plugin(&mut self, plugin_name: &str) {
+        lock_plugin(self, plugin_name)
+    }
+
+    fn unlock_plugin(&mut self, plugin_name: &str) {
+        unlock_plugin(self, plugin_name)
+    }
+
+    fn is_locked(&self, plugin_name: &str) -> bool {
+        is_locked(self, plugin_name)
+    }
+
+    fn merge_new_plugins(&mut self) -> Result<Vec<String>, Error> {
+        merge_new_plugins(self)
+    }
+
+    fn clear(&mut self) {
+        clear(self)
+    }
+
+    fn stale_active_entries(&self) -> Result<Vec<String>, Error> {
+        stale_active_entries(self, |line| match plugin_line_mapper(line) {
+            Some((name, true)) => Some(name),
+            _ => None,
+        })
+    }
+
+    fn verify_active_file_matches(&self) -> Result<bool, Error> {
+        verify_active_file_matches(self, |line| match plugin_line_mapper(line) {
+            Some((name, true)) => Some(name),
+            _ => None,
+        })
+    }
 }
 
 impl AsteriskBasedLoadOrder {
+    fn load_filtered<F: Fn(&str) -> bool>(
+        &mut self,
+        filter: F,
+        progress: &(dyn Fn(usize, usize) + Sync),
+    ) -> Result<Vec<String>, Error> {
+        self.plugins_mut().clear();
+        self.subset_loaded = false;
+        self.game_settings.refresh_additional_plugins_directories();
+
+        let mut plugin_tuples = self.read_from_active_plugins_file()?;
+        plugin_tuples.retain(|(name, _)| filter(name));
+        let mut filenames = self.find_plugins_in_dir_sorted()?;
+        filenames.retain(|filename| filter(filename));
+
+        self.load_unique_plugins(plugin_tuples, filenames, progress);
+
+        self.add_implicitly_active_plugins()?;
+
+        let limit = read_active_plugins_limit(self.game_settings().active_plugins_file())?;
+        self.game_settings.set_active_plugins_limit(limit);
+
+        let max_active_normal_plugins = self.game_settings().max_active_normal_plugins();
+        Ok(self.deactivate_excess_plugins(max_active_normal_plugins))
+    }
+
     fn read_from_active_plugins_file(&self) -> Result<Vec<(String, bool)>, Error> {
         read_plugin_names(
             self.game_settings().active_plugins_file(),
@@ -236,18 +589,22 @@ mod tests {
     use enums::GameId;
     use filetime::{set_file_times, FileTime};
     use load_order::tests::*;
-    use std::fs::{remove_dir_all, File};
+    use load_order::ActivePluginsFileLock;
+    use std::fs::{metadata, remove_dir_all, remove_file, rename, File};
     use std::io;
     use std::io::{BufRead, BufReader};
     use std::path::Path;
     use tempfile::tempdir;
-    use tests::copy_to_test_dir;
+    use tests::{copy_to_test_dir, write_plugin};
 
     fn prepare(game_id: GameId, game_dir: &Path) -> AsteriskBasedLoadOrder {
         let (game_settings, plugins) = mock_game_files(game_id, game_dir);
         AsteriskBasedLoadOrder {
             game_settings,
             plugins,
+            locked_plugins: Vec::new(),
+            subset_loaded: false,
+            held_lock: None,
         }
     }
 
@@ -623,6 +980,52 @@ mod tests {
         assert_eq!(plugins[261..4357], active_plugin_names[255..]);
     }
 
+    #[test]
+    fn load_should_not_count_light_flagged_esp_files_against_the_normal_plugin_limit() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        let mut plugins: Vec<String> = vec![load_order.game_settings().master_file().to_string()];
+        for i in 0..254 {
+            plugins.push(format!("Blank{}.esm", i));
+            copy_to_test_dir("Blank - Different.esm", &plugins[i + 1], load_order.game_settings());
+        }
+        for i in 0..10 {
+            plugins.push(format!("Light{}.esp", i));
+            copy_to_test_dir("Blank.esl", &plugins[i + 255], load_order.game_settings());
+        }
+        plugins.push("Extra.esp".to_string());
+        copy_to_test_dir("Blank.esp", "Extra.esp", load_order.game_settings());
+
+        write_active_plugins_file(load_order.game_settings(), &plugins);
+
+        let deactivated = load_order.load_and_report_excess_plugins().unwrap();
+
+        assert_eq!(vec!["Extra.esp".to_string()], deactivated);
+        for i in 0..10 {
+            assert!(load_order.is_active(&format!("Light{}.esp", i)));
+        }
+        assert_eq!(plugins[..265], load_order.active_plugin_names()[..]);
+    }
+
+    #[test]
+    fn plugins_slice_should_give_access_to_the_parsed_plugins_in_load_order() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        load_order.load().unwrap();
+
+        let plugins = load_order.plugins_slice();
+        assert_eq!(load_order.plugin_names().len(), plugins.len());
+
+        let masters: Vec<&str> = plugins
+            .iter()
+            .filter(|p| p.is_master_file())
+            .map(|p| p.name())
+            .collect();
+        assert_eq!(vec!["Skyrim.esm", "Blank.esm"], masters);
+    }
+
     #[test]
     fn load_should_not_duplicate_a_plugin_that_has_a_ghosted_duplicate() {
         let tmp_dir = tempdir().unwrap();
@@ -677,6 +1080,42 @@ mod tests {
         assert_eq!(expected_filenames, load_order.plugin_names());
     }
 
+    #[test]
+    fn refresh_implicitly_active_should_activate_a_newly_installed_dlc_master() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        write_active_plugins_file(load_order.game_settings(), &["Blank.esp"]);
+        load_order.load().unwrap();
+
+        copy_to_test_dir("Blank.esm", "Dawnguard.esm", load_order.game_settings());
+        load_order.refresh_implicitly_active().unwrap();
+
+        assert!(load_order.is_active("Dawnguard.esm"));
+        assert_eq!(Some(1), load_order.index_of("Dawnguard.esm"));
+        assert!(load_order.is_active("Blank.esp"));
+    }
+
+    #[test]
+    fn refresh_implicitly_active_should_reread_the_ccc_file() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        load_order.load().unwrap();
+        let existing_filenames = to_owned(load_order.plugin_names());
+        assert!(!load_order.is_active("Blank.esm"));
+
+        {
+            let mut file = File::create(tmp_dir.path().join("Skyrim.ccc")).unwrap();
+            writeln!(file, "Blank.esm").unwrap();
+        }
+        load_order.refresh_implicitly_active().unwrap();
+
+        assert!(load_order.is_active("Blank.esm"));
+        assert!(load_order.is_active("Skyrim.esm"));
+        assert_eq!(existing_filenames, load_order.plugin_names());
+    }
+
     #[test]
     fn save_should_create_active_plugins_file_parent_directory_if_it_does_not_exist() {
         let tmp_dir = tempdir().unwrap();
@@ -702,6 +1141,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn save_should_error_without_writing_if_the_game_settings_are_read_only() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        let active_plugins_file = load_order.game_settings().active_plugins_file().clone();
+        if active_plugins_file.exists() {
+            remove_file(&active_plugins_file).unwrap();
+        }
+        load_order.game_settings.set_read_only(true);
+
+        load_order.load().unwrap();
+        load_order.activate("Blank - Different.esp").unwrap();
+
+        match load_order.save().unwrap_err() {
+            Error::ReadOnly => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert!(!active_plugins_file.exists());
+    }
+
+    #[test]
+    fn activate_should_not_unghost_a_plugin_if_the_game_settings_are_read_only() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        let plugins_dir = load_order.game_settings().plugins_directory().to_path_buf();
+        rename(
+            plugins_dir.join("Blank - Different.esp"),
+            plugins_dir.join("Blank - Different.esp.ghost"),
+        ).unwrap();
+        let modification_time = metadata(plugins_dir.join("Blank - Different.esp.ghost"))
+            .unwrap()
+            .modified()
+            .unwrap();
+
+        load_order.game_settings.set_read_only(true);
+        load_order.load().unwrap();
+        load_order.activate("Blank - Different.esp").unwrap();
+
+        assert!(load_order.is_active("Blank - Different.esp"));
+        assert!(!plugins_dir.join("Blank - Different.esp").exists());
+        let ghosted_path = plugins_dir.join("Blank - Different.esp.ghost");
+        assert_eq!(
+            modification_time,
+            metadata(&ghosted_path).unwrap().modified().unwrap()
+        );
+    }
+
     #[test]
     fn save_should_write_active_plugins_file() {
         let tmp_dir = tempdir().unwrap();
@@ -716,6 +1204,242 @@ mod tests {
         );
     }
 
+    #[test]
+    fn save_should_error_without_writing_if_locking_is_enabled_and_the_lock_is_held() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+        load_order.game_settings.set_lock_on_save(true);
+
+        let lock_path = tmp_dir.path().join("local/plugins.txt.lock");
+        File::create(&lock_path).unwrap();
+
+        let active_plugins_file = load_order.game_settings().active_plugins_file().clone();
+        assert!(!active_plugins_file.exists());
+
+        match load_order.save().unwrap_err() {
+            Error::LoadOrderLocked(_) => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert!(!active_plugins_file.exists());
+
+        remove_file(&lock_path).unwrap();
+        load_order.save().unwrap();
+        assert!(active_plugins_file.exists());
+        assert!(!lock_path.exists());
+    }
+
+    #[test]
+    fn save_should_ignore_a_held_lock_if_locking_is_not_enabled() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        let lock_path = tmp_dir.path().join("local/plugins.txt.lock");
+        File::create(&lock_path).unwrap();
+
+        load_order.save().unwrap();
+
+        assert!(load_order.game_settings().active_plugins_file().exists());
+        assert!(lock_path.exists());
+    }
+
+    #[test]
+    fn save_should_succeed_while_the_load_order_holds_the_lock() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+        load_order.game_settings.set_lock_on_save(true);
+        let mut other_load_order = load_order.clone();
+
+        load_order.lock_active_plugins_file().unwrap();
+        load_order.save().unwrap();
+
+        let lock_path = tmp_dir.path().join("local/plugins.txt.lock");
+        assert!(load_order.game_settings().active_plugins_file().exists());
+        assert!(lock_path.exists());
+
+        match other_load_order.save().unwrap_err() {
+            Error::LoadOrderLocked(ref p) if p == &lock_path => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+
+        load_order.unlock_active_plugins_file();
+        assert!(!lock_path.exists());
+        assert!(other_load_order.save().is_ok());
+    }
+
+    #[test]
+    fn save_should_error_while_a_lock_that_the_load_order_does_not_hold_exists() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+        load_order.game_settings.set_lock_on_save(true);
+
+        let lock = ActivePluginsFileLock::acquire(load_order.game_settings()).unwrap();
+
+        match load_order.save().unwrap_err() {
+            Error::LoadOrderLocked(ref p) if p == lock.path() => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert!(!load_order.game_settings().active_plugins_file().exists());
+    }
+
+    #[test]
+    fn save_should_preserve_a_utf8_bom_in_the_existing_active_plugins_file() {
+        use std::fs::{read, write};
+
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        let active_plugins_file = load_order.game_settings().active_plugins_file().clone();
+        write(&active_plugins_file, "\u{feff}*Blank.esp\n").unwrap();
+
+        load_order.load().unwrap();
+        assert_eq!(
+            vec!["Skyrim.esm", "Blank.esp"],
+            load_order.active_plugin_names()
+        );
+
+        load_order.activate("Blàñk.esp").unwrap();
+        load_order.save().unwrap();
+
+        let content = String::from_utf8(read(&active_plugins_file).unwrap()).unwrap();
+        assert!(content.starts_with("\u{feff}"));
+        assert!(content.lines().any(|l| l == "*Blàñk.esp"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn save_should_write_through_an_active_plugins_file_symlink() {
+        use std::fs::{read_link, symlink_metadata};
+        use std::os::unix::fs::symlink;
+
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        let active_plugins_file = load_order.game_settings().active_plugins_file().clone();
+        let target = tmp_dir.path().join("profile").join("plugins.txt");
+        if active_plugins_file.exists() {
+            remove_file(&active_plugins_file).unwrap();
+        }
+        symlink(&target, &active_plugins_file).unwrap();
+
+        load_order.save().unwrap();
+
+        assert!(symlink_metadata(&active_plugins_file)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(target, read_link(&active_plugins_file).unwrap());
+        assert!(target.exists());
+
+        load_order.load().unwrap();
+        assert_eq!(
+            vec!["Skyrim.esm", "Blank.esp"],
+            load_order.active_plugin_names()
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn save_should_error_if_the_active_plugins_file_is_a_symlink_cycle() {
+        use std::os::unix::fs::symlink;
+
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        let active_plugins_file = load_order.game_settings().active_plugins_file().clone();
+        let other_link = tmp_dir.path().join("plugins.txt");
+        if active_plugins_file.exists() {
+            remove_file(&active_plugins_file).unwrap();
+        }
+        symlink(&other_link, &active_plugins_file).unwrap();
+        symlink(&active_plugins_file, &other_link).unwrap();
+
+        match load_order.save().unwrap_err() {
+            Error::IoError(_) => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn load_should_error_if_the_plugins_directory_cannot_be_read() {
+        use std::fs::{read_dir, set_permissions, Permissions};
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        let plugins_dir = load_order.game_settings().plugins_directory().to_path_buf();
+        set_permissions(&plugins_dir, Permissions::from_mode(0o000)).unwrap();
+
+        // Permissions aren't enforced for privileged users, so only check the
+        // result if the directory really is unreadable.
+        let is_readable = read_dir(&plugins_dir).is_ok();
+        let result = load_order.load();
+
+        set_permissions(&plugins_dir, Permissions::from_mode(0o755)).unwrap();
+
+        if !is_readable {
+            match result.unwrap_err() {
+                Error::PermissionDenied(path) => assert_eq!(plugins_dir, path),
+                e => panic!("Wrong error type: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn active_plugins_as_written_should_match_the_saved_active_plugins_file() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        load_order.save().unwrap();
+
+        let file = File::open(load_order.game_settings().active_plugins_file()).unwrap();
+        let lines: Vec<String> = BufReader::new(file).lines().map(|l| l.unwrap()).collect();
+
+        assert_eq!(vec!["*Blank.esp", "Blank - Different.esp"], lines);
+        assert_eq!(lines, load_order.active_plugins_as_written());
+    }
+
+    #[test]
+    fn save_should_error_if_an_active_plugin_was_deleted_since_loading() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        write_active_plugins_file(load_order.game_settings(), &["Blank.esp"]);
+        load_order.load().unwrap();
+        load_order.activate("Blank - Different.esp").unwrap();
+
+        let plugin_path = load_order
+            .game_settings()
+            .plugins_directory()
+            .join("Blank - Different.esp");
+        remove_file(&plugin_path).unwrap();
+
+        match load_order.save().unwrap_err() {
+            Error::InvalidPath(path) => assert_eq!(plugin_path, path),
+            e => panic!("Wrong error type: {:?}", e),
+        }
+
+        let file = File::open(load_order.game_settings().active_plugins_file()).unwrap();
+        let lines: Vec<String> = BufReader::new(file).lines().map(|l| l.unwrap()).collect();
+        assert_eq!(vec!["*Blank.esp"], lines);
+    }
+
+    #[test]
+    fn verify_active_file_matches_should_be_true_after_saving_and_false_after_an_edit() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        load_order.save().unwrap();
+        assert!(load_order.verify_active_file_matches().unwrap());
+
+        write_active_plugins_file(
+            load_order.game_settings(),
+            &["Blank.esp", "Blank - Different.esp"],
+        );
+        assert!(!load_order.verify_active_file_matches().unwrap());
+    }
+
     #[test]
     fn save_should_write_unghosted_plugin_names() {
         let tmp_dir = tempdir().unwrap();
@@ -899,6 +1623,42 @@ mod tests {
         assert_eq!(num_plugins + 1, load_order.plugins().len());
     }
 
+    #[test]
+    fn set_plugin_index_should_accept_every_index_in_the_valid_index_range() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        for plugin_name in &["Blank.esm", "Blank.esp", "Blank - Master Dependent.esp"] {
+            let range = load_order.valid_index_range(plugin_name).unwrap();
+            assert!(range.start > 0);
+
+            let existing_filenames = to_owned(load_order.plugin_names());
+            assert!(load_order.set_plugin_index(plugin_name, range.start - 1).is_err());
+            assert_eq!(existing_filenames, load_order.plugin_names());
+
+            for index in range.rev() {
+                load_order.set_plugin_index(plugin_name, index).unwrap();
+                assert!(load_order.is_ordering_valid());
+            }
+        }
+    }
+
+    #[test]
+    fn reorder_masters_should_error_with_the_name_of_a_listed_non_master() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        let existing_filenames = to_owned(load_order.plugin_names());
+        match load_order
+            .reorder_masters(&["Skyrim.esm", "Blank - Different.esp"])
+            .unwrap_err()
+        {
+            Error::InvalidPlugin(ref x) if x == "Blank - Different.esp" => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(existing_filenames, load_order.plugin_names());
+    }
+
     #[test]
     fn is_self_consistent_should_return_true() {
         let tmp_dir = tempdir().unwrap();
@@ -936,6 +1696,70 @@ mod tests {
         assert!(!load_order.is_active(&plugins[i]));
     }
 
+    #[test]
+    fn activate_many_should_activate_nothing_if_the_set_would_exceed_the_limit() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        let plugins = prepare_bulk_plugins(load_order.game_settings());
+
+        let plugin_refs: Vec<&str> = plugins[..254].iter().map(AsRef::as_ref).collect();
+        assert!(load_order.set_active_plugins(&plugin_refs).is_ok());
+
+        let names = [plugins[4356].as_str(), plugins[254].as_str(), plugins[256].as_str()];
+        match load_order.activate_many(&names).unwrap_err() {
+            Error::TooManyActivePlugins { .. } => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+
+        for name in &names {
+            assert!(!load_order.is_active(name));
+        }
+
+        assert!(load_order.activate_many(&names[..2]).is_ok());
+        assert!(load_order.is_active(names[0]));
+        assert!(load_order.is_active(names[1]));
+    }
+
+    #[test]
+    fn activate_with_masters_should_activate_the_plugin_and_its_masters() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        write_plugin(
+            "Dependent.esp",
+            &["Blank.esm", "Blank - Different.esp", "Missing.esm"],
+            &[],
+            load_order.game_settings(),
+        );
+
+        let activated = load_order.activate_with_masters("Dependent.esp").unwrap();
+
+        assert_eq!(
+            vec!["Dependent.esp", "Blank.esm", "Blank - Different.esp"],
+            activated
+        );
+        for plugin_name in &activated {
+            assert!(load_order.is_active(plugin_name));
+        }
+    }
+
+    #[test]
+    fn activate_many_should_activate_nothing_if_a_plugin_is_not_installed() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::SkyrimSE, tmp_dir.path());
+
+        match load_order
+            .activate_many(&["Blank - Different.esp", "missing.esp"])
+            .unwrap_err()
+        {
+            Error::InvalidPlugin(name) => assert_eq!("missing.esp", name),
+            e => panic!("Wrong error type: {:?}", e),
+        }
+
+        assert!(!load_order.is_active("Blank - Different.esp"));
+    }
+
     #[test]
     fn set_active_plugins_should_count_light_masters_and_normal_plugins_separately() {
         let tmp_dir = tempdir().unwrap();
@@ -17,9 +17,11 @@
  * along with libloadorder. If not, see <http://www.gnu.org/licenses/>.
  */
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use rayon::prelude::*;
 
+use super::find_first_non_master_position;
 use super::mutable::MutableLoadOrder;
 use enums::Error;
 use plugin::{trim_dot_ghost, Plugin};
@@ -40,17 +42,60 @@ pub trait InsertableLoadOrder: MutableLoadOrder {
         }
     }
 
+    /// Get the position at which the given plugin would be inserted without
+    /// moving any locked plugins, which may be after the last locked plugin.
+    /// This errors with `Error::PluginLocked` if a master would have to be
+    /// inserted after a locked non-master.
+    fn unlocked_insert_position(&self, plugin: &Plugin) -> Result<Option<usize>, Error> {
+        let position = self.insert_position(plugin);
+        let last_locked_index = self.locked_plugins()
+            .iter()
+            .filter_map(|p| self.index_of(p))
+            .max();
+
+        match (position, last_locked_index) {
+            (Some(position), Some(index)) if position <= index => {
+                let is_master = plugin.is_master_file() || plugin.is_light_master_file();
+                let first_non_master_position = find_first_non_master_position(self.plugins());
+                if is_master && first_non_master_position.is_some_and(|i| i <= index) {
+                    Err(Error::PluginLocked(self.plugins()[index].name().to_string()))
+                } else if index + 1 < self.plugins().len() {
+                    Ok(Some(index + 1))
+                } else {
+                    Ok(None)
+                }
+            }
+            _ => Ok(position),
+        }
+    }
+
+    /// Insert a plugin without moving any locked plugins.
+    fn insert_unlocked(&mut self, plugin: Plugin) -> Result<usize, Error> {
+        match self.unlocked_insert_position(&plugin)? {
+            Some(position) => {
+                self.plugins_mut().insert(position, plugin);
+                Ok(position)
+            }
+            None => {
+                self.plugins_mut().push(plugin);
+                Ok(self.plugins().len() - 1)
+            }
+        }
+    }
+
     fn add_to_load_order(&mut self, plugin_name: &str) -> Result<usize, Error> {
         let plugin = Plugin::new(plugin_name, self.game_settings())?;
 
-        Ok(self.insert(plugin))
+        self.insert_unlocked(plugin)
     }
 
     fn find_or_add(&mut self, plugin_name: &str) -> Result<usize, Error> {
         match self.index_of(plugin_name) {
             Some(i) => Ok(i),
-            None => self.add_to_load_order(plugin_name)
-                .map_err(|_| Error::InvalidPlugin(plugin_name.to_string())),
+            None => self.add_to_load_order(plugin_name).map_err(|e| match e {
+                Error::PluginLocked(x) => Error::PluginLocked(x),
+                _ => Error::InvalidPlugin(plugin_name.to_string()),
+            }),
         }
     }
 
@@ -58,14 +103,21 @@ pub trait InsertableLoadOrder: MutableLoadOrder {
         &mut self,
         plugin_name_tuples: Vec<(String, bool)>,
         installed_filenames: Vec<String>,
+        progress: &(dyn Fn(usize, usize) + Sync),
     ) {
         let plugins: Vec<Plugin> = {
             let game_settings = self.game_settings();
+            let plugin_name_tuples =
+                remove_duplicates_icase(plugin_name_tuples, installed_filenames);
+            let total = plugin_name_tuples.len();
+            let completed = AtomicUsize::new(0);
 
-            remove_duplicates_icase(plugin_name_tuples, installed_filenames)
+            plugin_name_tuples
                 .into_par_iter()
                 .filter_map(|(filename, active)| {
-                    Plugin::with_active(&filename, game_settings, active).ok()
+                    let plugin = Plugin::with_active(&filename, game_settings, active).ok();
+                    progress(completed.fetch_add(1, Ordering::SeqCst) + 1, total);
+                    plugin
                 })
                 .collect()
         };
@@ -87,6 +139,14 @@ pub trait InsertableLoadOrder: MutableLoadOrder {
             activate_unvalidated(self, &plugin_name)?;
         }
 
+        let hardcoded_first_index = self.game_settings()
+            .hardcoded_first_plugin()
+            .and_then(|p| self.index_of(p));
+        if let Some(index) = hardcoded_first_index {
+            let plugin = self.plugins_mut().remove(index);
+            self.plugins_mut().insert(0, plugin);
+        }
+
         Ok(())
     }
 }
@@ -122,7 +182,10 @@ fn activate_unvalidated<T: InsertableLoadOrder + ?Sized>(
     let index = {
         let index = load_order.index_of(filename);
         if index.is_none() && Plugin::is_valid(&filename, load_order.game_settings()) {
-            Some(load_order.add_to_load_order(filename)?)
+            // The game loads implicitly active plugins whatever the load order
+            // says, so they are inserted even if that moves locked plugins.
+            let plugin = Plugin::new(filename, load_order.game_settings())?;
+            Some(load_order.insert(plugin))
         } else {
             index
         }
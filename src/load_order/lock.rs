@@ -0,0 +1,206 @@
+/*
+ * This file is part of libloadorder
+ *
+ * Copyright (C) 2017 Oliver Hamlet
+ *
+ * libloadorder is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * libloadorder is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with libloadorder. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+use std::ffi::OsString;
+use std::fs::{remove_file, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::{create_parent_dirs, ensure_writable, resolve_symlinks};
+use enums::Error;
+use game_settings::GameSettings;
+
+/// An advisory lock on a game's active plugins file, held by creating a lock
+/// file next to it. The lock is released when it is dropped.
+///
+/// The lock only protects against other processes that also take it before
+/// writing the active plugins file, it doesn't stop the game or any other
+/// program from writing to the file. If a process exits without dropping its
+/// lock, the lock file is left behind and must be deleted.
+///
+/// A load order can hold the lock across loading and saving using
+/// `WritableLoadOrder::lock_active_plugins_file()`. A lock acquired directly
+/// isn't known to any load order, so saving one with locking enabled fails
+/// while it is held.
+#[derive(Debug)]
+pub struct ActivePluginsFileLock {
+    path: PathBuf,
+}
+
+impl ActivePluginsFileLock {
+    /// Take the lock, failing with `Error::LoadOrderLocked` if it is already
+    /// held, or with `Error::ReadOnly` if the game settings are read-only.
+    pub fn acquire(game_settings: &GameSettings) -> Result<Self, Error> {
+        ensure_writable(game_settings)?;
+
+        let path = lock_file_path(game_settings.active_plugins_file());
+        create_parent_dirs(&path)?;
+
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => Ok(ActivePluginsFileLock { path }),
+            Err(ref e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                Err(Error::LoadOrderLocked(path))
+            }
+            Err(e) => Err(Error::IoError(e)),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ActivePluginsFileLock {
+    fn drop(&mut self) {
+        remove_file(&self.path).ok();
+    }
+}
+
+/// Take the lock if the game settings say that saving should be locked,
+/// unless the given held lock is already the lock for the active plugins file.
+/// Existing lock files are ignored if locking isn't enabled.
+pub fn lock_for_save(
+    game_settings: &GameSettings,
+    held_lock: Option<&ActivePluginsFileLock>,
+) -> Result<Option<ActivePluginsFileLock>, Error> {
+    if !game_settings.lock_on_save() {
+        return Ok(None);
+    }
+
+    let path = lock_file_path(game_settings.active_plugins_file());
+    match held_lock {
+        Some(lock) if lock.path() == path => Ok(None),
+        _ => ActivePluginsFileLock::acquire(game_settings).map(Some),
+    }
+}
+
+fn lock_file_path(active_plugins_file: &Path) -> PathBuf {
+    // Lock the file that is written to, so that links to the same file share
+    // a lock.
+    let path = resolve_symlinks(active_plugins_file)
+        .unwrap_or_else(|| active_plugins_file.to_path_buf());
+    let mut file_name = path.file_name().map(OsString::from).unwrap_or_default();
+    file_name.push(".lock");
+
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    use enums::GameId;
+
+    fn game_settings(game_dir: &Path) -> GameSettings {
+        GameSettings::with_local_path(GameId::SkyrimSE, game_dir, &game_dir.join("local"))
+            .unwrap()
+    }
+
+    #[test]
+    fn acquire_should_error_if_the_lock_is_already_held() {
+        let tmp_dir = tempdir().unwrap();
+        let settings = game_settings(tmp_dir.path());
+
+        let lock = ActivePluginsFileLock::acquire(&settings).unwrap();
+        assert_eq!(tmp_dir.path().join("local/plugins.txt.lock"), lock.path());
+
+        match ActivePluginsFileLock::acquire(&settings).unwrap_err() {
+            Error::LoadOrderLocked(ref path) if path == lock.path() => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn acquire_should_error_without_creating_a_lock_file_if_read_only() {
+        let tmp_dir = tempdir().unwrap();
+        let mut settings = game_settings(tmp_dir.path());
+        settings.set_read_only(true);
+
+        match ActivePluginsFileLock::acquire(&settings).unwrap_err() {
+            Error::ReadOnly => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert!(!tmp_dir.path().join("local/plugins.txt.lock").exists());
+    }
+
+    #[test]
+    fn dropping_the_lock_should_release_it() {
+        let tmp_dir = tempdir().unwrap();
+        let settings = game_settings(tmp_dir.path());
+
+        let lock = ActivePluginsFileLock::acquire(&settings).unwrap();
+        let path = lock.path().to_path_buf();
+        drop(lock);
+
+        assert!(!path.exists());
+        assert!(ActivePluginsFileLock::acquire(&settings).is_ok());
+    }
+
+    #[test]
+    fn lock_for_save_should_not_lock_unless_enabled() {
+        let tmp_dir = tempdir().unwrap();
+        let mut settings = game_settings(tmp_dir.path());
+
+        assert!(lock_for_save(&settings, None).unwrap().is_none());
+
+        settings.set_lock_on_save(true);
+        assert!(lock_for_save(&settings, None).unwrap().is_some());
+    }
+
+    #[test]
+    fn lock_for_save_should_not_lock_again_if_given_the_held_lock() {
+        let tmp_dir = tempdir().unwrap();
+        let mut settings = game_settings(tmp_dir.path());
+        settings.set_lock_on_save(true);
+
+        let lock = ActivePluginsFileLock::acquire(&settings).unwrap();
+
+        assert!(lock_for_save(&settings, Some(&lock)).unwrap().is_none());
+        assert!(lock.path().exists());
+    }
+
+    #[test]
+    fn lock_for_save_should_error_if_the_lock_is_held_but_not_given() {
+        let tmp_dir = tempdir().unwrap();
+        let mut settings = game_settings(tmp_dir.path());
+        settings.set_lock_on_save(true);
+
+        let lock = ActivePluginsFileLock::acquire(&settings).unwrap();
+
+        match lock_for_save(&settings, None).unwrap_err() {
+            Error::LoadOrderLocked(ref p) if p == lock.path() => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn lock_for_save_should_ignore_an_existing_lock_file_if_not_enabled() {
+        let tmp_dir = tempdir().unwrap();
+        let settings = game_settings(tmp_dir.path());
+
+        let path = tmp_dir.path().join("local/plugins.txt.lock");
+        create_parent_dirs(&path).unwrap();
+        OpenOptions::new().write(true).create_new(true).open(&path).unwrap();
+
+        assert!(lock_for_save(&settings, None).unwrap().is_none());
+        assert!(path.exists());
+    }
+}
@@ -0,0 +1,794 @@
+/*
+ * This file is part of libloadorder
+ *
+ * Copyright (C) 2017 Oliver Hamlet
+ *
+ * libloadorder is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * libloadorder is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with libloadorder. If not, see <http://www.gnu.org/licenses/>.
+ */
+use std::collections::HashSet;
+use std::io::Write;
+use std::ops::Range;
+use std::path::Path;
+
+use encoding::all::WINDOWS_1252;
+use encoding::{EncoderTrap, Encoding};
+
+use super::insertable::InsertableLoadOrder;
+use super::mutable::MutableLoadOrder;
+use super::readable::{
+    active_count_status, active_index_of, active_master_names, active_non_master_names,
+    active_plugin_names, active_plugins_as_written, content_hash, effective_load_order,
+    excess_active_plugins, flat_entries, form_version, group_by_archive, index_of, is_active,
+    is_blueprint_master, is_master_plugin, is_ordering_valid, is_override_only, light_count,
+    master_count, master_dependency_cycles, master_file_present, master_indices,
+    mismatched_extension_plugins, missing_implicit_plugins, move_delta, non_master_count,
+    orphaned_plugins, plugin_at, plugin_has_associated_archive, plugin_names, plugin_names_reversed,
+    plugin_status, plugin_tier, record_types, remaining_slots, report, valid_index_range, validate,
+    write_mo2_modlist, ActiveCountStatus, LoadOrderEntry, ReadableLoadOrder, ReadableLoadOrderExt,
+};
+use super::writable::{
+    activate, activate_many, activate_with_masters, active_plugins_file_text, active_plugins_match,
+    apply_loot_order, apply_order_from_index, clear, deactivate, deactivate_temporarily,
+    is_in_subset, is_locked, lock_plugin, move_to_tier_end, move_to_tier_start, reorder_masters,
+    restore_active_state, set_active_plugins, set_active_plugins_prioritized, set_active_range,
+    set_complete_load_order, set_load_order_appending_rest, unlock_plugin,
+    validate_active_plugin_counts, ActiveStateToken, StateFingerprint, WritableLoadOrder,
+};
+use super::find_first_non_master_position;
+use enums::{Error, PluginStatus, PluginTier, ValidationWarning};
+use game_settings::GameSettings;
+use plugin::Plugin;
+
+/// A load order that is held entirely in memory.
+///
+/// The load order is created from plugins that are built from known metadata
+/// (see `Plugin::from_metadata()`), so creating, loading and saving it never
+/// touch the filesystem: `save()` records the active plugins in an in-memory
+/// buffer, and `load()` restores the active plugins from that buffer. Methods
+/// that would compare the load order against the game's files work on that
+/// buffer instead. Only plugins that aren't already in the load order are
+/// read from the game's plugins directory, when they are added to it, and
+/// queries about installed files (e.g. `plugin_status()`) still look at them.
+#[derive(Clone, Debug)]
+pub struct MemoryLoadOrder {
+    game_settings: GameSettings,
+    plugins: Vec<Plugin>,
+    locked_plugins: Vec<String>,
+    saved_active_plugins: Vec<String>,
+    subset_loaded: bool,
+}
+
+impl MemoryLoadOrder {
+    pub fn new(game_settings: GameSettings, plugins: Vec<Plugin>) -> Result<Self, Error> {
+        let plugin_names: HashSet<String> =
+            plugins.iter().map(|p| p.name().to_lowercase()).collect();
+        if plugin_names.len() != plugins.len() {
+            return Err(Error::DuplicatePlugin);
+        }
+
+        if !is_ordering_valid(&plugins) {
+            return Err(Error::NonMasterBeforeMaster);
+        }
+
+        Ok(Self {
+            game_settings,
+            plugins,
+            locked_plugins: Vec::new(),
+            saved_active_plugins: Vec::new(),
+            subset_loaded: false,
+        })
+    }
+
+    pub fn saved_active_plugins(&self) -> &[String] {
+        &self.saved_active_plugins
+    }
+
+    /// Restore the saved active plugins that pass the given filter, leaving
+    /// all other plugins inactive, and return any that had to be deactivated
+    /// to stay within the active plugin limit.
+    fn restore_saved_active_plugins<F>(&mut self, filter: F) -> Result<Vec<String>, Error>
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.deactivate_all();
+
+        let plugin_indices: Vec<usize> = self
+            .saved_active_plugins
+            .iter()
+            .filter(|p| filter(p))
+            .filter_map(|p| self.index_of(p))
+            .collect();
+
+        for index in plugin_indices {
+            self.plugins_mut()[index].activate()?;
+        }
+
+        let max_active_normal_plugins = self.game_settings().max_active_normal_plugins();
+        Ok(self.deactivate_excess_plugins(max_active_normal_plugins))
+    }
+}
+
+impl ReadableLoadOrder for MemoryLoadOrder {
+    fn game_settings(&self) -> &GameSettings {
+        &self.game_settings
+    }
+
+    fn plugins_directory(&self) -> &Path {
+        self.game_settings().plugins_directory_path()
+    }
+
+    fn plugin_names(&self) -> Vec<&str> {
+        plugin_names(self.plugins())
+    }
+
+    fn plugins_slice(&self) -> &[Plugin] {
+        self.plugins()
+    }
+
+    fn plugin_names_reversed(&self) -> Vec<&str> {
+        plugin_names_reversed(self.plugins())
+    }
+
+    fn effective_load_order(&self) -> Vec<String> {
+        effective_load_order(self.plugins(), self.game_settings())
+    }
+
+    fn index_of(&self, plugin_name: &str) -> Option<usize> {
+        index_of(self.plugins(), plugin_name)
+    }
+
+    fn active_index_of(&self, plugin_name: &str) -> Option<usize> {
+        active_index_of(self.plugins(), plugin_name)
+    }
+
+    fn move_delta(&self, plugin_name: &str, target: usize) -> Option<isize> {
+        move_delta(&self.plugins, plugin_name, target)
+    }
+
+    fn plugin_at(&self, index: usize) -> Option<&str> {
+        plugin_at(self.plugins(), index)
+    }
+
+    fn active_plugin_names(&self) -> Vec<&str> {
+        active_plugin_names(self.plugins())
+    }
+
+    fn active_master_names(&self) -> Vec<&str> {
+        active_master_names(self.plugins())
+    }
+
+    fn active_non_master_names(&self) -> Vec<&str> {
+        active_non_master_names(self.plugins())
+    }
+
+    fn master_count(&self) -> usize {
+        master_count(self.plugins())
+    }
+
+    fn non_master_count(&self) -> usize {
+        non_master_count(self.plugins())
+    }
+
+    fn light_count(&self) -> usize {
+        light_count(self.plugins())
+    }
+
+    fn active_count_status(&self) -> ActiveCountStatus {
+        active_count_status(self.plugins(), self.game_settings())
+    }
+
+    fn remaining_slots(&self) -> (usize, usize) {
+        remaining_slots(self.plugins(), self.game_settings())
+    }
+
+    fn active_plugins_as_written(&self) -> Vec<String> {
+        active_plugins_as_written(self.plugins())
+    }
+
+    fn active_plugins_file_content(&self) -> Result<String, Error> {
+        active_plugins_file_text(self, Vec::new())
+    }
+
+    fn is_active(&self, plugin_name: &str) -> bool {
+        is_active(self.plugins(), plugin_name)
+    }
+
+    fn plugin_status(&self, plugin_name: &str) -> PluginStatus {
+        plugin_status(self.plugins(), self.game_settings(), plugin_name)
+    }
+
+    fn plugin_tier(&self, plugin_name: &str) -> Option<PluginTier> {
+        plugin_tier(self.plugins(), self.game_settings(), plugin_name)
+    }
+
+    fn flat_entries(&self) -> Vec<LoadOrderEntry<'_>> {
+        flat_entries(self.plugins())
+    }
+
+    fn excess_active_plugins(&self) -> Vec<&str> {
+        excess_active_plugins(self.plugins(), self.game_settings())
+    }
+
+    fn master_file_present(&self) -> bool {
+        master_file_present(self.plugins(), self.game_settings().master_file())
+    }
+
+    fn valid_index_range(&self, plugin_name: &str) -> Result<Range<usize>, Error> {
+        valid_index_range(self.plugins(), plugin_name, self.game_settings())
+    }
+
+    fn is_override_only(&self, plugin_name: &str) -> Result<bool, Error> {
+        is_override_only(self.plugins(), plugin_name)
+    }
+
+    fn master_indices(&self, plugin_name: &str) -> Result<Vec<(String, Option<usize>)>, Error> {
+        master_indices(self.plugins(), plugin_name)
+    }
+
+    fn master_dependency_cycles(&self) -> Vec<Vec<String>> {
+        master_dependency_cycles(self.plugins())
+    }
+
+    fn orphaned_plugins(&self) -> Result<Vec<String>, Error> {
+        orphaned_plugins(self.plugins())
+    }
+
+    fn mismatched_extension_plugins(&self) -> Vec<String> {
+        mismatched_extension_plugins(self.plugins())
+    }
+
+    fn is_ordering_valid(&self) -> bool {
+        is_ordering_valid(self.plugins())
+    }
+
+    fn plugin_has_associated_archive(&self, plugin_name: &str) -> bool {
+        plugin_has_associated_archive(plugin_name, self.game_settings())
+    }
+
+    fn content_hash(&self) -> u64 {
+        content_hash(self.plugins())
+    }
+
+    fn group_by_archive(&self) -> Vec<(String, Vec<String>)> {
+        group_by_archive(self.plugins(), self.game_settings())
+    }
+
+    fn duplicate_content_plugins(&self) -> Result<Vec<Vec<String>>, Error> {
+        // Plugins built from metadata have no file content to compare.
+        Ok(Vec::new())
+    }
+
+    fn read_active_plugins_file_raw(&self) -> Result<Vec<u8>, Error> {
+        let line_ending = self.game_settings().line_ending();
+        let mut content = Vec::new();
+        for plugin_name in &self.saved_active_plugins {
+            content.extend(WINDOWS_1252
+                .encode(plugin_name, EncoderTrap::Strict)
+                .map_err(Error::EncodeError)?);
+            content.extend(line_ending.as_bytes());
+        }
+
+        Ok(content)
+    }
+
+    fn write_mo2_modlist(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        write_mo2_modlist(&self.plugins, writer)
+    }
+
+    fn is_master_plugin(&self, plugin_name: &str) -> Option<bool> {
+        is_master_plugin(&self.plugins, self.game_settings(), plugin_name)
+    }
+
+    fn form_version(&self, plugin_name: &str) -> Option<u16> {
+        form_version(&self.plugins, plugin_name)
+    }
+
+    fn is_blueprint_master(&self, plugin_name: &str) -> Option<bool> {
+        is_blueprint_master(self.plugins(), plugin_name)
+    }
+
+    fn record_types(&self, plugin_name: &str) -> Result<HashSet<[u8; 4]>, Error> {
+        record_types(self.plugins(), plugin_name)
+    }
+
+    fn validate(&self) -> Vec<ValidationWarning> {
+        validate(&self.plugins, self.game_settings().id())
+    }
+
+    fn missing_implicit_plugins(&self) -> Vec<String> {
+        missing_implicit_plugins(self.game_settings())
+    }
+
+    fn report(&self) -> String {
+        report(self.plugins(), self.game_settings())
+    }
+}
+
+impl ReadableLoadOrderExt for MemoryLoadOrder {
+    fn plugins(&self) -> &Vec<Plugin> {
+        &self.plugins
+    }
+}
+
+impl MutableLoadOrder for MemoryLoadOrder {
+    fn plugins_mut(&mut self) -> &mut Vec<Plugin> {
+        &mut self.plugins
+    }
+
+    fn locked_plugins(&self) -> &[String] {
+        &self.locked_plugins
+    }
+
+    fn locked_plugins_mut(&mut self) -> &mut Vec<String> {
+        &mut self.locked_plugins
+    }
+}
+
+impl InsertableLoadOrder for MemoryLoadOrder {
+    fn insert_position(&self, plugin: &Plugin) -> Option<usize> {
+        if plugin.is_master_file() {
+            find_first_non_master_position(self.plugins())
+        } else {
+            None
+        }
+    }
+}
+
+impl WritableLoadOrder for MemoryLoadOrder {
+    fn load_and_report_excess_plugins_with_progress(
+        &mut self,
+        progress: &(dyn Fn(usize, usize) + Sync),
+    ) -> Result<Vec<String>, Error> {
+        let excess_plugins = self.restore_saved_active_plugins(|_| true)?;
+        self.subset_loaded = false;
+
+        // Plugins are already held in memory, so there's nothing to construct.
+        let total = self.plugins().len();
+        progress(total, total);
+
+        Ok(excess_plugins)
+    }
+
+    fn load_and_report_unghosted_plugins(&mut self) -> Result<Vec<String>, Error> {
+        // Loading doesn't touch any files, so nothing is ever unghosted.
+        self.load()?;
+
+        Ok(Vec::new())
+    }
+
+    fn load_subset(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        // Every plugin stays in the load order, as they can't be read back
+        // in later, so only the active state is limited to the subset.
+        let game_settings = self.game_settings().clone();
+        self.restore_saved_active_plugins(|p| is_in_subset(&game_settings, plugin_names, p))?;
+        self.subset_loaded = true;
+
+        Ok(())
+    }
+
+    fn save(&mut self) -> Result<(), Error> {
+        if self.subset_loaded {
+            return Err(Error::SubsetLoaded);
+        }
+
+        validate_active_plugin_counts(self)?;
+
+        self.saved_active_plugins = self
+            .active_plugin_names()
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        Ok(())
+    }
+
+    fn save_load_order_only(&mut self) -> Result<(), Error> {
+        self.save()
+    }
+
+    fn save_would_change(&self) -> Result<bool, Error> {
+        let active_plugin_names = self.active_plugin_names();
+        Ok(active_plugin_names.len() != self.saved_active_plugins.len()
+            || active_plugin_names
+                .iter()
+                .zip(&self.saved_active_plugins)
+                .any(|(a, b)| a != b))
+    }
+
+    fn lock_active_plugins_file(&mut self) -> Result<(), Error> {
+        // Saving doesn't write any files, so there's nothing to lock.
+        Ok(())
+    }
+
+    fn unlock_active_plugins_file(&mut self) {}
+
+    fn set_load_order(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        self.replace_plugins(plugin_names)
+    }
+
+    fn set_complete_load_order(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        set_complete_load_order(self, plugin_names)
+    }
+
+    fn set_load_order_appending_rest(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        set_load_order_appending_rest(self, plugin_names)
+    }
+
+    fn apply_order_from_index(&mut self, start: usize, plugin_names: &[&str]) -> Result<(), Error> {
+        apply_order_from_index(self, start, plugin_names)
+    }
+
+    fn apply_loot_order(&mut self, sorted_plugin_names: &[&str]) -> Result<Vec<String>, Error> {
+        apply_loot_order(self, sorted_plugin_names)
+    }
+
+    fn set_plugin_index(&mut self, plugin_name: &str, position: usize) -> Result<(), Error> {
+        self.move_or_insert_plugin_with_index(plugin_name, position)
+    }
+
+    fn move_to_tier_start(&mut self, plugin_name: &str) -> Result<(), Error> {
+        move_to_tier_start(self, plugin_name)
+    }
+
+    fn move_to_tier_end(&mut self, plugin_name: &str) -> Result<(), Error> {
+        move_to_tier_end(self, plugin_name)
+    }
+
+    fn is_self_consistent(&self) -> Result<bool, Error> {
+        Ok(true)
+    }
+
+    fn activate(&mut self, plugin_name: &str) -> Result<(), Error> {
+        activate(self, plugin_name)
+    }
+
+    fn activate_many(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        activate_many(self, plugin_names)
+    }
+
+    fn activate_with_masters(&mut self, plugin_name: &str) -> Result<Vec<String>, Error> {
+        activate_with_masters(self, plugin_name)
+    }
+
+    fn deactivate(&mut self, plugin_name: &str) -> Result<(), Error> {
+        deactivate(self, plugin_name)
+    }
+
+    fn deactivate_temporarily(&mut self, plugin_names: &[&str]) -> Result<ActiveStateToken, Error> {
+        deactivate_temporarily(self, plugin_names)
+    }
+
+    fn restore_active_state(&mut self, token: ActiveStateToken) -> Result<(), Error> {
+        restore_active_state(self, token)
+    }
+
+    fn set_active_range(
+        &mut self,
+        start: usize,
+        end: usize,
+        active: bool,
+    ) -> Result<Vec<String>, Error> {
+        set_active_range(self, start, end, active)
+    }
+
+    fn set_active_plugins(&mut self, active_plugin_names: &[&str]) -> Result<(), Error> {
+        set_active_plugins(self, active_plugin_names)
+    }
+
+    fn set_active_plugins_prioritized(
+        &mut self,
+        active_plugin_names: &[&str],
+    ) -> Result<Vec<String>, Error> {
+        set_active_plugins_prioritized(self, active_plugin_names)
+    }
+
+    fn reorder_masters(&mut self, master_order: &[&str]) -> Result<(), Error> {
+        reorder_masters(self, master_order)
+    }
+
+    fn changed_plugins_since_load(&self) -> Result<Vec<String>, Error> {
+        // Plugins built from metadata have no files that could change.
+        Ok(Vec::new())
+    }
+
+    fn refresh_implicitly_active(&mut self) -> Result<(), Error> {
+        self.game_settings.refresh_implicitly_active_plugins()?;
+
+        self.add_implicitly_active_plugins()
+    }
+
+    fn state_fingerprint(&self) -> Result<StateFingerprint, Error> {
+        // The load order's state isn't held in any files.
+        Ok(StateFingerprint {
+            active_plugins_file_time: None,
+            plugins_directory_time: None,
+        })
+    }
+
+    fn lock_plugin(&mut self, plugin_name: &str) {
+        lock_plugin(self, plugin_name)
+    }
+
+    fn unlock_plugin(&mut self, plugin_name: &str) {
+        unlock_plugin(self, plugin_name)
+    }
+
+    fn is_locked(&self, plugin_name: &str) -> bool {
+        is_locked(self, plugin_name)
+    }
+
+    fn merge_new_plugins(&mut self) -> Result<Vec<String>, Error> {
+        // The load order isn't built from the plugins directory, so plugins
+        // are only added to it explicitly.
+        Ok(Vec::new())
+    }
+
+    fn clear(&mut self) {
+        clear(self)
+    }
+
+    fn stale_active_entries(&self) -> Result<Vec<String>, Error> {
+        Ok(self
+            .saved_active_plugins
+            .iter()
+            .filter(|p| self.index_of(p).is_none())
+            .cloned()
+            .collect())
+    }
+
+    fn verify_active_file_matches(&self) -> Result<bool, Error> {
+        Ok(active_plugins_match(self, &self.saved_active_plugins))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::Path;
+
+    use tempfile::tempdir;
+
+    use enums::GameId;
+    use load_order::tests::*;
+
+    fn to_plugins(game_settings: &GameSettings, plugin_names: &[&str]) -> Vec<Plugin> {
+        plugin_names
+            .iter()
+            .map(|n| {
+                let is_master = n.ends_with(".esm");
+                Plugin::from_metadata(n, game_settings, is_master, false, Vec::new()).unwrap()
+            })
+            .collect()
+    }
+
+    fn prepare(game_id: GameId, game_dir: &Path) -> MemoryLoadOrder {
+        let (game_settings, _) = mock_game_files(game_id, game_dir);
+        let master_file = game_settings.master_file().to_string();
+        let plugins = to_plugins(
+            &game_settings,
+            &[
+                master_file.as_str(),
+                "Blank.esm",
+                "Blank.esp",
+                "Blank - Different.esp",
+            ],
+        );
+
+        MemoryLoadOrder::new(game_settings, plugins).unwrap()
+    }
+
+    #[test]
+    fn new_should_create_a_load_order_with_the_given_plugins_inactive() {
+        let tmp_dir = tempdir().unwrap();
+        let load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        let expected_filenames = vec![
+            "Oblivion.esm",
+            "Blank.esm",
+            "Blank.esp",
+            "Blank - Different.esp",
+        ];
+        assert_eq!(expected_filenames, load_order.plugin_names());
+        assert!(load_order.active_plugin_names().is_empty());
+    }
+
+    #[test]
+    fn new_should_error_if_given_a_non_master_before_a_master() {
+        let tmp_dir = tempdir().unwrap();
+        let (game_settings, _) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        let plugins = to_plugins(&game_settings, &["Blank.esp", "Blank.esm"]);
+        assert!(MemoryLoadOrder::new(game_settings, plugins).is_err());
+    }
+
+    #[test]
+    fn new_should_not_read_plugins_from_the_filesystem() {
+        let tmp_dir = tempdir().unwrap();
+        let game_dir = tmp_dir.path().join("missing");
+        let game_settings = GameSettings::with_local_path(
+            GameId::SkyrimSE,
+            &game_dir,
+            &tmp_dir.path().join("local"),
+        ).unwrap();
+
+        let plugins = vec![
+            Plugin::from_metadata("Skyrim.esm", &game_settings, true, false, Vec::new()).unwrap(),
+            Plugin::from_metadata(
+                "Blank.esl",
+                &game_settings,
+                true,
+                true,
+                vec!["Skyrim.esm".to_string()],
+            ).unwrap(),
+            Plugin::from_metadata("Blank.esp", &game_settings, false, false, Vec::new()).unwrap(),
+        ];
+
+        let mut load_order = MemoryLoadOrder::new(game_settings, plugins).unwrap();
+
+        assert!(!game_dir.exists());
+        assert_eq!(
+            vec!["Skyrim.esm", "Blank.esl", "Blank.esp"],
+            load_order.plugin_names()
+        );
+        assert_eq!(Some(true), load_order.is_master_plugin("Blank.esl"));
+
+        load_order.activate("Blank.esp").unwrap();
+        load_order.save().unwrap();
+        load_order.set_load_order(&["Skyrim.esm", "Blank.esp"]).unwrap();
+
+        assert_eq!(vec!["Skyrim.esm", "Blank.esp"], load_order.plugin_names());
+        assert_eq!(vec!["Blank.esp"], load_order.active_plugin_names());
+        assert!(!game_dir.exists());
+    }
+
+    #[test]
+    fn plugins_directory_should_be_the_game_settings_plugins_directory() {
+        let tmp_dir = tempdir().unwrap();
+        let load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        assert_eq!(tmp_dir.path().join("Data"), load_order.plugins_directory());
+    }
+
+    #[test]
+    fn activate_should_activate_a_plugin_in_the_load_order() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        assert!(load_order.activate("Blank.esp").is_ok());
+        assert!(load_order.is_active("Blank.esp"));
+    }
+
+    #[test]
+    fn activate_should_add_an_installed_plugin_not_in_the_load_order() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        assert!(load_order.activate("Blàñk.esp").is_ok());
+        assert_eq!(Some(4), load_order.index_of("Blàñk.esp"));
+        assert!(load_order.is_active("Blàñk.esp"));
+    }
+
+    #[test]
+    fn set_plugin_index_should_move_an_existing_plugin() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        assert!(load_order.set_plugin_index("Blank - Different.esp", 2).is_ok());
+        assert_eq!(Some(2), load_order.index_of("Blank - Different.esp"));
+        assert_eq!(Some(3), load_order.index_of("Blank.esp"));
+    }
+
+    #[test]
+    fn set_plugin_index_should_error_if_moving_a_non_master_before_a_master() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        assert!(load_order.set_plugin_index("Blank.esp", 0).is_err());
+        assert_eq!(Some(2), load_order.index_of("Blank.esp"));
+    }
+
+    #[test]
+    fn save_should_only_write_to_the_in_memory_buffer() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        load_order.activate("Blank.esp").unwrap();
+        load_order.save().unwrap();
+
+        assert_eq!(&["Blank.esp".to_string()], load_order.saved_active_plugins());
+        assert!(!load_order.game_settings().active_plugins_file().exists());
+    }
+
+    #[test]
+    fn load_should_restore_active_plugins_from_the_in_memory_buffer() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        load_order.activate("Blank.esp").unwrap();
+        load_order.save().unwrap();
+        load_order.deactivate("Blank.esp").unwrap();
+        load_order.activate("Blank - Different.esp").unwrap();
+
+        load_order.load().unwrap();
+
+        assert_eq!(vec!["Blank.esp"], load_order.active_plugin_names());
+    }
+
+    #[test]
+    fn load_subset_should_keep_all_plugins_and_only_activate_saved_plugins_in_the_subset() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        load_order.activate_many(&["Blank.esm", "Blank.esp"]).unwrap();
+        load_order.save().unwrap();
+
+        load_order.load_subset(&["Blank.esp"]).unwrap();
+
+        assert_eq!(4, load_order.plugin_names().len());
+        assert_eq!(vec!["Blank.esp"], load_order.active_plugin_names());
+
+        load_order.load().unwrap();
+
+        assert_eq!(4, load_order.plugin_names().len());
+        assert_eq!(vec!["Blank.esm", "Blank.esp"], load_order.active_plugin_names());
+    }
+
+    #[test]
+    fn save_should_error_after_load_subset_until_load_is_called() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        load_order.activate("Blank.esp").unwrap();
+        load_order.save().unwrap();
+        load_order.load_subset(&["Blank.esm"]).unwrap();
+
+        match load_order.save().unwrap_err() {
+            Error::SubsetLoaded => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(&["Blank.esp".to_string()], load_order.saved_active_plugins());
+
+        load_order.load().unwrap();
+        assert!(load_order.save().is_ok());
+    }
+
+    #[test]
+    fn methods_comparing_against_the_game_files_should_not_touch_the_filesystem() {
+        let tmp_dir = tempdir().unwrap();
+        let game_dir = tmp_dir.path().join("missing");
+        let game_settings =
+            GameSettings::with_local_path(GameId::Oblivion, &game_dir, &game_dir.join("local"))
+                .unwrap();
+        let plugins = to_plugins(&game_settings, &["Oblivion.esm", "Blank.esp"]);
+        let mut load_order = MemoryLoadOrder::new(game_settings, plugins).unwrap();
+
+        load_order.activate("Blank.esp").unwrap();
+        load_order.save().unwrap();
+
+        assert_eq!(
+            b"Blank.esp\n".to_vec(),
+            load_order.read_active_plugins_file_raw().unwrap()
+        );
+        assert!(load_order.load_and_report_unghosted_plugins().unwrap().is_empty());
+        assert!(load_order.changed_plugins_since_load().unwrap().is_empty());
+        assert!(load_order.duplicate_content_plugins().unwrap().is_empty());
+        assert!(load_order.merge_new_plugins().unwrap().is_empty());
+        assert_eq!(
+            load_order.state_fingerprint().unwrap(),
+            load_order.state_fingerprint().unwrap()
+        );
+        assert!(!game_dir.exists());
+    }
+}
@@ -19,6 +19,8 @@
 
 mod asterisk_based;
 mod insertable;
+mod lock;
+mod memory_based;
 mod mutable;
 mod readable;
 #[cfg(test)]
@@ -27,15 +29,20 @@ mod textfile_based;
 mod timestamp_based;
 mod writable;
 
-use std::fs::create_dir_all;
-use std::path::Path;
+use std::fs::{create_dir_all, read_link, File};
+use std::path::{Path, PathBuf};
 
 use enums::Error;
+use game_settings::GameSettings;
 pub use load_order::asterisk_based::AsteriskBasedLoadOrder;
-pub use load_order::readable::ReadableLoadOrder;
+pub use load_order::lock::ActivePluginsFileLock;
+pub use load_order::memory_based::MemoryLoadOrder;
+pub use load_order::readable::{
+    ActiveCountStatus, LoadOrderEntry, ReadableLoadOrder, MAX_ACTIVE_NORMAL_PLUGINS,
+};
 pub use load_order::textfile_based::TextfileBasedLoadOrder;
 pub use load_order::timestamp_based::TimestampBasedLoadOrder;
-pub use load_order::writable::WritableLoadOrder;
+pub use load_order::writable::{ActiveStateToken, StateFingerprint, WritableLoadOrder};
 use plugin::Plugin;
 
 fn find_first_non_master_position(plugins: &[Plugin]) -> Option<usize> {
@@ -44,6 +51,46 @@ fn find_first_non_master_position(plugins: &[Plugin]) -> Option<usize> {
         .position(|p| !p.is_master_file() && !p.is_light_master_file())
 }
 
+/// Follow any symlinks at the given path to the path of the file they point
+/// to, or `None` if the links form a cycle. Opening the path follows links
+/// anyway, so this is only needed where the target's own path matters.
+fn resolve_symlinks(path: &Path) -> Option<PathBuf> {
+    let mut visited = Vec::new();
+    let mut path = path.to_path_buf();
+    while let Ok(target) = read_link(&path) {
+        if visited.contains(&path) {
+            return None;
+        }
+        let next_path = match path.parent() {
+            Some(parent) => parent.join(target),
+            None => target,
+        };
+        visited.push(path);
+        path = next_path;
+    }
+    Some(path)
+}
+
+fn ensure_writable(game_settings: &GameSettings) -> Result<(), Error> {
+    if game_settings.is_read_only() {
+        Err(Error::ReadOnly)
+    } else {
+        Ok(())
+    }
+}
+
+/// Create or truncate the file at the given path, creating any missing parent
+/// directories. If the path is a dangling symlink, the directories are created
+/// for its target, which is then created instead of replacing the link.
+fn create_file(path: &Path) -> Result<File, Error> {
+    if path.exists() {
+        create_parent_dirs(path)?;
+    } else {
+        create_parent_dirs(&resolve_symlinks(path).unwrap_or_else(|| path.to_path_buf()))?;
+    }
+    File::create(path).map_err(Error::from)
+}
+
 fn create_parent_dirs(path: &Path) -> Result<(), Error> {
     if let Some(x) = path.parent() {
         if !x.exists() {
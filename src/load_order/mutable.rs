@@ -23,22 +23,66 @@ use std::io::Read;
 use std::mem;
 use std::path::Path;
 
-use encoding::all::WINDOWS_1252;
+use encoding::all::{UTF_8, WINDOWS_1252};
 use encoding::{DecoderTrap, Encoding};
 use rayon::prelude::*;
+use unicase::eq;
 
-use super::find_first_non_master_position;
-use super::readable::ReadableLoadOrderExt;
+use super::readable::{is_ordering_valid, ReadableLoadOrderExt};
 use enums::Error;
-use plugin::Plugin;
+use plugin::{has_invalid_characters, trim_dot_ghost, Plugin};
+
+pub const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
 
 pub trait MutableLoadOrder: ReadableLoadOrderExt {
     fn plugins_mut(&mut self) -> &mut Vec<Plugin>;
 
-    fn deactivate_excess_plugins(&mut self) {
-        for index in self.get_excess_active_plugin_indices() {
+    fn locked_plugins(&self) -> &[String];
+
+    fn locked_plugins_mut(&mut self) -> &mut Vec<String>;
+
+    /// Check that no locked plugin would change position if the load order
+    /// was replaced by the given plugin names.
+    fn validate_locked_positions(&self, new_plugin_names: &[&str]) -> Result<(), Error> {
+        for locked_plugin in self.locked_plugins() {
+            let index = match self.index_of(locked_plugin) {
+                Some(x) => x,
+                None => continue,
+            };
+
+            let new_index = new_plugin_names
+                .iter()
+                .position(|n| eq(trim_dot_ghost(n), locked_plugin.as_str()));
+            if new_index != Some(index) {
+                return Err(Error::PluginLocked(locked_plugin.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that the game's master file would load first if the load order
+    /// was replaced by the given plugin names, if it is one of them.
+    fn validate_game_master_position(&self, new_plugin_names: &[&str]) -> Result<(), Error> {
+        let master_file = self.game_settings().master_file();
+        match new_plugin_names.iter().position(|n| eq(trim_dot_ghost(n), master_file)) {
+            Some(index) if index != 0 => Err(Error::GameMasterMustLoadFirst),
+            _ => Ok(()),
+        }
+    }
+
+    fn deactivate_excess_plugins(&mut self, max_active_normal_plugins: usize) -> Vec<String> {
+        let mut plugin_names = Vec::new();
+        for index in self
+            .get_excess_active_plugin_indices(max_active_normal_plugins)
+            .into_iter()
+            .rev()
+        {
             self.plugins_mut()[index].deactivate();
+            plugin_names.push(self.plugins()[index].name().to_string());
         }
+
+        plugin_names
     }
 
     fn move_or_insert_plugin_with_index(
@@ -46,10 +90,23 @@ pub trait MutableLoadOrder: ReadableLoadOrderExt {
         plugin_name: &str,
         position: usize,
     ) -> Result<(), Error> {
-        if let Some(x) = self.index_of(plugin_name) {
-            if x == position {
-                return Ok(());
+        validate_plugin_name(plugin_name)?;
+
+        let current_index = self.index_of(plugin_name);
+        if current_index == Some(position) {
+            return Ok(());
+        }
+
+        {
+            let mut new_plugin_names = self.plugin_names();
+            if let Some(x) = current_index {
+                new_plugin_names.remove(x);
             }
+            let new_index = position.min(new_plugin_names.len());
+            new_plugin_names.insert(new_index, plugin_name);
+
+            self.validate_locked_positions(&new_plugin_names)?;
+            self.validate_game_master_position(&new_plugin_names)?;
         }
 
         let plugin = get_plugin_to_insert_at(self, plugin_name, position)?;
@@ -70,16 +127,23 @@ pub trait MutableLoadOrder: ReadableLoadOrderExt {
     }
 
     fn replace_plugins(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        for plugin_name in plugin_names {
+            validate_plugin_name(plugin_name)?;
+        }
+
         if !are_plugin_names_unique(plugin_names) {
             return Err(Error::DuplicatePlugin);
         }
 
+        self.validate_locked_positions(plugin_names)?;
+        self.validate_game_master_position(plugin_names)?;
+
         let mut plugins = match self.map_to_plugins(plugin_names) {
             Err(x) => return Err(Error::InvalidPlugin(x.to_string())),
             Ok(x) => x,
         };
 
-        if !is_partitioned_by_master_flag(&plugins) {
+        if !is_ordering_valid(&plugins) {
             return Err(Error::NonMasterBeforeMaster);
         }
 
@@ -126,13 +190,42 @@ where
     let mut file = File::open(file_path)?;
     file.read_to_end(&mut content)?;
 
-    let content = WINDOWS_1252
-        .decode(&content, DecoderTrap::Strict)
-        .map_err(Error::DecodeError)?;
+    let content = if content.starts_with(UTF8_BOM) {
+        UTF_8.decode(&content[UTF8_BOM.len()..], DecoderTrap::Strict)
+    } else {
+        WINDOWS_1252.decode(&content, DecoderTrap::Strict)
+    }.map_err(Error::DecodeError)?;
 
     Ok(content.lines().filter_map(line_mapper).collect())
 }
 
+/// Check if the given file starts with a UTF-8 byte order mark. Files without
+/// one are assumed to be encoded in Windows-1252.
+pub fn has_utf8_bom(file_path: &Path) -> Result<bool, Error> {
+    if !file_path.exists() {
+        return Ok(false);
+    }
+
+    let mut content: Vec<u8> = Vec::new();
+    File::open(file_path)?
+        .take(UTF8_BOM.len() as u64)
+        .read_to_end(&mut content)?;
+
+    Ok(content == UTF8_BOM)
+}
+
+pub fn read_active_plugins_limit(file_path: &Path) -> Result<Option<usize>, Error> {
+    let limits = read_plugin_names(file_path, limit_directive_mapper)?;
+
+    Ok(limits.into_iter().min())
+}
+
+fn limit_directive_mapper(line: &str) -> Option<usize> {
+    line.strip_prefix('#')
+        .and_then(|directive| directive.trim().strip_prefix("max="))
+        .and_then(|limit| limit.trim().parse().ok())
+}
+
 pub fn plugin_line_mapper(line: &str) -> Option<String> {
     if line.is_empty() || line.starts_with('#') {
         None
@@ -161,20 +254,17 @@ fn get_plugin_to_insert_at<T: MutableLoadOrder + ?Sized>(
     }
 }
 
+fn validate_plugin_name(plugin_name: &str) -> Result<(), Error> {
+    if has_invalid_characters(plugin_name) {
+        Err(Error::InvalidPluginName(plugin_name.to_string()))
+    } else {
+        Ok(())
+    }
+}
+
 fn are_plugin_names_unique(plugin_names: &[&str]) -> bool {
     let unique_plugin_names: HashSet<String> =
         plugin_names.par_iter().map(|s| s.to_lowercase()).collect();
 
     unique_plugin_names.len() == plugin_names.len()
 }
-
-fn is_partitioned_by_master_flag(plugins: &[Plugin]) -> bool {
-    let plugin_pos = match find_first_non_master_position(plugins) {
-        None => return true,
-        Some(x) => x,
-    };
-    match plugins.iter().rposition(|p| p.is_master_file()) {
-        None => true,
-        Some(master_pos) => master_pos < plugin_pos,
-    }
-}
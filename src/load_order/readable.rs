@@ -16,42 +16,234 @@
  * You should have received a copy of the GNU General Public License
  * along with libloadorder. If not, see <http://www.gnu.org/licenses/>.
  */
-use std::collections::HashSet;
-use std::fs::read_dir;
+use std::collections::{HashMap, HashSet};
+use std::fs::{read_dir, File};
+use std::io::{self, Read, Write};
+use std::iter::once;
+use std::ops::Range;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
 
 use rayon::iter::Either;
 use rayon::prelude::*;
+use unicase::eq;
 
 use super::find_first_non_master_position;
-use enums::Error;
+use enums::{Error, GameId, LoadOrderMethod, PluginStatus, PluginTier, ValidationWarning};
 use game_settings::GameSettings;
-use plugin::{trim_dot_ghost, Plugin};
+use ghostable_path::GhostablePath;
+use plugin::{has_valid_extension, trim_dot_ghost, Plugin};
 
 pub const MAX_ACTIVE_NORMAL_PLUGINS: usize = 255;
 pub const MAX_ACTIVE_LIGHT_MASTERS: usize = 4096;
 
+/// How close the number of active normal plugins is to the game's limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ActiveCountStatus {
+    pub count: usize,
+    pub limit: usize,
+    pub over_warning_threshold: bool,
+    pub over_limit: bool,
+}
+
+/// A plugin's position and state in the load order, for copying the whole
+/// load order in one pass, e.g. across an FFI boundary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LoadOrderEntry<'a> {
+    pub index: usize,
+    pub name: &'a str,
+    pub tier: PluginTier,
+    pub active: bool,
+}
+
+const SKYRIM_ADJACENT_MASTERS: &[(&str, &str)] = &[("Skyrim.esm", "Update.esm")];
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
 pub trait ReadableLoadOrder {
     fn game_settings(&self) -> &GameSettings;
 
+    fn plugins_directory(&self) -> &Path;
+
     fn plugin_names(&self) -> Vec<&str>;
 
+    /// Get the parsed plugins in load order, so that their data can be read
+    /// without parsing the plugin files again.
+    fn plugins_slice(&self) -> &[Plugin];
+
+    fn plugin_names_reversed(&self) -> Vec<&str>;
+
+    /// Get the active plugins in the order that the game loads them, which
+    /// may differ from the load order. The game loads its implicitly active
+    /// plugins first, in their fixed order, then other active masters and
+    /// then other active plugins.
+    fn effective_load_order(&self) -> Vec<String>;
+
     fn index_of(&self, plugin_name: &str) -> Option<usize>;
 
+    fn active_index_of(&self, plugin_name: &str) -> Option<usize>;
+
+    fn move_delta(&self, plugin_name: &str, target: usize) -> Option<isize>;
+
     fn plugin_at(&self, index: usize) -> Option<&str>;
 
     fn active_plugin_names(&self) -> Vec<&str>;
 
+    /// Get the names of the active plugins that are masters, including light
+    /// masters, in load order.
+    fn active_master_names(&self) -> Vec<&str>;
+
+    fn active_non_master_names(&self) -> Vec<&str>;
+
+    /// Count the plugins that are masters, including light masters.
+    fn master_count(&self) -> usize;
+
+    fn non_master_count(&self) -> usize;
+
+    /// Count the plugins that are light masters, whether they have a .esl
+    /// extension or the light master flag set.
+    fn light_count(&self) -> usize;
+
+    /// Get the number of active normal plugins and how it compares to the
+    /// limit and to the game settings' warning threshold, which defaults to
+    /// 90% of the limit. Light masters are not counted.
+    fn active_count_status(&self) -> ActiveCountStatus;
+
+    /// Get the number of normal plugins and the number of light masters that
+    /// could still be activated without exceeding the game's limits. The
+    /// light master figure is zero for games that don't support them.
+    fn remaining_slots(&self) -> (usize, usize);
+
+    /// Get the lines that saving would write to the active plugins file,
+    /// excluding any prelude that is preserved from the existing file.
+    fn active_plugins_as_written(&self) -> Vec<String>;
+
+    /// Get the text that saving would write to the active plugins file,
+    /// including any preserved prelude. Prelude bytes that aren't valid
+    /// Windows-1252 are replaced.
+    fn active_plugins_file_content(&self) -> Result<String, Error>;
+
     fn is_active(&self, plugin_name: &str) -> bool;
+
+    fn plugin_status(&self, plugin_name: &str) -> PluginStatus;
+
+    /// Get how the given plugin counts towards the game's active plugin
+    /// limits, or `None` if it isn't in the load order or installed.
+    fn plugin_tier(&self, plugin_name: &str) -> Option<PluginTier>;
+
+    /// Get the index, name, tier and active state of every plugin in the
+    /// load order, in load order.
+    fn flat_entries(&self) -> Vec<LoadOrderEntry<'_>>;
+
+    fn excess_active_plugins(&self) -> Vec<&str>;
+
+    fn master_file_present(&self) -> bool;
+
+    fn valid_index_range(&self, plugin_name: &str) -> Result<Range<usize>, Error>;
+
+    fn is_override_only(&self, plugin_name: &str) -> Result<bool, Error>;
+
+    fn master_indices(&self, plugin_name: &str) -> Result<Vec<(String, Option<usize>)>, Error>;
+
+    fn master_dependency_cycles(&self) -> Vec<Vec<String>>;
+
+    /// Get the plugins that have masters missing from the load order and that
+    /// no other plugin in the load order depends on. Such plugins can't be
+    /// loaded, and removing them won't break anything else.
+    fn orphaned_plugins(&self) -> Result<Vec<String>, Error>;
+
+    /// Get the plugins that have a .esp extension but the master flag set,
+    /// or a .esm extension but not the master flag. Such plugins are valid,
+    /// but may be confusing.
+    fn mismatched_extension_plugins(&self) -> Vec<String>;
+
+    fn is_ordering_valid(&self) -> bool;
+
+    fn plugin_has_associated_archive(&self, plugin_name: &str) -> bool;
+
+    fn content_hash(&self) -> u64;
+
+    fn group_by_archive(&self) -> Vec<(String, Vec<String>)>;
+
+    fn duplicate_content_plugins(&self) -> Result<Vec<Vec<String>>, Error>;
+
+    fn read_active_plugins_file_raw(&self) -> Result<Vec<u8>, Error>;
+
+    /// Write the load order in the format of a Mod Organizer 2 `modlist.txt`,
+    /// with each plugin prefixed by `+` if it is active and `-` if not. MO2
+    /// lists entries from highest to lowest priority, so the last plugin in
+    /// the load order is written first and the game master is written last.
+    fn write_mo2_modlist(&self, writer: &mut dyn Write) -> Result<(), Error>;
+
+    fn is_master_plugin(&self, plugin_name: &str) -> Option<bool>;
+
+    fn form_version(&self, plugin_name: &str) -> Option<u16>;
+
+    fn is_blueprint_master(&self, plugin_name: &str) -> Option<bool>;
+
+    fn record_types(&self, plugin_name: &str) -> Result<HashSet<[u8; 4]>, Error>;
+
+    /// Check the load order against game-specific conventions that the game
+    /// doesn't enforce but that mods commonly rely on.
+    fn validate(&self) -> Vec<ValidationWarning>;
+
+    fn missing_implicit_plugins(&self) -> Vec<String>;
+
+    /// Format the load order as one line per plugin, giving its index, a `*`
+    /// if it is active, `L` for light masters or `M` for other masters, its
+    /// filename and, for timestamp-based games, its modification time in
+    /// seconds since the Unix epoch.
+    fn report(&self) -> String;
 }
 
 pub fn plugin_names(plugins: &[Plugin]) -> Vec<&str> {
     plugins.iter().map(Plugin::name).collect()
 }
 
+pub fn plugin_names_reversed(plugins: &[Plugin]) -> Vec<&str> {
+    plugins.iter().rev().map(Plugin::name).collect()
+}
+
+pub fn effective_load_order(plugins: &[Plugin], game_settings: &GameSettings) -> Vec<String> {
+    let implicitly_active_plugins = game_settings
+        .implicitly_active_plugins()
+        .iter()
+        .filter_map(|n| plugins.iter().find(|p| p.name_matches(n)));
+
+    let (masters, non_masters): (Vec<&Plugin>, Vec<&Plugin>) = plugins
+        .iter()
+        .filter(|p| p.is_active() && !game_settings.is_implicitly_active(p.name()))
+        .partition(|p| p.is_master_file());
+
+    implicitly_active_plugins
+        .chain(masters)
+        .chain(non_masters)
+        .map(|p| p.name().to_string())
+        .collect()
+}
+
 pub fn index_of(plugins: &[Plugin], plugin_name: &str) -> Option<usize> {
     plugins.iter().position(|p| p.name_matches(plugin_name))
 }
 
+/// Get the number of positions the plugin would have to move by to end up at
+/// the target index, which is negative if it would have to move backwards.
+pub fn move_delta(plugins: &[Plugin], plugin_name: &str, target: usize) -> Option<isize> {
+    if target >= plugins.len() {
+        return None;
+    }
+
+    index_of(plugins, plugin_name).map(|index| target as isize - index as isize)
+}
+
+pub fn active_index_of(plugins: &[Plugin], plugin_name: &str) -> Option<usize> {
+    plugins
+        .iter()
+        .filter(|p| p.is_active())
+        .position(|p| p.name_matches(plugin_name))
+}
+
 pub fn plugin_at(plugins: &[Plugin], index: usize) -> Option<&str> {
     plugins.get(index).map(Plugin::name)
 }
@@ -64,6 +256,113 @@ pub fn active_plugin_names(plugins: &[Plugin]) -> Vec<&str> {
         .collect()
 }
 
+pub fn active_master_names(plugins: &[Plugin]) -> Vec<&str> {
+    plugins
+        .iter()
+        .filter(|p| p.is_active() && p.is_master_file())
+        .map(Plugin::name)
+        .collect()
+}
+
+pub fn active_non_master_names(plugins: &[Plugin]) -> Vec<&str> {
+    plugins
+        .iter()
+        .filter(|p| p.is_active() && !p.is_master_file())
+        .map(Plugin::name)
+        .collect()
+}
+
+pub fn master_count(plugins: &[Plugin]) -> usize {
+    plugins.iter().filter(|p| p.is_master_file()).count()
+}
+
+pub fn non_master_count(plugins: &[Plugin]) -> usize {
+    plugins.iter().filter(|p| !p.is_master_file()).count()
+}
+
+pub fn light_count(plugins: &[Plugin]) -> usize {
+    plugins.iter().filter(|p| p.is_light_master_file()).count()
+}
+
+pub fn report(plugins: &[Plugin], game_settings: &GameSettings) -> String {
+    let show_timestamps = game_settings.load_order_method() == LoadOrderMethod::Timestamp;
+
+    plugins
+        .iter()
+        .enumerate()
+        .map(|(index, plugin)| {
+            let active = if plugin.is_active() { '*' } else { ' ' };
+            let kind = if plugin.is_light_master_file() {
+                'L'
+            } else if plugin.is_master_file() {
+                'M'
+            } else {
+                ' '
+            };
+
+            let mut line = format!("{:>3} {} {} {}", index, active, kind, plugin.name());
+            if show_timestamps {
+                let seconds = plugin
+                    .modification_time()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                line.push_str(&format!(" {}", seconds));
+            }
+            line.push('\n');
+
+            line
+        })
+        .collect()
+}
+
+pub fn active_count_status(plugins: &[Plugin], game_settings: &GameSettings) -> ActiveCountStatus {
+    let count = plugins
+        .iter()
+        .filter(|p| !p.is_light_master_file() && p.is_active())
+        .count();
+    let limit = game_settings.max_active_normal_plugins();
+    let threshold = game_settings
+        .active_plugins_warning_threshold()
+        .unwrap_or(limit * 9 / 10);
+
+    ActiveCountStatus {
+        count,
+        limit,
+        over_warning_threshold: count > threshold,
+        over_limit: count > limit,
+    }
+}
+
+pub fn remaining_slots(plugins: &[Plugin], game_settings: &GameSettings) -> (usize, usize) {
+    let normal_count = plugins
+        .iter()
+        .filter(|p| !p.is_light_master_file() && p.is_active())
+        .count();
+    let normal_slots = game_settings
+        .max_active_normal_plugins()
+        .saturating_sub(normal_count);
+
+    let light_slots = if game_settings.supports_light_masters() {
+        let light_count = plugins
+            .iter()
+            .filter(|p| p.is_light_master_file() && p.is_active())
+            .count();
+        MAX_ACTIVE_LIGHT_MASTERS.saturating_sub(light_count)
+    } else {
+        0
+    };
+
+    (normal_slots, light_slots)
+}
+
+pub fn active_plugins_as_written(plugins: &[Plugin]) -> Vec<String> {
+    active_plugin_names(plugins)
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
 pub fn is_active(plugins: &[Plugin], plugin_name: &str) -> bool {
     plugins
         .iter()
@@ -71,233 +370,1243 @@ pub fn is_active(plugins: &[Plugin], plugin_name: &str) -> bool {
         .map_or(false, |p| p.is_active())
 }
 
-pub trait ReadableLoadOrderExt: ReadableLoadOrder + Sync {
-    fn plugins(&self) -> &Vec<Plugin>;
+pub fn plugin_status(
+    plugins: &[Plugin],
+    game_settings: &GameSettings,
+    plugin_name: &str,
+) -> PluginStatus {
+    if index_of(plugins, plugin_name).is_some() {
+        return PluginStatus::Loaded;
+    }
 
-    fn count_active_normal_plugins(&self) -> usize {
-        self.plugins()
-            .iter()
-            .filter(|p| !p.is_light_master_file() && p.is_active())
-            .count()
+    let path = game_settings
+        .plugin_path(trim_dot_ghost(plugin_name))
+        .resolve_path();
+
+    match path {
+        Ok(ref p) if p.is_ghosted() => PluginStatus::Ghosted,
+        Ok(_) => PluginStatus::NotLoaded,
+        Err(_) => PluginStatus::NotInstalled,
     }
+}
 
-    fn count_active_light_masters(&self) -> usize {
-        self.plugins()
-            .iter()
-            .filter(|p| p.is_light_master_file() && p.is_active())
-            .count()
+pub fn excess_active_plugins<'a>(
+    plugins: &'a [Plugin],
+    game_settings: &GameSettings,
+) -> Vec<&'a str> {
+    excess_active_plugin_indices(
+        plugins,
+        game_settings.implicitly_active_plugins(),
+        game_settings.max_active_normal_plugins(),
+    )
+        .into_iter()
+        .rev()
+        .map(|i| plugins[i].name())
+        .collect()
+}
+
+pub fn master_file_present(plugins: &[Plugin], master_file: &str) -> bool {
+    index_of(plugins, master_file).is_some()
+}
+
+pub fn valid_index_range(
+    plugins: &[Plugin],
+    plugin_name: &str,
+    game_settings: &GameSettings,
+) -> Result<Range<usize>, Error> {
+    let current_index = index_of(plugins, plugin_name);
+    let is_master = match current_index {
+        Some(index) => plugins[index].is_master_file(),
+        None => Plugin::new(plugin_name, game_settings)
+            .map_err(|_| Error::InvalidPlugin(plugin_name.to_string()))?
+            .is_master_file(),
+    };
+
+    if eq(trim_dot_ghost(plugin_name), game_settings.master_file()) {
+        return Ok(0..1);
     }
 
-    fn find_plugins_in_dir(&self) -> Vec<String> {
-        let entries = match read_dir(&self.game_settings().plugins_directory()) {
-            Ok(x) => x,
-            _ => return Vec::new(),
-        };
+    // Positions are counted after the plugin is removed from its current
+    // position, as that's how they're interpreted when it's moved.
+    let other_plugins: Vec<&Plugin> = plugins
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| Some(i) != current_index)
+        .map(|(_, p)| p)
+        .collect();
 
-        let mut set: HashSet<String> = HashSet::new();
+    let start = other_plugins
+        .iter()
+        .take_while(|p| game_settings.is_hardcoded_plugin(p.name()))
+        .count();
+    let first_non_master_position = other_plugins
+        .iter()
+        .position(|p| !p.is_master_file() && !p.is_light_master_file())
+        .unwrap_or(other_plugins.len());
 
-        entries
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().map(|f| f.is_file()).unwrap_or(false))
-            .filter_map(|e| e.file_name().to_str().and_then(|f| Some(f.to_owned())))
-            .filter(|ref filename| set.insert(trim_dot_ghost(&filename).to_lowercase()))
-            .collect()
+    if is_master {
+        Ok(start..first_non_master_position + 1)
+    } else {
+        Ok(first_non_master_position.max(start)..other_plugins.len() + 1)
     }
+}
 
-    fn find_plugins_in_dir_sorted(&self) -> Vec<String> {
-        let mut filenames = self.find_plugins_in_dir();
-        filenames.sort();
+pub fn is_override_only(plugins: &[Plugin], plugin_name: &str) -> Result<bool, Error> {
+    plugins
+        .iter()
+        .find(|p| p.name_matches(plugin_name))
+        .ok_or_else(|| Error::PluginNotFound(plugin_name.to_string()))
+        .and_then(Plugin::is_override_only)
+}
+
+pub fn master_indices(
+    plugins: &[Plugin],
+    plugin_name: &str,
+) -> Result<Vec<(String, Option<usize>)>, Error> {
+    let plugin = plugins
+        .iter()
+        .find(|p| p.name_matches(plugin_name))
+        .ok_or_else(|| Error::PluginNotFound(plugin_name.to_string()))?;
+
+    Ok(plugin
+        .masters()
+        .iter()
+        .map(|m| (m.clone(), index_of(plugins, m)))
+        .collect())
+}
+
+pub fn orphaned_plugins(plugins: &[Plugin]) -> Result<Vec<String>, Error> {
+    let mut has_missing_masters = vec![false; plugins.len()];
+    let mut has_dependents = vec![false; plugins.len()];
+
+    for (index, plugin) in plugins.iter().enumerate() {
+        for master in plugin.masters() {
+            match index_of(plugins, master) {
+                Some(i) => has_dependents[i] = true,
+                None => has_missing_masters[index] = true,
+            }
+        }
+    }
+
+    Ok(plugins
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| has_missing_masters[*i] && !has_dependents[*i])
+        .map(|(_, p)| p.name().to_string())
+        .collect())
+}
+
+pub fn master_dependency_cycles(plugins: &[Plugin]) -> Vec<Vec<String>> {
+    let masters: Vec<Vec<usize>> = plugins
+        .iter()
+        .map(|plugin| {
+            plugin
+                .masters()
+                .iter()
+                .filter_map(|m| index_of(plugins, m))
+                .collect()
+        })
+        .collect();
+
+    let mut search = CycleSearch::new(plugins.len());
+    for index in 0..plugins.len() {
+        if search.indices[index].is_none() {
+            search.visit(index, &masters);
+        }
+    }
+
+    search
+        .components
+        .into_iter()
+        .filter(|c| c.len() > 1 || masters[c[0]].contains(&c[0]))
+        .map(|mut c| {
+            c.sort();
+            c.into_iter().map(|i| plugins[i].name().to_string()).collect()
+        })
+        .collect()
+}
+
+// Tarjan's strongly connected components algorithm, used to find groups of
+// plugins that (directly or indirectly) depend on each other as masters.
+struct CycleSearch {
+    next_index: usize,
+    indices: Vec<Option<usize>>,
+    low_links: Vec<usize>,
+    stack: Vec<usize>,
+    on_stack: Vec<bool>,
+    components: Vec<Vec<usize>>,
+}
+
+impl CycleSearch {
+    fn new(size: usize) -> CycleSearch {
+        CycleSearch {
+            next_index: 0,
+            indices: vec![None; size],
+            low_links: vec![0; size],
+            stack: Vec::new(),
+            on_stack: vec![false; size],
+            components: Vec::new(),
+        }
+    }
+
+    fn visit(&mut self, node: usize, edges: &[Vec<usize>]) {
+        self.indices[node] = Some(self.next_index);
+        self.low_links[node] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for &successor in &edges[node] {
+            match self.indices[successor] {
+                None => {
+                    self.visit(successor, edges);
+                    self.low_links[node] = self.low_links[node].min(self.low_links[successor]);
+                }
+                Some(i) if self.on_stack[successor] => {
+                    self.low_links[node] = self.low_links[node].min(i);
+                }
+                _ => {}
+            }
+        }
+
+        if Some(self.low_links[node]) == self.indices[node] {
+            let mut component = Vec::new();
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+pub fn is_ordering_valid(plugins: &[Plugin]) -> bool {
+    let plugin_pos = match find_first_non_master_position(plugins) {
+        None => return true,
+        Some(x) => x,
+    };
+    match plugins.iter().rposition(|p| p.is_master_file()) {
+        None => true,
+        Some(master_pos) => master_pos < plugin_pos,
+    }
+}
+
+pub fn plugin_has_associated_archive(plugin_name: &str, game_settings: &GameSettings) -> bool {
+    let archive_name = associated_archive_name(plugin_name, game_settings);
+
+    game_settings.plugin_path(&archive_name).exists()
+}
+
+pub fn group_by_archive(
+    plugins: &[Plugin],
+    game_settings: &GameSettings,
+) -> Vec<(String, Vec<String>)> {
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
 
-        filenames
+    for plugin in plugins {
+        if !plugin_has_associated_archive(plugin.name(), game_settings) {
+            groups.push((plugin.name().to_string(), vec![plugin.name().to_string()]));
+            continue;
+        }
+
+        let archive_name = associated_archive_name(plugin.name(), game_settings);
+        match groups.iter_mut().find(|g| eq(&g.0, &archive_name)) {
+            Some(group) => group.1.push(plugin.name().to_string()),
+            None => groups.push((archive_name, vec![plugin.name().to_string()])),
+        }
+    }
+
+    groups
+}
+
+fn associated_archive_name(plugin_name: &str, game_settings: &GameSettings) -> String {
+    let plugin_name = trim_dot_ghost(plugin_name);
+    let stem = match plugin_name.rfind('.') {
+        Some(x) => &plugin_name[..x],
+        None => plugin_name,
+    };
+
+    format!("{}{}", stem, game_settings.archive_extension())
+}
+
+/// Hashes the ordered plugin names and active states using FNV-1a, which
+/// unlike the standard library's default hasher gives the same result across
+/// runs. Names are lowercased so that case differences don't change the hash.
+pub fn content_hash(plugins: &[Plugin]) -> u64 {
+    plugins.iter().fold(FNV_OFFSET_BASIS, |hash, plugin| {
+        let hash = fnv1a(hash, plugin.name().to_lowercase().as_bytes());
+        fnv1a(hash, &[0, plugin.is_active() as u8])
+    })
+}
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Group plugins that have identical file content, omitting any plugins that
+/// aren't duplicated. Groups and the plugins in them are in load order. Only
+/// plugins with the same file size have their content checksummed, and any
+/// plugins that can't be read are left out.
+pub fn duplicate_content_plugins(plugins: &[Plugin]) -> Result<Vec<Vec<String>>, Error> {
+    let sizes: Vec<Option<u64>> = plugins
+        .par_iter()
+        .map(|plugin| plugin.file_size().ok())
+        .collect();
+
+    let mut size_counts: HashMap<u64, usize> = HashMap::new();
+    for size in sizes.iter().filter_map(|s| *s) {
+        *size_counts.entry(size).or_insert(0) += 1;
+    }
+
+    let keys: Vec<Option<(u64, u32)>> = plugins
+        .par_iter()
+        .zip(sizes.par_iter())
+        .map(|(plugin, size)| match *size {
+            Some(size) if size_counts[&size] > 1 => plugin.crc().ok().map(|crc| (size, crc)),
+            _ => None,
+        })
+        .collect();
+
+    let mut group_indices: HashMap<(u64, u32), usize> = HashMap::new();
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    let keyed_plugins = plugins.iter().zip(keys).filter_map(|(p, k)| k.map(|k| (p, k)));
+    for (plugin, key) in keyed_plugins {
+        let index = *group_indices.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[index].push(plugin.name().to_string());
+    }
+
+    Ok(groups.into_iter().filter(|group| group.len() > 1).collect())
+}
+
+pub fn mismatched_extension_plugins(plugins: &[Plugin]) -> Vec<String> {
+    plugins
+        .iter()
+        .filter(|p| {
+            let name = p.name().to_lowercase();
+            match p.master_flag() {
+                Some(true) => name.ends_with(".esp"),
+                Some(false) => name.ends_with(".esm"),
+                None => false,
+            }
+        })
+        .map(|p| p.name().to_string())
+        .collect()
+}
+
+pub fn plugin_tier(
+    plugins: &[Plugin],
+    game_settings: &GameSettings,
+    plugin_name: &str,
+) -> Option<PluginTier> {
+    match plugins.iter().find(|p| p.name_matches(plugin_name)) {
+        Some(plugin) => Some(tier(plugin)),
+        None => Plugin::new(plugin_name, game_settings)
+            .ok()
+            .map(|plugin| tier(&plugin)),
+    }
+}
+
+fn tier(plugin: &Plugin) -> PluginTier {
+    if plugin.is_light_master_file() {
+        PluginTier::Light
+    } else {
+        PluginTier::Full
+    }
+}
+
+pub fn flat_entries(plugins: &[Plugin]) -> Vec<LoadOrderEntry<'_>> {
+    plugins
+        .iter()
+        .enumerate()
+        .map(|(index, plugin)| LoadOrderEntry {
+            index,
+            name: plugin.name(),
+            tier: tier(plugin),
+            active: plugin.is_active(),
+        })
+        .collect()
+}
+
+pub fn is_master_plugin(
+    plugins: &[Plugin],
+    game_settings: &GameSettings,
+    plugin_name: &str,
+) -> Option<bool> {
+    match plugins.iter().find(|p| p.name_matches(plugin_name)) {
+        Some(plugin) => Some(plugin.is_master_file()),
+        None => Plugin::new(plugin_name, game_settings)
+            .ok()
+            .map(|plugin| plugin.is_master_file()),
     }
+}
+
+fn adjacent_masters(game_id: GameId) -> &'static [(&'static str, &'static str)] {
+    match game_id {
+        GameId::Skyrim | GameId::SkyrimSE | GameId::SkyrimVR => SKYRIM_ADJACENT_MASTERS,
+        _ => &[],
+    }
+}
 
-    fn get_excess_active_plugin_indices(&self) -> Vec<usize> {
-        let implicitly_active_plugins = self.game_settings().implicitly_active_plugins();
-        let mut normal_active_count = self.count_active_normal_plugins();
-        let mut light_master_active_count = self.count_active_light_masters();
+pub fn validate(plugins: &[Plugin], game_id: GameId) -> Vec<ValidationWarning> {
+    adjacent_masters(game_id)
+        .iter()
+        .filter(|&&(first, second)| {
+            match (index_of(plugins, first), index_of(plugins, second)) {
+                (Some(first_index), Some(second_index)) => second_index != first_index + 1,
+                _ => false,
+            }
+        })
+        .map(|&(first, second)| ValidationWarning::MastersNotAdjacent {
+            first: first.to_string(),
+            second: second.to_string(),
+        })
+        .collect()
+}
+
+/// Get the implicitly active plugins that aren't installed. Creation Club
+/// plugins are only implicitly active once installed, so can't be missing.
+pub fn missing_implicit_plugins(game_settings: &GameSettings) -> Vec<String> {
+    game_settings
+        .implicitly_active_plugins()
+        .iter()
+        .filter(|p| game_settings.plugin_path(p).resolve_path().is_err())
+        .cloned()
+        .collect()
+}
+
+pub fn record_types(plugins: &[Plugin], plugin_name: &str) -> Result<HashSet<[u8; 4]>, Error> {
+    plugins
+        .iter()
+        .find(|p| p.name_matches(plugin_name))
+        .ok_or_else(|| Error::PluginNotFound(plugin_name.to_string()))
+        .and_then(Plugin::record_types)
+}
+
+pub fn is_blueprint_master(plugins: &[Plugin], plugin_name: &str) -> Option<bool> {
+    plugins
+        .iter()
+        .find(|p| p.name_matches(plugin_name))
+        .map(Plugin::is_blueprint_master)
+}
+
+pub fn form_version(plugins: &[Plugin], plugin_name: &str) -> Option<u16> {
+    plugins
+        .iter()
+        .find(|p| p.name_matches(plugin_name))
+        .and_then(Plugin::form_version)
+}
+
+pub fn write_mo2_modlist(plugins: &[Plugin], writer: &mut dyn Write) -> Result<(), Error> {
+    for plugin in plugins.iter().rev() {
+        let prefix = if plugin.is_active() { '+' } else { '-' };
+        writeln!(writer, "{}{}", prefix, plugin.name())?;
+    }
+
+    Ok(())
+}
+
+pub fn read_active_plugins_file_raw(game_settings: &GameSettings) -> Result<Vec<u8>, Error> {
+    let path = game_settings.active_plugins_file();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut content = Vec::new();
+    File::open(path)?.read_to_end(&mut content)?;
+
+    Ok(content)
+}
+
+pub trait ReadableLoadOrderExt: ReadableLoadOrder + Sync {
+    fn plugins(&self) -> &Vec<Plugin>;
+
+    fn count_active_normal_plugins(&self) -> usize {
+        self.plugins()
+            .iter()
+            .filter(|p| !p.is_light_master_file() && p.is_active())
+            .count()
+    }
+
+    fn count_active_light_masters(&self) -> usize {
+        self.plugins()
+            .iter()
+            .filter(|p| p.is_light_master_file() && p.is_active())
+            .count()
+    }
+
+    fn find_plugins_in_dir(&self) -> Result<Vec<String>, Error> {
+        let game_settings = self.game_settings();
+        let directories = game_settings
+            .additional_plugins_directories()
+            .iter()
+            .rev()
+            .map(|d| d.as_path())
+            .chain(once(game_settings.plugins_directory_path()));
+
+        let permission_denied = |e: &io::Error| e.kind() == io::ErrorKind::PermissionDenied;
+
+        let mut set: HashSet<String> = HashSet::new();
+        let mut filenames = Vec::new();
+        for directory in directories {
+            let entries = match read_dir(directory) {
+                Ok(entries) => entries,
+                Err(ref e) if permission_denied(e) => {
+                    return Err(Error::PermissionDenied(directory.to_path_buf()))
+                }
+                Err(_) => continue,
+            };
+
+            for entry in entries {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(ref e) if permission_denied(e) => {
+                        return Err(Error::PermissionDenied(directory.to_path_buf()))
+                    }
+                    Err(_) => continue,
+                };
+
+                let is_file = match entry.file_type() {
+                    Ok(file_type) => file_type.is_file(),
+                    Err(ref e) if permission_denied(e) => {
+                        return Err(Error::PermissionDenied(entry.path()))
+                    }
+                    Err(_) => false,
+                };
+
+                if let (true, Some(filename)) = (is_file, entry.file_name().to_str()) {
+                    if has_valid_extension(filename, game_settings)
+                        && !game_settings.is_quarantined(filename)
+                        && set.insert(trim_dot_ghost(filename).to_lowercase())
+                    {
+                        filenames.push(filename.to_owned());
+                    }
+                }
+            }
+        }
+
+        Ok(filenames)
+    }
+
+    fn find_plugins_in_dir_sorted(&self) -> Result<Vec<String>, Error> {
+        let mut filenames = self.find_plugins_in_dir()?;
+        filenames.sort();
+
+        Ok(filenames)
+    }
+
+    fn get_excess_active_plugin_indices(&self, max_active_normal_plugins: usize) -> Vec<usize> {
+        excess_active_plugin_indices(
+            self.plugins(),
+            self.game_settings().implicitly_active_plugins(),
+            max_active_normal_plugins,
+        )
+    }
+
+    fn validate_index(&self, index: usize, is_master: bool) -> Result<(), Error> {
+        match find_first_non_master_position(self.plugins()) {
+            None if !is_master && index < self.plugins().len() => Err(Error::NonMasterBeforeMaster),
+            Some(i) if is_master && index > i || !is_master && index < i => {
+                Err(Error::NonMasterBeforeMaster)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn map_to_plugins(&self, plugin_names: &[&str]) -> Result<Vec<Plugin>, Error> {
+        plugin_names
+            .par_iter()
+            .map(|n| to_plugin(n, self.plugins(), self.game_settings()))
+            .collect()
+    }
+
+    fn lookup_plugins(
+        &mut self,
+        active_plugin_names: &[&str],
+    ) -> Result<(Vec<usize>, Vec<Plugin>), Error> {
+        let (existing_plugin_indices, new_plugin_names): (Vec<usize>, Vec<&str>) =
+            active_plugin_names.into_par_iter().partition_map(|n| {
+                match self.plugins()
+                    .par_iter()
+                    .position_any(|p| p.name_matches(n))
+                {
+                    Some(x) => Either::Left(x),
+                    None => Either::Right(n),
+                }
+            });
+
+        let new_plugins = new_plugin_names
+            .into_par_iter()
+            .map(|n| {
+                Plugin::new(n, self.game_settings())
+                    .map_err(|_| Error::InvalidPlugin(n.to_string()))
+            })
+            .collect::<Result<Vec<Plugin>, Error>>()?;
+
+        Ok((existing_plugin_indices, new_plugins))
+    }
+
+    fn count_normal_plugins(
+        &mut self,
+        existing_plugin_indices: &[usize],
+        new_plugins: &[Plugin],
+    ) -> usize {
+        count_plugins(self.plugins(), existing_plugin_indices, new_plugins, false)
+    }
+
+    fn count_light_masters(
+        &mut self,
+        existing_plugin_indices: &[usize],
+        new_plugins: &[Plugin],
+    ) -> usize {
+        if self.game_settings().id().supports_light_masters() {
+            count_plugins(self.plugins(), existing_plugin_indices, new_plugins, true)
+        } else {
+            0
+        }
+    }
+}
+
+fn excess_active_plugin_indices(
+    plugins: &[Plugin],
+    implicitly_active_plugins: &[String],
+    max_active_normal_plugins: usize,
+) -> Vec<usize> {
+    // Light plugins (whether flagged or .esl) don't take up normal plugin
+    // slots, so each kind is only counted against its own limit.
+    let mut normal_active_count = plugins
+        .iter()
+        .filter(|p| !p.is_light_master_file() && p.is_active())
+        .count();
+    let mut light_master_active_count = plugins
+        .iter()
+        .filter(|p| p.is_light_master_file() && p.is_active())
+        .count();
+
+    let mut plugin_indices: Vec<usize> = Vec::new();
+    for (index, plugin) in plugins.iter().enumerate().rev() {
+        if normal_active_count <= max_active_normal_plugins
+            && light_master_active_count <= MAX_ACTIVE_LIGHT_MASTERS
+        {
+            break;
+        }
+        let can_deactivate = plugin.is_active()
+            && !implicitly_active_plugins
+                .iter()
+                .any(|i| plugin.name_matches(i));
+        if can_deactivate {
+            if plugin.is_light_master_file()
+                && light_master_active_count > MAX_ACTIVE_LIGHT_MASTERS
+            {
+                plugin_indices.push(index);
+                light_master_active_count -= 1;
+            } else if !plugin.is_light_master_file()
+                && normal_active_count > max_active_normal_plugins
+            {
+                plugin_indices.push(index);
+                normal_active_count -= 1;
+            }
+        }
+    }
+
+    plugin_indices
+}
+
+fn to_plugin(
+    plugin_name: &str,
+    existing_plugins: &[Plugin],
+    game_settings: &GameSettings,
+) -> Result<Plugin, Error> {
+    let existing_plugin = existing_plugins
+        .par_iter()
+        .find_any(|p| p.name_matches(plugin_name));
+
+    match existing_plugin {
+        None => Plugin::new(plugin_name, game_settings),
+        Some(x) => Ok(x.clone()),
+    }
+}
+
+fn count_plugins(
+    existing_plugins: &[Plugin],
+    existing_plugin_indices: &[usize],
+    new_plugins: &[Plugin],
+    count_light_masters: bool,
+) -> usize {
+    let new_count = new_plugins
+        .iter()
+        .filter(|p| p.is_light_master_file() == count_light_masters)
+        .count();
+
+    let existing_count = existing_plugin_indices
+        .into_iter()
+        .filter(|i| existing_plugins[**i].is_light_master_file() == count_light_masters)
+        .count();
+
+    new_count + existing_count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs::{create_dir_all, remove_file};
+    use std::io::Write;
+    use std::path::Path;
+
+    use tempfile::tempdir;
+
+    use enums::GameId;
+    use load_order::tests::mock_game_files;
+    use tests::{copy_to_test_dir, write_plugin};
+
+    fn prepare(game_dir: &Path) -> Vec<Plugin> {
+        let (_, plugins) = mock_game_files(GameId::Oblivion, game_dir);
+
+        plugins
+    }
+
+    fn prepare_with_ghosted_plugin(game_dir: &Path) -> Vec<Plugin> {
+        let (settings, mut plugins) = mock_game_files(GameId::Oblivion, game_dir);
+
+        copy_to_test_dir(
+            "Blank - Different.esm",
+            "Blank - Different.esm.ghost",
+            &settings,
+        );
+        plugins.insert(
+            1,
+            Plugin::new("Blank - Different.esm.ghost", &settings).unwrap(),
+        );
+
+        plugins
+    }
+
+    #[test]
+    fn duplicate_content_plugins_should_group_plugins_with_identical_content() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        plugins.push(Plugin::new("Blàñk.esp", &settings).unwrap());
+
+        let groups = duplicate_content_plugins(&plugins).unwrap();
+
+        assert_eq!(vec![vec!["Blank.esp", "Blàñk.esp"]], groups);
+    }
+
+    #[test]
+    fn duplicate_content_plugins_should_skip_plugins_that_cannot_be_read() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        plugins.push(Plugin::new("Blàñk.esp", &settings).unwrap());
+        plugins.push(Plugin::new("Blank.esm", &settings).unwrap());
+        remove_file(settings.plugins_directory().join("Blank.esm")).unwrap();
+
+        let groups = duplicate_content_plugins(&plugins).unwrap();
+
+        assert_eq!(vec![vec!["Blank.esp", "Blàñk.esp"]], groups);
+    }
+
+    #[test]
+    fn read_active_plugins_file_raw_should_return_an_empty_vec_if_the_file_does_not_exist() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, _) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        assert!(read_active_plugins_file_raw(&settings).unwrap().is_empty());
+    }
+
+    #[test]
+    fn read_active_plugins_file_raw_should_return_the_file_content_unchanged() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, _) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        let content = b"Blank.esp\r\nBl\xe0\xf1k.esp\n\xff";
+        create_dir_all(settings.active_plugins_file().parent().unwrap()).unwrap();
+        File::create(settings.active_plugins_file())
+            .unwrap()
+            .write_all(content)
+            .unwrap();
+
+        assert_eq!(
+            content.to_vec(),
+            read_active_plugins_file_raw(&settings).unwrap()
+        );
+    }
+
+    #[test]
+    fn is_master_plugin_should_check_loaded_and_installed_plugins() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        assert_eq!(Some(true), is_master_plugin(&plugins, &settings, "Oblivion.esm"));
+        assert_eq!(Some(false), is_master_plugin(&plugins, &settings, "Blank.esp"));
+        assert_eq!(Some(true), is_master_plugin(&plugins, &settings, "Blank.esm"));
+        assert_eq!(None, is_master_plugin(&plugins, &settings, "missing.esp"));
+    }
+
+    #[test]
+    fn write_mo2_modlist_should_write_plugins_in_reverse_order_with_active_state_prefixes() {
+        let tmp_dir = tempdir().unwrap();
+        let plugins = prepare(tmp_dir.path());
+
+        let mut output = Vec::new();
+        write_mo2_modlist(&plugins, &mut output).unwrap();
+
+        assert_eq!(
+            "-Blank - Different.esp\n+Blank.esp\n-Oblivion.esm\n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn validate_should_warn_if_a_master_loads_between_skyrim_esm_and_update_esm() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::Skyrim, tmp_dir.path());
+
+        copy_to_test_dir("Blank.esm", "Update.esm", &settings);
+        plugins.insert(1, Plugin::new("Blank.esm", &settings).unwrap());
+        plugins.insert(2, Plugin::new("Update.esm", &settings).unwrap());
+
+        assert_eq!(
+            vec![ValidationWarning::MastersNotAdjacent {
+                first: "Skyrim.esm".to_string(),
+                second: "Update.esm".to_string(),
+            }],
+            validate(&plugins, GameId::Skyrim)
+        );
+
+        plugins.swap(1, 2);
+
+        assert!(validate(&plugins, GameId::Skyrim).is_empty());
+    }
+
+    #[test]
+    fn validate_should_not_warn_for_games_without_master_adjacency_conventions() {
+        let tmp_dir = tempdir().unwrap();
+        let plugins = prepare(tmp_dir.path());
+
+        assert!(validate(&plugins, GameId::Oblivion).is_empty());
+    }
+
+    #[test]
+    fn master_indices_should_map_masters_to_their_load_order_indices() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        write_plugin("Dependent.esp", &["Blank.esm", "Missing.esm"], &[], &settings);
+        plugins.push(Plugin::new("Blank.esm", &settings).unwrap());
+        plugins.push(Plugin::new("Dependent.esp", &settings).unwrap());
+
+        let indices = master_indices(&plugins, "Dependent.esp").unwrap();
+
+        assert_eq!(
+            vec![
+                ("Blank.esm".to_string(), Some(plugins.len() - 2)),
+                ("Missing.esm".to_string(), None),
+            ],
+            indices
+        );
+    }
+
+    #[test]
+    fn master_indices_should_error_if_the_plugin_is_not_in_the_load_order() {
+        let tmp_dir = tempdir().unwrap();
+        let plugins = prepare(tmp_dir.path());
+
+        match master_indices(&plugins, "missing.esp").unwrap_err() {
+            Error::PluginNotFound(_) => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn move_delta_should_return_the_signed_distance_to_the_target_index() {
+        let tmp_dir = tempdir().unwrap();
+        let plugins = prepare(tmp_dir.path());
+
+        assert_eq!(Some(1), move_delta(&plugins, "Blank.esp", 2));
+        assert_eq!(Some(-2), move_delta(&plugins, "Blank - Different.esp", 0));
+        assert_eq!(Some(0), move_delta(&plugins, "Blank.esp", 1));
+    }
+
+    #[test]
+    fn move_delta_should_return_none_for_an_invalid_target_or_unknown_plugin() {
+        let tmp_dir = tempdir().unwrap();
+        let plugins = prepare(tmp_dir.path());
+
+        assert_eq!(None, move_delta(&plugins, "Blank.esp", 3));
+        assert_eq!(None, move_delta(&plugins, "missing.esp", 0));
+    }
+
+    #[test]
+    fn missing_implicit_plugins_should_return_implicitly_active_plugins_that_are_not_installed() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, _) = mock_game_files(GameId::Skyrim, tmp_dir.path());
+
+        copy_to_test_dir("Blank.esm", "Update.esm", &settings);
+        assert!(missing_implicit_plugins(&settings).is_empty());
+
+        remove_file(settings.plugins_directory().join("Update.esm")).unwrap();
+        assert_eq!(vec!["Update.esm"], missing_implicit_plugins(&settings));
+    }
+
+    #[test]
+    fn plugin_names_should_return_filenames_for_plugins_in_load_order() {
+        let tmp_dir = tempdir().unwrap();
+        let plugins = prepare(&tmp_dir.path());
+
+        let expected_plugin_names = vec!["Oblivion.esm", "Blank.esp", "Blank - Different.esp"];
+        assert_eq!(expected_plugin_names, plugin_names(&plugins));
+    }
+
+    #[test]
+    fn plugin_names_should_return_unghosted_filenames() {
+        let tmp_dir = tempdir().unwrap();
+        let plugins = prepare_with_ghosted_plugin(&tmp_dir.path());
+
+        let expected_plugin_names = vec![
+            "Oblivion.esm",
+            "Blank - Different.esm",
+            "Blank.esp",
+            "Blank - Different.esp",
+        ];
+        assert_eq!(expected_plugin_names, plugin_names(&plugins));
+    }
+
+    #[test]
+    fn valid_index_range_should_cover_the_master_block_after_the_game_master_for_a_master() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        assert_eq!(1..2, valid_index_range(&plugins, "Blank.esm", &settings).unwrap());
+    }
+
+    #[test]
+    fn valid_index_range_should_only_contain_index_zero_for_the_game_master() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        assert_eq!(0..1, valid_index_range(&plugins, "Oblivion.esm", &settings).unwrap());
+    }
+
+    #[test]
+    fn valid_index_range_should_exclude_the_first_non_master_index_for_a_loaded_master() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+        plugins.insert(1, Plugin::new("Blank.esm", &settings).unwrap());
+
+        assert_eq!(1..2, valid_index_range(&plugins, "Blank.esm", &settings).unwrap());
+    }
+
+    #[test]
+    fn valid_index_range_should_not_count_a_loaded_non_master_itself() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+        plugins.insert(1, Plugin::new("Blank.esm", &settings).unwrap());
+
+        assert_eq!(2..4, valid_index_range(&plugins, "Blank.esp", &settings).unwrap());
+    }
+
+    #[test]
+    fn valid_index_range_should_start_after_hardcoded_plugins() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::SkyrimSE, tmp_dir.path());
+        copy_to_test_dir("Blank.esm", "Update.esm", &settings);
+        plugins.insert(1, Plugin::new("Update.esm", &settings).unwrap());
+
+        assert_eq!(2..3, valid_index_range(&plugins, "Blank.esm", &settings).unwrap());
+        assert_eq!(2..4, valid_index_range(&plugins, "Blank.esp", &settings).unwrap());
+    }
+
+    #[test]
+    fn valid_index_range_should_start_after_the_master_block_for_a_non_master() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        assert_eq!(
+            1..4,
+            valid_index_range(&plugins, "Blank - Master Dependent.esp", &settings).unwrap()
+        );
+        assert_eq!(1..3, valid_index_range(&plugins, "Blank.esp", &settings).unwrap());
+    }
+
+    #[test]
+    fn valid_index_range_should_error_for_a_plugin_that_is_not_installed() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        assert!(valid_index_range(&plugins, "missing.esp", &settings).is_err());
+    }
+
+    #[test]
+    fn is_override_only_should_error_if_the_plugin_is_not_in_the_load_order() {
+        let tmp_dir = tempdir().unwrap();
+        let plugins = prepare(tmp_dir.path());
+
+        match is_override_only(&plugins, "missing.esp").unwrap_err() {
+            Error::PluginNotFound(ref x) if x == "missing.esp" => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn orphaned_plugins_should_only_include_broken_plugins_that_nothing_depends_on() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        write_plugin("Orphan.esp", &["Missing.esm"], &[], &settings);
+        write_plugin("Broken.esp", &["Missing.esm"], &[], &settings);
+        write_plugin("Dependent.esp", &["Broken.esp"], &[], &settings);
+        for plugin_name in &["Orphan.esp", "Broken.esp", "Dependent.esp"] {
+            plugins.push(Plugin::new(plugin_name, &settings).unwrap());
+        }
+
+        assert_eq!(vec!["Orphan.esp"], orphaned_plugins(&plugins).unwrap());
+    }
+
+    #[test]
+    fn effective_load_order_should_load_implicitly_active_plugins_first_then_active_masters() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::SkyrimSE, tmp_dir.path());
+
+        copy_to_test_dir("Blank.esm", "Update.esm", &settings);
+        copy_to_test_dir("Blank.esm", "Dawnguard.esm", &settings);
+        let plugin_names_to_insert = ["Blank.esm", "Dawnguard.esm", "Update.esm"];
+        for (index, plugin_name) in plugin_names_to_insert.iter().enumerate() {
+            let plugin = Plugin::with_active(plugin_name, &settings, true).unwrap();
+            plugins.insert(index + 1, plugin);
+        }
+
+        assert_eq!(
+            vec![
+                "Skyrim.esm",
+                "Blank.esm",
+                "Dawnguard.esm",
+                "Update.esm",
+                "Blank.esp",
+                "Blank - Different.esp",
+            ],
+            plugin_names(&plugins)
+        );
+        assert_eq!(
+            vec![
+                "Skyrim.esm",
+                "Update.esm",
+                "Dawnguard.esm",
+                "Blank.esm",
+                "Blank.esp",
+            ],
+            effective_load_order(&plugins, &settings)
+        );
+    }
+
+    #[test]
+    fn plugin_tier_should_classify_full_and_light_plugins_and_be_none_if_not_installed() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, plugins) = mock_game_files(GameId::SkyrimSE, tmp_dir.path());
+
+        copy_to_test_dir("Blank.esm", "Blank.esl", &settings);
+
+        assert_eq!(
+            Some(PluginTier::Full),
+            plugin_tier(&plugins, &settings, "Skyrim.esm")
+        );
+        assert_eq!(
+            Some(PluginTier::Full),
+            plugin_tier(&plugins, &settings, "Blank.esp")
+        );
+        assert_eq!(
+            Some(PluginTier::Light),
+            plugin_tier(&plugins, &settings, "Blank.esl")
+        );
+        assert_eq!(None, plugin_tier(&plugins, &settings, "missing.esp"));
+    }
+
+    #[test]
+    fn mismatched_extension_plugins_should_include_master_flagged_esps_and_unflagged_esms() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
 
-        let mut plugin_indices: Vec<usize> = Vec::new();
-        for (index, plugin) in self.plugins().iter().enumerate().rev() {
-            if normal_active_count <= MAX_ACTIVE_NORMAL_PLUGINS
-                && light_master_active_count <= MAX_ACTIVE_LIGHT_MASTERS
-            {
-                break;
-            }
-            let can_deactivate = plugin.is_active()
-                && !implicitly_active_plugins
-                    .iter()
-                    .any(|i| plugin.name_matches(i));
-            if can_deactivate {
-                if plugin.is_light_master_file()
-                    && light_master_active_count > MAX_ACTIVE_LIGHT_MASTERS
-                {
-                    plugin_indices.push(index);
-                    light_master_active_count -= 1;
-                } else if !plugin.is_light_master_file()
-                    && normal_active_count > MAX_ACTIVE_NORMAL_PLUGINS
-                {
-                    plugin_indices.push(index);
-                    normal_active_count -= 1;
-                }
-            }
+        copy_to_test_dir("Blank.esm", "Blank - Master.esp", &settings);
+        copy_to_test_dir("Blank.esp", "Blank - Plugin.esm", &settings);
+        for plugin_name in &["Blank - Master.esp", "Blank - Plugin.esm"] {
+            plugins.push(Plugin::new(plugin_name, &settings).unwrap());
         }
 
-        plugin_indices
+        assert_eq!(
+            vec!["Blank - Master.esp", "Blank - Plugin.esm"],
+            mismatched_extension_plugins(&plugins)
+        );
     }
 
-    fn validate_index(&self, index: usize, is_master: bool) -> Result<(), Error> {
-        match find_first_non_master_position(self.plugins()) {
-            None if !is_master && index < self.plugins().len() => Err(Error::NonMasterBeforeMaster),
-            Some(i) if is_master && index > i || !is_master && index < i => {
-                Err(Error::NonMasterBeforeMaster)
-            }
-            _ => Ok(()),
+    #[test]
+    fn flat_entries_should_match_the_individual_accessors() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::SkyrimSE, tmp_dir.path());
+
+        copy_to_test_dir("Blank.esm", "Blank.esl", &settings);
+        plugins.push(Plugin::with_active("Blank.esl", &settings, true).unwrap());
+
+        let entries = flat_entries(&plugins);
+
+        assert_eq!(plugin_names(&plugins).len(), entries.len());
+        for entry in entries {
+            assert_eq!(Some(entry.name), plugin_at(&plugins, entry.index));
+            assert_eq!(Some(entry.index), index_of(&plugins, entry.name));
+            assert_eq!(
+                plugin_tier(&plugins, &settings, entry.name),
+                Some(entry.tier)
+            );
+            assert_eq!(is_active(&plugins, entry.name), entry.active);
         }
+        assert_eq!(PluginTier::Light, flat_entries(&plugins)[3].tier);
     }
 
-    fn map_to_plugins(&self, plugin_names: &[&str]) -> Result<Vec<Plugin>, Error> {
-        plugin_names
-            .par_iter()
-            .map(|n| to_plugin(n, self.plugins(), self.game_settings()))
-            .collect()
+    #[test]
+    fn master_dependency_cycles_should_be_empty_if_there_are_no_cycles() {
+        let tmp_dir = tempdir().unwrap();
+        let plugins = prepare(tmp_dir.path());
+
+        assert!(master_dependency_cycles(&plugins).is_empty());
     }
 
-    fn lookup_plugins(
-        &mut self,
-        active_plugin_names: &[&str],
-    ) -> Result<(Vec<usize>, Vec<Plugin>), Error> {
-        let (existing_plugin_indices, new_plugin_names): (Vec<usize>, Vec<&str>) =
-            active_plugin_names.into_par_iter().partition_map(|n| {
-                match self.plugins()
-                    .par_iter()
-                    .position_any(|p| p.name_matches(n))
-                {
-                    Some(x) => Either::Left(x),
-                    None => Either::Right(n),
-                }
-            });
+    #[test]
+    fn master_dependency_cycles_should_find_plugins_that_are_each_others_masters() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
 
-        let new_plugins = new_plugin_names
-            .into_par_iter()
-            .map(|n| {
-                Plugin::new(n, self.game_settings())
-                    .map_err(|_| Error::InvalidPlugin(n.to_string()))
-            })
-            .collect::<Result<Vec<Plugin>, Error>>()?;
+        write_plugin("A.esp", &["B.esp"], &[], &settings);
+        write_plugin("B.esp", &["A.esp"], &[], &settings);
+        write_plugin("C.esp", &["A.esp"], &[], &settings);
+        plugins.push(Plugin::new("A.esp", &settings).unwrap());
+        plugins.push(Plugin::new("B.esp", &settings).unwrap());
+        plugins.push(Plugin::new("C.esp", &settings).unwrap());
 
-        Ok((existing_plugin_indices, new_plugins))
+        let expected_cycles = vec![vec!["A.esp".to_string(), "B.esp".to_string()]];
+        assert_eq!(expected_cycles, master_dependency_cycles(&plugins));
     }
 
-    fn count_normal_plugins(
-        &mut self,
-        existing_plugin_indices: &[usize],
-        new_plugins: &[Plugin],
-    ) -> usize {
-        count_plugins(self.plugins(), existing_plugin_indices, new_plugins, false)
-    }
+    #[test]
+    fn is_ordering_valid_should_be_true_if_masters_load_before_non_masters() {
+        let tmp_dir = tempdir().unwrap();
+        let plugins = prepare(tmp_dir.path());
 
-    fn count_light_masters(
-        &mut self,
-        existing_plugin_indices: &[usize],
-        new_plugins: &[Plugin],
-    ) -> usize {
-        if self.game_settings().id().supports_light_masters() {
-            count_plugins(self.plugins(), existing_plugin_indices, new_plugins, true)
-        } else {
-            0
-        }
+        assert!(is_ordering_valid(&plugins));
     }
-}
 
-fn to_plugin(
-    plugin_name: &str,
-    existing_plugins: &[Plugin],
-    game_settings: &GameSettings,
-) -> Result<Plugin, Error> {
-    let existing_plugin = existing_plugins
-        .par_iter()
-        .find_any(|p| p.name_matches(plugin_name));
+    #[test]
+    fn is_ordering_valid_should_be_false_if_a_non_master_loads_before_a_master() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
 
-    match existing_plugin {
-        None => Plugin::new(plugin_name, game_settings),
-        Some(x) => Ok(x.clone()),
+        plugins.push(Plugin::new("Blank.esm", &settings).unwrap());
+
+        assert!(!is_ordering_valid(&plugins));
     }
-}
 
-fn count_plugins(
-    existing_plugins: &[Plugin],
-    existing_plugin_indices: &[usize],
-    new_plugins: &[Plugin],
-    count_light_masters: bool,
-) -> usize {
-    let new_count = new_plugins
-        .iter()
-        .filter(|p| p.is_light_master_file() == count_light_masters)
-        .count();
+    #[test]
+    fn plugin_has_associated_archive_should_be_true_if_an_archive_with_the_same_stem_exists() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, _) = mock_game_files(GameId::Oblivion, tmp_dir.path());
 
-    let existing_count = existing_plugin_indices
-        .into_iter()
-        .filter(|i| existing_plugins[**i].is_light_master_file() == count_light_masters)
-        .count();
+        File::create(settings.plugins_directory().join("Blank.bsa")).unwrap();
 
-    new_count + existing_count
-}
+        assert!(plugin_has_associated_archive("Blank.esp", &settings));
+        assert!(plugin_has_associated_archive("Blank.esm.ghost", &settings));
+        assert!(!plugin_has_associated_archive("Blank - Different.esp", &settings));
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn plugin_has_associated_archive_should_find_archives_in_additional_plugins_directories() {
+        let tmp_dir = tempdir().unwrap();
+        let (mut settings, _) = mock_game_files(GameId::Oblivion, tmp_dir.path());
 
-    use std::path::Path;
+        let directory = tmp_dir.path().join("mod1");
+        create_dir_all(&directory).unwrap();
+        File::create(directory.join("blank.bsa")).unwrap();
+        settings.set_additional_plugins_directories(vec![directory]);
 
-    use tempfile::tempdir;
+        assert!(plugin_has_associated_archive("Blank.esp", &settings));
+        assert!(!plugin_has_associated_archive("Blank - Different.esp", &settings));
+    }
 
-    use enums::GameId;
-    use load_order::tests::mock_game_files;
-    use tests::copy_to_test_dir;
+    #[test]
+    fn plugin_has_associated_archive_should_use_the_game_archive_extension() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, _) = mock_game_files(GameId::Fallout4, tmp_dir.path());
 
-    fn prepare(game_dir: &Path) -> Vec<Plugin> {
-        let (_, plugins) = mock_game_files(GameId::Oblivion, game_dir);
+        File::create(settings.plugins_directory().join("Blank.bsa")).unwrap();
+        assert!(!plugin_has_associated_archive("Blank.esp", &settings));
 
-        plugins
+        File::create(settings.plugins_directory().join("Blank.ba2")).unwrap();
+        assert!(plugin_has_associated_archive("Blank.esp", &settings));
     }
 
-    fn prepare_with_ghosted_plugin(game_dir: &Path) -> Vec<Plugin> {
-        let (settings, mut plugins) = mock_game_files(GameId::Oblivion, game_dir);
-
-        copy_to_test_dir(
-            "Blank - Different.esm",
-            "Blank - Different.esm.ghost",
-            &settings,
-        );
-        plugins.insert(
-            1,
-            Plugin::new("Blank - Different.esm.ghost", &settings).unwrap(),
+    #[test]
+    fn group_by_archive_should_group_plugins_that_share_an_archive() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        plugins.insert(1, Plugin::new("Blank.esm", &settings).unwrap());
+        File::create(settings.plugins_directory().join("Blank.bsa")).unwrap();
+
+        let groups = group_by_archive(&plugins, &settings);
+
+        assert_eq!(
+            vec![
+                ("Oblivion.esm".to_string(), vec!["Oblivion.esm".to_string()]),
+                (
+                    "Blank.bsa".to_string(),
+                    vec!["Blank.esm".to_string(), "Blank.esp".to_string()],
+                ),
+                (
+                    "Blank - Different.esp".to_string(),
+                    vec!["Blank - Different.esp".to_string()],
+                ),
+            ],
+            groups
         );
+    }
 
-        plugins
+    #[test]
+    fn active_index_of_should_count_only_active_plugins() {
+        let tmp_dir = tempdir().unwrap();
+        let mut plugins = prepare(tmp_dir.path());
+
+        plugins[0].activate().unwrap();
+        plugins[2].activate().unwrap();
+
+        assert_eq!(Some(0), active_index_of(&plugins, "Oblivion.esm"));
+        assert_eq!(Some(1), active_index_of(&plugins, "Blank.esp"));
+        assert_eq!(Some(2), active_index_of(&plugins, "blank - different.esp"));
     }
 
     #[test]
-    fn plugin_names_should_return_filenames_for_plugins_in_load_order() {
+    fn active_index_of_should_return_none_for_inactive_or_missing_plugins() {
         let tmp_dir = tempdir().unwrap();
-        let plugins = prepare(&tmp_dir.path());
+        let plugins = prepare(tmp_dir.path());
 
-        let expected_plugin_names = vec!["Oblivion.esm", "Blank.esp", "Blank - Different.esp"];
-        assert_eq!(expected_plugin_names, plugin_names(&plugins));
+        assert_eq!(None, active_index_of(&plugins, "Blank - Different.esp"));
+        assert_eq!(None, active_index_of(&plugins, "missing.esp"));
     }
 
     #[test]
-    fn plugin_names_should_return_unghosted_filenames() {
+    fn plugin_names_reversed_should_return_the_reverse_of_plugin_names() {
         let tmp_dir = tempdir().unwrap();
-        let plugins = prepare_with_ghosted_plugin(&tmp_dir.path());
+        let plugins = prepare_with_ghosted_plugin(tmp_dir.path());
 
-        let expected_plugin_names = vec![
-            "Oblivion.esm",
-            "Blank - Different.esm",
-            "Blank.esp",
-            "Blank - Different.esp",
-        ];
-        assert_eq!(expected_plugin_names, plugin_names(&plugins));
+        let mut expected_plugin_names = plugin_names(&plugins);
+        expected_plugin_names.reverse();
+
+        assert_eq!(expected_plugin_names, plugin_names_reversed(&plugins));
     }
 
     #[test]
@@ -348,6 +1657,201 @@ mod tests {
         assert_eq!("Blank - Different.esm", plugin_at(&plugins, 1).unwrap());
     }
 
+    #[test]
+    fn master_non_master_and_light_counts_should_count_plugins_by_type() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::SkyrimSE, tmp_dir.path());
+
+        copy_to_test_dir("Blank.esl", "Blank.esl", &settings);
+        copy_to_test_dir("Blank.esl", "Light.esp", &settings);
+        for plugin_name in &["Blank.esm", "Blank.esl", "Light.esp"] {
+            plugins.push(Plugin::new(plugin_name, &settings).unwrap());
+        }
+
+        assert_eq!(3, master_count(&plugins));
+        assert_eq!(3, non_master_count(&plugins));
+        assert_eq!(2, light_count(&plugins));
+    }
+
+    #[test]
+    fn report_should_list_index_active_marker_type_and_name_for_each_plugin() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::SkyrimSE, tmp_dir.path());
+
+        copy_to_test_dir("Blank.esl", "Blank.esl", &settings);
+        plugins.push(Plugin::with_active("Blank.esl", &settings, true).unwrap());
+
+        let expected = concat!(
+            "  0   M Skyrim.esm\n",
+            "  1 *   Blank.esp\n",
+            "  2     Blank - Different.esp\n",
+            "  3 * L Blank.esl\n"
+        );
+        assert_eq!(expected, report(&plugins, &settings));
+    }
+
+    #[test]
+    fn report_should_include_modification_times_for_timestamp_based_games() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        let seconds: Vec<u64> = plugins
+            .iter()
+            .map(|p| {
+                p.modification_time()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs()
+            })
+            .collect();
+
+        let expected = format!(
+            concat!(
+                "  0   M Oblivion.esm {}\n",
+                "  1 *   Blank.esp {}\n",
+                "  2     Blank - Different.esp {}\n"
+            ),
+            seconds[0], seconds[1], seconds[2]
+        );
+        assert_eq!(expected, report(&plugins, &settings));
+    }
+
+    #[test]
+    fn active_master_and_non_master_names_should_split_the_active_plugins() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::SkyrimSE, tmp_dir.path());
+
+        copy_to_test_dir("Blank.esl", "Blank.esl", &settings);
+        plugins.push(Plugin::with_active("Blank.esm", &settings, true).unwrap());
+        plugins.push(Plugin::with_active("Blank.esl", &settings, true).unwrap());
+        plugins.push(Plugin::new("Blank - Master Dependent.esp", &settings).unwrap());
+        plugins[0].activate().unwrap();
+
+        assert_eq!(
+            vec!["Skyrim.esm", "Blank.esm", "Blank.esl"],
+            active_master_names(&plugins)
+        );
+        assert_eq!(vec!["Blank.esp"], active_non_master_names(&plugins));
+    }
+
+    #[test]
+    fn remaining_slots_should_subtract_active_normal_and_light_plugins_from_the_limits() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::SkyrimSE, tmp_dir.path());
+
+        copy_to_test_dir("Blank.esm", "Blank.esl", &settings);
+        let light_plugin = Plugin::with_active("Blank.esl", &settings, true).unwrap();
+        plugins.extend(vec![light_plugin; 10]);
+
+        let normal_plugin = Plugin::with_active("Blank - Different.esp", &settings, true).unwrap();
+        plugins.extend(vec![normal_plugin; 100]);
+
+        assert_eq!((154, 4086), remaining_slots(&plugins, &settings));
+    }
+
+    #[test]
+    fn remaining_slots_should_have_no_light_slots_for_games_without_light_masters() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        assert_eq!((254, 0), remaining_slots(&plugins, &settings));
+    }
+
+    #[test]
+    fn remaining_slots_should_have_no_light_slots_for_game_versions_without_light_masters() {
+        let tmp_dir = tempdir().unwrap();
+        let (mut settings, plugins) = mock_game_files(GameId::SkyrimSE, tmp_dir.path());
+
+        settings.set_game_version(Some((1, 4, 2)));
+
+        assert_eq!(0, remaining_slots(&plugins, &settings).1);
+    }
+
+    #[test]
+    fn active_count_status_should_warn_when_the_count_crosses_the_threshold() {
+        let tmp_dir = tempdir().unwrap();
+        let (mut settings, mut plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        let active_plugin = Plugin::with_active("Blank - Different.esp", &settings, true).unwrap();
+        plugins.extend(vec![active_plugin.clone(); 228]);
+
+        let status = active_count_status(&plugins, &settings);
+        assert_eq!(229, status.count);
+        assert_eq!(255, status.limit);
+        assert!(!status.over_warning_threshold);
+        assert!(!status.over_limit);
+
+        plugins.push(active_plugin.clone());
+        assert!(active_count_status(&plugins, &settings).over_warning_threshold);
+
+        settings.set_active_plugins_warning_threshold(Some(240));
+        assert!(!active_count_status(&plugins, &settings).over_warning_threshold);
+
+        plugins.extend(vec![active_plugin; 26]);
+        let status = active_count_status(&plugins, &settings);
+        assert_eq!(256, status.count);
+        assert!(status.over_warning_threshold);
+        assert!(status.over_limit);
+    }
+
+    #[test]
+    fn plugin_status_should_be_loaded_for_a_plugin_in_the_load_order() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        assert_eq!(
+            PluginStatus::Loaded,
+            plugin_status(&plugins, &settings, "Blank.esp")
+        );
+    }
+
+    #[test]
+    fn plugin_status_should_be_ghosted_for_an_unloaded_plugin_with_a_ghosted_file() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        copy_to_test_dir("Blank.esm", "Ghosted.esm.ghost", &settings);
+        assert_eq!(
+            PluginStatus::Ghosted,
+            plugin_status(&plugins, &settings, "Ghosted.esm")
+        );
+    }
+
+    #[test]
+    fn plugin_status_should_be_loaded_for_a_loaded_plugin_with_a_ghosted_file() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, mut plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        copy_to_test_dir("Blank.esm", "Ghosted.esm.ghost", &settings);
+        plugins.push(Plugin::new("Ghosted.esm.ghost", &settings).unwrap());
+        assert_eq!(
+            PluginStatus::Loaded,
+            plugin_status(&plugins, &settings, "Ghosted.esm")
+        );
+    }
+
+    #[test]
+    fn plugin_status_should_be_not_loaded_for_an_installed_plugin_not_in_the_load_order() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        assert_eq!(
+            PluginStatus::NotLoaded,
+            plugin_status(&plugins, &settings, "Blank.esm")
+        );
+    }
+
+    #[test]
+    fn plugin_status_should_be_not_installed_for_a_missing_plugin() {
+        let tmp_dir = tempdir().unwrap();
+        let (settings, plugins) = mock_game_files(GameId::Oblivion, tmp_dir.path());
+
+        assert_eq!(
+            PluginStatus::NotInstalled,
+            plugin_status(&plugins, &settings, "missing.esp")
+        );
+    }
+
     #[test]
     fn active_plugin_names_should_return_filenames_for_active_plugins_in_load_order() {
         let tmp_dir = tempdir().unwrap();
@@ -388,4 +1892,41 @@ mod tests {
 
         assert!(is_active(&plugins, "blank.esp"));
     }
+
+    #[test]
+    fn content_hash_should_change_when_a_plugin_is_activated() {
+        let tmp_dir = tempdir().unwrap();
+        let mut plugins = prepare(tmp_dir.path());
+
+        let hash = content_hash(&plugins);
+        assert!(!plugins[2].is_active());
+        plugins[2].activate().unwrap();
+
+        assert_ne!(hash, content_hash(&plugins));
+    }
+
+    #[test]
+    fn content_hash_should_change_when_plugins_are_reordered() {
+        let tmp_dir = tempdir().unwrap();
+        let mut plugins = prepare(tmp_dir.path());
+
+        let hash = content_hash(&plugins);
+        plugins.swap(1, 2);
+
+        assert_ne!(hash, content_hash(&plugins));
+    }
+
+    #[test]
+    fn content_hash_should_be_stable_across_clones() {
+        let tmp_dir = tempdir().unwrap();
+        let plugins = prepare(tmp_dir.path());
+
+        assert_eq!(content_hash(&plugins), content_hash(&plugins.clone()));
+    }
+
+    #[test]
+    fn content_hash_should_use_the_fnv_1a_algorithm() {
+        assert_eq!(FNV_OFFSET_BASIS, content_hash(&[]));
+        assert_eq!(0xaf63_dc4c_8601_ec8c, fnv1a(FNV_OFFSET_BASIS, b"a"));
+    }
 }
@@ -16,25 +16,47 @@
  * You should have received a copy of the GNU General Public License
  * along with libloadorder. If not, see <http://www.gnu.org/licenses/>.
  */
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::{BufWriter, Read, Write};
+use std::ops::Range;
 use std::path::Path;
+use std::sync::Arc;
 
-use encoding::all::WINDOWS_1252;
-use encoding::{EncoderTrap, Encoding};
 use unicase::eq;
 
 use super::insertable::InsertableLoadOrder;
+use super::lock::{lock_for_save, ActivePluginsFileLock};
 use super::mutable::{
-    load_active_plugins, plugin_line_mapper, read_plugin_names, MutableLoadOrder,
+    load_active_plugins, plugin_line_mapper, read_active_plugins_limit, read_plugin_names,
+    MutableLoadOrder,
 };
 use super::readable::{
-    active_plugin_names, index_of, is_active, plugin_at, plugin_names, ReadableLoadOrder,
-    ReadableLoadOrderExt,
+    active_count_status, active_index_of, active_master_names, active_non_master_names,
+    active_plugin_names, active_plugins_as_written, content_hash, duplicate_content_plugins,
+    effective_load_order, excess_active_plugins, flat_entries, form_version, group_by_archive,
+    index_of, is_active, is_blueprint_master, is_master_plugin, is_ordering_valid, is_override_only,
+    light_count, master_count, master_dependency_cycles, master_file_present, master_indices,
+    mismatched_extension_plugins, missing_implicit_plugins, move_delta, non_master_count,
+    orphaned_plugins, plugin_at, plugin_has_associated_archive, plugin_names, plugin_names_reversed,
+    plugin_status, plugin_tier, read_active_plugins_file_raw, record_types, remaining_slots, report,
+    valid_index_range, validate, write_mo2_modlist, ActiveCountStatus, LoadOrderEntry,
+    ReadableLoadOrder, ReadableLoadOrderExt,
 };
-use super::writable::{activate, deactivate, set_active_plugins, WritableLoadOrder};
-use super::{create_parent_dirs, find_first_non_master_position};
-use enums::Error;
+use super::writable::{
+    activate, activate_many, activate_with_masters, active_plugins_file_content,
+    active_plugins_file_text, active_plugins_file_would_change, apply_loot_order,
+    apply_order_from_index, changed_plugins_since_load, clear, deactivate, deactivate_temporarily,
+    is_in_subset, is_locked, load_and_report_unghosted_plugins, lock_plugin, merge_new_plugins,
+    move_to_tier_end, move_to_tier_start, reorder_masters, restore_active_state, set_active_plugins,
+    set_active_plugins_prioritized, set_active_range, set_complete_load_order,
+    set_load_order_appending_rest, stale_active_entries, state_fingerprint, unlock_plugin,
+    validate_active_plugin_counts, validate_active_plugins_exist,
+    validate_hardcoded_first_plugin_index, verify_active_file_matches, ActiveStateToken,
+    StateFingerprint, WritableLoadOrder,
+};
+use super::{create_file, ensure_writable, find_first_non_master_position};
+use enums::{Error, PluginStatus, PluginTier, ValidationWarning};
 use game_settings::GameSettings;
 use plugin::{trim_dot_ghost, Plugin};
 
@@ -42,6 +64,9 @@ use plugin::{trim_dot_ghost, Plugin};
 pub struct TextfileBasedLoadOrder {
     game_settings: GameSettings,
     plugins: Vec<Plugin>,
+    locked_plugins: Vec<String>,
+    subset_loaded: bool,
+    held_lock: Option<Arc<ActivePluginsFileLock>>,
 }
 
 impl TextfileBasedLoadOrder {
@@ -49,6 +74,9 @@ impl TextfileBasedLoadOrder {
         Self {
             game_settings,
             plugins: Vec::new(),
+            locked_plugins: Vec::new(),
+            subset_loaded: false,
+            held_lock: None,
         }
     }
 }
@@ -58,14 +86,38 @@ impl ReadableLoadOrder for TextfileBasedLoadOrder {
         &self.game_settings
     }
 
+    fn plugins_directory(&self) -> &Path {
+        self.game_settings().plugins_directory_path()
+    }
+
     fn plugin_names(&self) -> Vec<&str> {
         plugin_names(self.plugins())
     }
 
+    fn plugins_slice(&self) -> &[Plugin] {
+        self.plugins()
+    }
+
+    fn plugin_names_reversed(&self) -> Vec<&str> {
+        plugin_names_reversed(self.plugins())
+    }
+
+    fn effective_load_order(&self) -> Vec<String> {
+        effective_load_order(self.plugins(), self.game_settings())
+    }
+
     fn index_of(&self, plugin_name: &str) -> Option<usize> {
         index_of(self.plugins(), plugin_name)
     }
 
+    fn active_index_of(&self, plugin_name: &str) -> Option<usize> {
+        active_index_of(self.plugins(), plugin_name)
+    }
+
+    fn move_delta(&self, plugin_name: &str, target: usize) -> Option<isize> {
+        move_delta(&self.plugins, plugin_name, target)
+    }
+
     fn plugin_at(&self, index: usize) -> Option<&str> {
         plugin_at(self.plugins(), index)
     }
@@ -74,9 +126,145 @@ impl ReadableLoadOrder for TextfileBasedLoadOrder {
         active_plugin_names(self.plugins())
     }
 
+    fn active_master_names(&self) -> Vec<&str> {
+        active_master_names(self.plugins())
+    }
+
+    fn active_non_master_names(&self) -> Vec<&str> {
+        active_non_master_names(self.plugins())
+    }
+
+    fn master_count(&self) -> usize {
+        master_count(self.plugins())
+    }
+
+    fn non_master_count(&self) -> usize {
+        non_master_count(self.plugins())
+    }
+
+    fn light_count(&self) -> usize {
+        light_count(self.plugins())
+    }
+
+    fn active_count_status(&self) -> ActiveCountStatus {
+        active_count_status(self.plugins(), self.game_settings())
+    }
+
+    fn remaining_slots(&self) -> (usize, usize) {
+        remaining_slots(self.plugins(), self.game_settings())
+    }
+
+    fn active_plugins_as_written(&self) -> Vec<String> {
+        active_plugins_as_written(self.plugins())
+    }
+
+    fn active_plugins_file_content(&self) -> Result<String, Error> {
+        active_plugins_file_text(self, Vec::new())
+    }
+
     fn is_active(&self, plugin_name: &str) -> bool {
         is_active(self.plugins(), plugin_name)
     }
+
+    fn plugin_status(&self, plugin_name: &str) -> PluginStatus {
+        plugin_status(self.plugins(), self.game_settings(), plugin_name)
+    }
+
+    fn plugin_tier(&self, plugin_name: &str) -> Option<PluginTier> {
+        plugin_tier(self.plugins(), self.game_settings(), plugin_name)
+    }
+
+    fn flat_entries(&self) -> Vec<LoadOrderEntry<'_>> {
+        flat_entries(self.plugins())
+    }
+
+    fn excess_active_plugins(&self) -> Vec<&str> {
+        excess_active_plugins(self.plugins(), self.game_settings())
+    }
+
+    fn master_file_present(&self) -> bool {
+        master_file_present(self.plugins(), self.game_settings().master_file())
+    }
+
+    fn valid_index_range(&self, plugin_name: &str) -> Result<Range<usize>, Error> {
+        valid_index_range(self.plugins(), plugin_name, self.game_settings())
+    }
+
+    fn is_override_only(&self, plugin_name: &str) -> Result<bool, Error> {
+        is_override_only(self.plugins(), plugin_name)
+    }
+
+    fn master_indices(&self, plugin_name: &str) -> Result<Vec<(String, Option<usize>)>, Error> {
+        master_indices(self.plugins(), plugin_name)
+    }
+
+    fn master_dependency_cycles(&self) -> Vec<Vec<String>> {
+        master_dependency_cycles(self.plugins())
+    }
+
+    fn orphaned_plugins(&self) -> Result<Vec<String>, Error> {
+        orphaned_plugins(self.plugins())
+    }
+
+    fn mismatched_extension_plugins(&self) -> Vec<String> {
+        mismatched_extension_plugins(self.plugins())
+    }
+
+    fn is_ordering_valid(&self) -> bool {
+        is_ordering_valid(self.plugins())
+    }
+
+    fn plugin_has_associated_archive(&self, plugin_name: &str) -> bool {
+        plugin_has_associated_archive(plugin_name, self.game_settings())
+    }
+
+    fn content_hash(&self) -> u64 {
+        content_hash(self.plugins())
+    }
+
+    fn group_by_archive(&self) -> Vec<(String, Vec<String>)> {
+        group_by_archive(self.plugins(), self.game_settings())
+    }
+
+    fn duplicate_content_plugins(&self) -> Result<Vec<Vec<String>>, Error> {
+        duplicate_content_plugins(&self.plugins)
+    }
+
+    fn read_active_plugins_file_raw(&self) -> Result<Vec<u8>, Error> {
+        read_active_plugins_file_raw(self.game_settings())
+    }
+
+    fn write_mo2_modlist(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        write_mo2_modlist(&self.plugins, writer)
+    }
+
+    fn is_master_plugin(&self, plugin_name: &str) -> Option<bool> {
+        is_master_plugin(&self.plugins, self.game_settings(), plugin_name)
+    }
+
+    fn form_version(&self, plugin_name: &str) -> Option<u16> {
+        form_version(&self.plugins, plugin_name)
+    }
+
+    fn is_blueprint_master(&self, plugin_name: &str) -> Option<bool> {
+        is_blueprint_master(self.plugins(), plugin_name)
+    }
+
+    fn record_types(&self, plugin_name: &str) -> Result<HashSet<[u8; 4]>, Error> {
+        record_types(self.plugins(), plugin_name)
+    }
+
+    fn validate(&self) -> Vec<ValidationWarning> {
+        validate(&self.plugins, self.game_settings().id())
+    }
+
+    fn missing_implicit_plugins(&self) -> Vec<String> {
+        missing_implicit_plugins(self.game_settings())
+    }
+
+    fn report(&self) -> String {
+        report(self.plugins(), self.game_settings())
+    }
 }
 
 impl ReadableLoadOrderExt for TextfileBasedLoadOrder {
@@ -89,6 +277,14 @@ impl MutableLoadOrder for TextfileBasedLoadOrder {
     fn plugins_mut(&mut self) -> &mut Vec<Plugin> {
         &mut self.plugins
     }
+
+    fn locked_plugins(&self) -> &[String] {
+        &self.locked_plugins
+    }
+
+    fn locked_plugins_mut(&mut self) -> &mut Vec<String> {
+        &mut self.locked_plugins
+    }
 }
 
 impl InsertableLoadOrder for TextfileBasedLoadOrder {
@@ -110,37 +306,68 @@ impl InsertableLoadOrder for TextfileBasedLoadOrder {
 }
 
 impl WritableLoadOrder for TextfileBasedLoadOrder {
-    fn load(&mut self) -> Result<(), Error> {
-        self.plugins_mut().clear();
+    fn load_and_report_excess_plugins_with_progress(
+        &mut self,
+        progress: &(dyn Fn(usize, usize) + Sync),
+    ) -> Result<Vec<String>, Error> {
+        self.load_filtered(|_| true, progress)
+    }
 
-        let load_order_file_exists = self.game_settings()
-            .load_order_file()
-            .map(|p| p.exists())
-            .unwrap_or(false);
+    fn load_and_report_unghosted_plugins(&mut self) -> Result<Vec<String>, Error> {
+        load_and_report_unghosted_plugins(self)
+    }
 
-        let plugin_tuples = if load_order_file_exists {
-            self.read_from_load_order_file()?
-        } else {
-            self.read_from_active_plugins_file()?
-        };
+    fn load_subset(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        let game_settings = self.game_settings().clone();
+        self.load_filtered(
+            |filename| is_in_subset(&game_settings, plugin_names, filename),
+            &|_, _| {},
+        )?;
+        self.subset_loaded = true;
 
-        let filenames = self.find_plugins_in_dir_sorted();
-        self.load_unique_plugins(plugin_tuples, filenames);
+        Ok(())
+    }
 
-        if load_order_file_exists {
-            load_active_plugins(self, plugin_line_mapper)?;
+    fn save(&mut self) -> Result<(), Error> {
+        ensure_writable(self.game_settings())?;
+        if self.subset_loaded {
+            return Err(Error::SubsetLoaded);
         }
+        let _lock = lock_for_save(self.game_settings(), self.held_lock.as_deref())?;
 
-        self.add_implicitly_active_plugins()?;
+        validate_active_plugin_counts(self)?;
+        validate_active_plugins_exist(self)?;
+
+        self.save_load_order()?;
+        self.save_active_plugins()
+    }
+
+    fn save_load_order_only(&mut self) -> Result<(), Error> {
+        ensure_writable(self.game_settings())?;
+        if self.subset_loaded {
+            return Err(Error::SubsetLoaded);
+        }
+        let _lock = lock_for_save(self.game_settings(), self.held_lock.as_deref())?;
+
+        self.save_load_order()
+    }
+
+    fn save_would_change(&self) -> Result<bool, Error> {
+        let content = active_plugins_file_content(self, Vec::new())?;
+        active_plugins_file_would_change(self.game_settings(), &content)
+    }
 
-        self.deactivate_excess_plugins();
+    fn lock_active_plugins_file(&mut self) -> Result<(), Error> {
+        if self.held_lock.is_none() {
+            let lock = ActivePluginsFileLock::acquire(self.game_settings())?;
+            self.held_lock = Some(Arc::new(lock));
+        }
 
         Ok(())
     }
 
-    fn save(&mut self) -> Result<(), Error> {
-        self.save_load_order()?;
-        self.save_active_plugins()
+    fn unlock_active_plugins_file(&mut self) {
+        self.held_lock = None;
     }
 
     fn set_load_order(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
@@ -151,20 +378,36 @@ impl WritableLoadOrder for TextfileBasedLoadOrder {
         self.replace_plugins(plugin_names)
     }
 
+    fn set_complete_load_order(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        set_complete_load_order(self, plugin_names)
+    }
+
+    fn set_load_order_appending_rest(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        set_load_order_appending_rest(self, plugin_names)
+    }
+
+    fn apply_order_from_index(&mut self, start: usize, plugin_names: &[&str]) -> Result<(), Error> {
+        apply_order_from_index(self, start, plugin_names)
+    }
+
+    fn apply_loot_order(&mut self, sorted_plugin_names: &[&str]) -> Result<Vec<String>, Error> {
+        apply_loot_order(self, sorted_plugin_names)
+    }
+
     fn set_plugin_index(&mut self, plugin_name: &str, position: usize) -> Result<(), Error> {
-        if position != 0
-            && !self.plugins().is_empty()
-            && eq(plugin_name, self.game_settings().master_file())
-        {
-            return Err(Error::GameMasterMustLoadFirst);
-        }
-        if position == 0 && !eq(plugin_name, self.game_settings().master_file()) {
-            return Err(Error::GameMasterMustLoadFirst);
-        }
+        validate_hardcoded_first_plugin_index(self, plugin_name, position)?;
 
         self.move_or_insert_plugin_with_index(plugin_name, position)
     }
 
+    fn move_to_tier_start(&mut self, plugin_name: &str) -> Result<(), Error> {
+        move_to_tier_start(self, plugin_name)
+    }
+
+    fn move_to_tier_end(&mut self, plugin_name: &str) -> Result<(), Error> {
+        move_to_tier_end(self, plugin_name)
+    }
+
     fn is_self_consistent(&self) -> Result<bool, Error> {
         match self.game_settings().load_order_file() {
             None => Ok(true),
@@ -197,16 +440,133 @@ impl WritableLoadOrder for TextfileBasedLoadOrder {
         activate(self, plugin_name)
     }
 
+    fn activate_many(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        activate_many(self, plugin_names)
+    }
+
+    fn activate_with_masters(&mut self, plugin_name: &str) -> Result<Vec<String>, Error> {
+        activate_with_masters(self, plugin_name)
+    }
+
     fn deactivate(&mut self, plugin_name: &str) -> Result<(), Error> {
         deactivate(self, plugin_name)
     }
 
+    fn deactivate_temporarily(&mut self, plugin_names: &[&str]) -> Result<ActiveStateToken, Error> {
+        deactivate_temporarily(self, plugin_names)
+    }
+
+    fn restore_active_state(&mut self, token: ActiveStateToken) -> Result<(), Error> {
+        restore_active_state(self, token)
+    }
+
+    fn set_active_range(
+        &mut self,
+        start: usize,
+        end: usize,
+        active: bool,
+    ) -> Result<Vec<String>, Error> {
+        set_active_range(self, start, end, active)
+    }
+
     fn set_active_plugins(&mut self, active_plugin_names: &[&str]) -> Result<(), Error> {
         set_active_plugins(self, active_plugin_names)
     }
+
+    fn set_active_plugins_prioritized(
+        &mut self,
+        active_plugin_names: &[&str],
+    ) -> Result<Vec<String>, Error> {
+        set_active_plugins_prioritized(self, active_plugin_names)
+    }
+
+    fn reorder_masters(&mut self, master_order: &[&str]) -> Result<(), Error> {
+        reorder_masters(self, master_order)
+    }
+
+    fn changed_plugins_since_load(&self) -> Result<Vec<String>, Error> {
+        changed_plugins_since_load(self)
+    }
+
+    fn refresh_implicitly_active(&mut self) -> Result<(), Error> {
+        self.game_settings.refresh_implicitly_active_plugins()?;
+
+        self.add_implicitly_active_plugins()
+    }
+
+    fn state_fingerprint(&self) -> Result<StateFingerprint, Error> {
+        state_fingerprint(self)
+    }
+
+    fn lock_plugin(&mut self, plugin_name: &str) {
+        lock_plugin(self, plugin_name)
+    }
+
+    fn unlock_plugin(&mut self, plugin_name: &str) {
+        unlock_plugin(self, plugin_name)
+    }
+
+    fn is_locked(&self, plugin_name: &str) -> bool {
+        is_locked(self, plugin_name)
+    }
+
+    fn merge_new_plugins(&mut self) -> Result<Vec<String>, Error> {
+        merge_new_plugins(self)
+    }
+
+    fn clear(&mut self) {
+        clear(self)
+    }
+
+    fn stale_active_entries(&self) -> Result<Vec<String>, Error> {
+        stale_active_entries(self, plugin_line_mapper)
+    }
+
+    fn verify_active_file_matches(&self) -> Result<bool, Error> {
+        verify_active_file_matches(self, plugin_line_mapper)
+    }
 }
 
 impl TextfileBasedLoadOrder {
+    fn load_filtered<F: Fn(&str) -> bool>(
+        &mut self,
+        filter: F,
+        progress: &(dyn Fn(usize, usize) + Sync),
+    ) -> Result<Vec<String>, Error> {
+        self.plugins_mut().clear();
+        self.subset_loaded = false;
+        self.game_settings.refresh_additional_plugins_directories();
+
+        let load_order_file_exists = self.game_settings()
+            .load_order_file()
+            .map(|p| p.exists())
+            .unwrap_or(false);
+
+        let mut plugin_tuples = if load_order_file_exists {
+            self.read_from_load_order_file()?
+        } else {
+            self.read_from_active_plugins_file()?
+        };
+        plugin_tuples.retain(|(name, _)| filter(name));
+
+        let mut filenames = self.find_plugins_in_dir_sorted()?;
+        filenames.retain(|filename| filter(filename));
+
+        self.load_unique_plugins(plugin_tuples, filenames, progress);
+
+        if load_order_file_exists {
+            load_active_plugins(self, plugin_line_mapper)?;
+        }
+
+        self.add_implicitly_active_plugins()?;
+
+        let limit = read_active_plugins_limit(self.game_settings().active_plugins_file())?;
+        self.game_settings.set_active_plugins_limit(limit);
+
+        let max_active_normal_plugins = self.game_settings().max_active_normal_plugins();
+        Ok(self.deactivate_excess_plugins(max_active_normal_plugins))
+    }
+
     fn read_from_load_order_file(&self) -> Result<Vec<(String, bool)>, Error> {
         match self.game_settings().load_order_file() {
             Some(file_path) => read_utf8_plugin_names(file_path, load_order_line_mapper)
@@ -224,9 +584,7 @@ impl TextfileBasedLoadOrder {
 
     fn save_load_order(&self) -> Result<(), Error> {
         if let Some(file_path) = self.game_settings().load_order_file() {
-            create_parent_dirs(file_path)?;
-
-            let file = File::create(file_path)?;
+            let file = create_file(file_path)?;
             let mut writer = BufWriter::new(file);
             for plugin_name in self.plugin_names() {
                 writeln!(writer, "{}", plugin_name)?;
@@ -236,16 +594,8 @@ impl TextfileBasedLoadOrder {
     }
 
     fn save_active_plugins(&self) -> Result<(), Error> {
-        create_parent_dirs(self.game_settings().active_plugins_file())?;
-
-        let file = File::create(self.game_settings().active_plugins_file())?;
-        let mut writer = BufWriter::new(file);
-        for plugin_name in self.active_plugin_names() {
-            writer.write_all(&WINDOWS_1252
-                .encode(&plugin_name, EncoderTrap::Strict)
-                .map_err(Error::EncodeError)?)?;
-            writeln!(writer)?;
-        }
+        let content = active_plugins_file_content(self, Vec::new())?;
+        create_file(self.game_settings().active_plugins_file())?.write_all(&content)?;
 
         Ok(())
     }
@@ -283,12 +633,16 @@ fn plugin_names_match(name1: &str, name2: &str) -> bool {
 mod tests {
     use super::*;
 
-    use enums::GameId;
+    use encoding::all::WINDOWS_1252;
+    use encoding::{EncoderTrap, Encoding};
+    use enums::{GameId, LineEnding};
+    use std::collections::HashMap;
     use filetime::{set_file_times, FileTime};
     use load_order::tests::*;
-    use std::fs::{remove_dir_all, File};
-    use std::io::Write;
+    use std::fs::{copy, create_dir, read, remove_dir_all, remove_file, File};
+    use std::io::{Read, Write};
     use std::path::Path;
+    use std::sync::Mutex;
     use tempfile::tempdir;
     use tests::copy_to_test_dir;
 
@@ -297,6 +651,9 @@ mod tests {
         TextfileBasedLoadOrder {
             game_settings,
             plugins,
+            locked_plugins: Vec::new(),
+            subset_loaded: false,
+            held_lock: None,
         }
     }
 
@@ -411,6 +768,40 @@ mod tests {
         assert!(load_order.index_of("Blank - Different.esp").is_none());
     }
 
+    #[test]
+    fn load_should_prefer_plugins_in_later_additional_plugins_directories() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        let plugins_directory = load_order.game_settings().plugins_directory().to_path_buf();
+        let overlay_directories = vec![tmp_dir.path().join("mod1"), tmp_dir.path().join("mod2")];
+        for directory in &overlay_directories {
+            create_dir(directory).unwrap();
+        }
+        copy(
+            plugins_directory.join("Blank.esm"),
+            overlay_directories[0].join("Blank.esp"),
+        ).unwrap();
+        copy(
+            plugins_directory.join("Blank - Master Dependent.esp"),
+            overlay_directories[1].join("blank.esp"),
+        ).unwrap();
+        copy(
+            plugins_directory.join("Blank.esp"),
+            overlay_directories[0].join("Overlay.esp"),
+        ).unwrap();
+
+        load_order
+            .game_settings
+            .set_additional_plugins_directories(overlay_directories);
+        load_order.load().unwrap();
+
+        let index = load_order.index_of("Blank.esp").unwrap();
+        assert_eq!(vec!["Blank.esm"], load_order.plugins()[index].masters());
+        assert_eq!(1, load_order.plugin_names().iter().filter(|n| eq(**n, "Blank.esp")).count());
+        assert!(load_order.index_of("Overlay.esp").is_some());
+    }
+
     #[test]
     fn load_should_get_load_order_from_load_order_file() {
         let tmp_dir = tempdir().unwrap();
@@ -491,6 +882,60 @@ mod tests {
         assert_eq!(expected_filenames, load_order.plugin_names());
     }
 
+    #[test]
+    fn load_subset_should_only_load_the_given_and_implicitly_active_plugins() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        load_order
+            .load_subset(&["blank - different.esp", "Blank.esm"])
+            .unwrap();
+
+        assert_eq!(
+            vec!["Skyrim.esm", "Blank.esm", "Blank - Different.esp"],
+            load_order.plugin_names()
+        );
+        assert!(load_order.is_active("Skyrim.esm"));
+        assert!(!load_order.is_active("Blank - Different.esp"));
+    }
+
+    #[test]
+    fn save_should_error_without_writing_after_load_subset() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        let filenames = vec![
+            "Skyrim.esm",
+            "Blank.esm",
+            "Blank.esp",
+            "Blank - Different.esp",
+        ];
+        write_load_order_file(load_order.game_settings(), &filenames);
+        write_active_plugins_file(load_order.game_settings(), &["Blank.esp"]);
+
+        let load_order_file = load_order.game_settings().load_order_file().unwrap().clone();
+        let active_plugins_file = load_order.game_settings().active_plugins_file().clone();
+        let load_order_content = read(&load_order_file).unwrap();
+        let active_plugins_content = read(&active_plugins_file).unwrap();
+
+        load_order.load_subset(&["Blank - Different.esp"]).unwrap();
+
+        match load_order.save().unwrap_err() {
+            Error::SubsetLoaded => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        match load_order.save_load_order_only().unwrap_err() {
+            Error::SubsetLoaded => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(load_order_content, read(&load_order_file).unwrap());
+        assert_eq!(active_plugins_content, read(&active_plugins_file).unwrap());
+
+        load_order.load().unwrap();
+        load_order.save().unwrap();
+        assert!(load_order.is_active("Blank.esp"));
+    }
+
     #[test]
     fn load_should_add_missing_plugins() {
         let tmp_dir = tempdir().unwrap();
@@ -526,6 +971,30 @@ mod tests {
         assert!(load_order.is_active("Update.esm"));
     }
 
+    #[test]
+    fn load_should_keep_the_hardcoded_first_plugin_first_and_active() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        write_load_order_file(
+            load_order.game_settings(),
+            &["Blank.esm", "Blank.esp", "Skyrim.esm"],
+        );
+        write_active_plugins_file(load_order.game_settings(), &["Blank.esp"]);
+        load_order.load().unwrap();
+
+        assert_eq!(Some(0), load_order.index_of("Skyrim.esm"));
+        assert!(load_order.is_active("Skyrim.esm"));
+
+        assert!(load_order.deactivate("Skyrim.esm").is_err());
+        match load_order.set_plugin_index("Skyrim.esm", 1).unwrap_err() {
+            Error::GameMasterMustLoadFirst => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(Some(0), load_order.index_of("Skyrim.esm"));
+        assert!(load_order.is_active("Skyrim.esm"));
+    }
+
     #[test]
     fn load_should_empty_the_load_order_if_the_plugins_directory_does_not_exist() {
         let tmp_dir = tempdir().unwrap();
@@ -611,6 +1080,27 @@ mod tests {
         assert_eq!(expected_filenames, load_order.active_plugin_names());
     }
 
+    #[test]
+    fn load_with_progress_should_report_progress_up_to_the_total_number_of_plugins() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        let reports = Mutex::new(Vec::new());
+        load_order
+            .load_with_progress(&|completed, total| {
+                reports.lock().unwrap().push((completed, total))
+            })
+            .unwrap();
+
+        let mut reports = reports.into_inner().unwrap();
+        reports.sort();
+
+        let total = load_order.plugin_names().len();
+        assert_eq!(total, reports.len());
+        assert!(reports.iter().all(|&(_, t)| t == total));
+        assert_eq!(Some(&(total, total)), reports.last());
+    }
+
     #[test]
     fn load_should_succeed_when_load_order_and_active_plugins_files_are_missing() {
         let tmp_dir = tempdir().unwrap();
@@ -621,16 +1111,39 @@ mod tests {
     }
 
     #[test]
-    fn load_should_deactivate_excess_plugins_not_including_implicitly_active_plugins() {
+    fn load_should_succeed_without_adding_the_game_master_if_it_is_missing() {
         let tmp_dir = tempdir().unwrap();
-        let mut load_order = prepare(GameId::Skyrim, &tmp_dir.path());
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
 
-        let mut plugins: Vec<String> = Vec::new();
-        plugins.push(load_order.game_settings().master_file().to_string());
-        for i in 0..260 {
-            plugins.push(format!("Blank{}.esm", i));
-            copy_to_test_dir(
-                "Blank - Different.esm",
+        let master_file = load_order.game_settings().master_file().to_string();
+        remove_file(load_order.game_settings().plugins_directory().join(&master_file)).unwrap();
+
+        assert!(load_order.load().is_ok());
+        assert!(!load_order.master_file_present());
+        assert!(load_order.index_of(&master_file).is_none());
+        assert!(load_order.index_of("Blank.esp").is_some());
+    }
+
+    #[test]
+    fn master_file_present_should_be_true_if_the_game_master_is_in_the_load_order() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        load_order.load().unwrap();
+        assert!(load_order.master_file_present());
+    }
+
+    #[test]
+    fn load_should_deactivate_excess_plugins_not_including_implicitly_active_plugins() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, &tmp_dir.path());
+
+        let mut plugins: Vec<String> = Vec::new();
+        plugins.push(load_order.game_settings().master_file().to_string());
+        for i in 0..260 {
+            plugins.push(format!("Blank{}.esm", i));
+            copy_to_test_dir(
+                "Blank - Different.esm",
                 &plugins.last().unwrap(),
                 load_order.game_settings(),
             );
@@ -717,6 +1230,54 @@ mod tests {
         assert_eq!(expected_filenames, plugin_names);
     }
 
+    #[test]
+    fn save_load_order_only_should_write_the_load_order_file_but_not_the_active_plugins_file() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        write_active_plugins_file(load_order.game_settings(), &["Blank.esp"]);
+        let active_plugins_file = load_order.game_settings().active_plugins_file().clone();
+        let original_content = read(&active_plugins_file).unwrap();
+
+        load_order.activate("Blank - Different.esp").unwrap();
+        load_order
+            .set_load_order(&["Skyrim.esm", "Blank - Different.esp", "Blank.esp"])
+            .unwrap();
+
+        load_order.save_load_order_only().unwrap();
+
+        assert_eq!(original_content, read(&active_plugins_file).unwrap());
+
+        let plugin_names = read_utf8_plugin_names(
+            load_order.game_settings().load_order_file().unwrap(),
+            plugin_line_mapper,
+        ).unwrap();
+        assert_eq!(
+            vec!["Skyrim.esm", "Blank - Different.esp", "Blank.esp"],
+            plugin_names
+        );
+    }
+
+    #[test]
+    fn save_load_order_only_should_error_without_writing_if_the_lock_is_held() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+        load_order.game_settings.set_lock_on_save(true);
+
+        let lock = ActivePluginsFileLock::acquire(load_order.game_settings()).unwrap();
+
+        match load_order.save_load_order_only().unwrap_err() {
+            Error::LoadOrderLocked(ref p) if p == lock.path() => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert!(!load_order.game_settings().load_order_file().unwrap().exists());
+
+        assert!(load_order.lock_active_plugins_file().is_err());
+        drop(lock);
+        load_order.lock_active_plugins_file().unwrap();
+        assert!(load_order.save_load_order_only().is_ok());
+    }
+
     #[test]
     fn save_should_create_active_plugins_file_parent_directory_if_it_does_not_exist() {
         let tmp_dir = tempdir().unwrap();
@@ -756,6 +1317,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn save_should_write_active_plugins_file_with_the_configured_line_ending() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        load_order.game_settings.set_line_ending(LineEnding::CrLf);
+        load_order.save().unwrap();
+
+        let mut content = String::new();
+        File::open(load_order.game_settings().active_plugins_file())
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert_eq!("Blank.esp\r\n", content);
+    }
+
+    #[test]
+    fn save_should_error_if_too_many_plugins_are_active() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        let plugin = Plugin::with_active("Blank.esp", load_order.game_settings(), true).unwrap();
+        load_order.plugins.extend(vec![plugin; 255]);
+
+        match load_order.save().unwrap_err() {
+            Error::TooManyActivePlugins {
+                light_count,
+                normal_count,
+            } => {
+                assert_eq!(0, light_count);
+                assert_eq!(256, normal_count);
+            }
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert!(!load_order.game_settings().active_plugins_file().exists());
+    }
+
     #[test]
     fn set_load_order_should_error_if_given_an_empty_list() {
         let tmp_dir = tempdir().unwrap();
@@ -767,6 +1365,24 @@ mod tests {
         assert_eq!(existing_filenames, load_order.plugin_names());
     }
 
+    #[test]
+    fn reorder_masters_should_error_if_the_game_master_would_not_load_first() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        load_order.set_plugin_index("Blank.esm", 1).unwrap();
+
+        let existing_filenames = to_owned(load_order.plugin_names());
+        match load_order
+            .reorder_masters(&["Blank.esm", "Skyrim.esm"])
+            .unwrap_err()
+        {
+            Error::GameMasterMustLoadFirst => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(existing_filenames, load_order.plugin_names());
+    }
+
     #[test]
     fn set_load_order_should_error_if_the_first_element_given_is_not_the_game_master() {
         let tmp_dir = tempdir().unwrap();
@@ -839,6 +1455,301 @@ mod tests {
         assert_eq!(filenames, load_order.plugin_names());
     }
 
+    #[test]
+    fn set_complete_load_order_should_error_if_an_installed_plugin_is_missing() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        let existing_filenames = to_owned(load_order.plugin_names());
+        let filenames = vec![
+            "Skyrim.esm",
+            "Blank.esm",
+            "Blank.esp",
+            "Blank - Master Dependent.esp",
+            "Blank - Different.esp",
+        ];
+        match load_order.set_complete_load_order(&filenames).unwrap_err() {
+            Error::IncompleteLoadOrder(x) => assert_eq!(vec!["Blàñk.esp"], x),
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(existing_filenames, load_order.plugin_names());
+    }
+
+    #[test]
+    fn set_complete_load_order_should_set_the_load_order_if_all_installed_plugins_are_given() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        let filenames = vec![
+            "Skyrim.esm",
+            "Blank.esm",
+            "Blàñk.esp",
+            "Blank.esp",
+            "Blank - Master Dependent.esp",
+            "Blank - Different.esp",
+        ];
+        load_order.set_complete_load_order(&filenames).unwrap();
+
+        assert_eq!(filenames, load_order.plugin_names());
+    }
+
+    #[test]
+    fn set_load_order_appending_rest_should_append_unlisted_plugins_after_their_tier() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        let filenames = vec![
+            "Skyrim.esm",
+            "Blàñk.esp",
+            "Blank - Master Dependent.esp",
+            "Blank - Different.esp",
+        ];
+        load_order.set_load_order_appending_rest(&filenames).unwrap();
+
+        let expected_filenames = vec![
+            "Skyrim.esm",
+            "Blank.esm",
+            "Blàñk.esp",
+            "Blank - Master Dependent.esp",
+            "Blank - Different.esp",
+            "Blank.esp",
+        ];
+        assert_eq!(expected_filenames, load_order.plugin_names());
+    }
+
+    #[test]
+    fn stale_active_entries_should_return_active_plugins_that_are_not_installed() {
+        let tmp_dir = tempdir().unwrap();
+        let load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        write_active_plugins_file(
+            load_order.game_settings(),
+            &["Skyrim.esm", "Blank.esp", "Missing.esp"],
+        );
+
+        assert_eq!(
+            vec!["Missing.esp".to_string()],
+            load_order.stale_active_entries().unwrap()
+        );
+    }
+
+    #[test]
+    fn restore_active_state_should_restore_plugins_deactivated_temporarily() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        assert!(load_order.is_active("Blank.esp"));
+        assert!(!load_order.is_active("Blank - Different.esp"));
+
+        let token = load_order
+            .deactivate_temporarily(&["Blank.esp", "Blank - Different.esp"])
+            .unwrap();
+
+        assert!(!load_order.is_active("Blank.esp"));
+        assert!(!load_order.is_active("Blank - Different.esp"));
+
+        load_order.restore_active_state(token).unwrap();
+
+        assert!(load_order.is_active("Blank.esp"));
+        assert!(!load_order.is_active("Blank - Different.esp"));
+    }
+
+    #[test]
+    fn restore_active_state_should_error_if_it_would_exceed_the_active_plugins_limit() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        let token = load_order.deactivate_temporarily(&["Blank.esp"]).unwrap();
+        load_order.activate("Blank - Different.esp").unwrap();
+        load_order.game_settings.set_active_plugins_limit(Some(1));
+
+        match load_order.restore_active_state(token).unwrap_err() {
+            Error::TooManyActivePlugins { normal_count, .. } => assert_eq!(2, normal_count),
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert!(!load_order.is_active("Blank.esp"));
+        assert!(load_order.is_active("Blank - Different.esp"));
+    }
+
+    #[test]
+    fn deactivate_temporarily_should_not_change_anything_if_a_plugin_is_implicitly_active() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        match load_order
+            .deactivate_temporarily(&["Blank.esp", "Skyrim.esm"])
+            .unwrap_err()
+        {
+            Error::ImplicitlyActivePlugin(_) => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+
+        assert!(load_order.is_active("Blank.esp"));
+    }
+
+    #[test]
+    fn clear_should_empty_the_load_order_and_keep_the_game_settings() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+        let load_order_file = load_order.game_settings().load_order_file().unwrap().clone();
+
+        load_order.clear();
+
+        assert!(load_order.plugin_names().is_empty());
+        assert_eq!(GameId::Skyrim, load_order.game_settings().id());
+        assert_eq!(
+            Some(&load_order_file),
+            load_order.game_settings().load_order_file()
+        );
+        assert!(tmp_dir.path().join("Data").join("Blank.esp").exists());
+    }
+
+    #[test]
+    fn merge_new_plugins_should_error_if_adding_a_master_would_move_a_locked_plugin() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        let filenames = vec![
+            "Skyrim.esm",
+            "Blank.esm",
+            "Blàñk.esp",
+            "Blank.esp",
+            "Blank - Master Dependent.esp",
+            "Blank - Different.esp",
+        ];
+        load_order.set_load_order(&filenames).unwrap();
+        load_order.lock_plugin("Blàñk.esp");
+
+        copy_to_test_dir("Blank.esm", "New.esm", load_order.game_settings());
+
+        match load_order.merge_new_plugins().unwrap_err() {
+            Error::PluginLocked(ref x) if x == "Blàñk.esp" => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(filenames, load_order.plugin_names());
+    }
+
+    #[test]
+    fn merge_new_plugins_should_add_non_masters_after_a_locked_plugin() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        let filenames = vec!["Skyrim.esm", "Blank.esm", "Blank.esp"];
+        load_order.set_load_order(&filenames).unwrap();
+        load_order.lock_plugin("Blank.esp");
+
+        load_order.merge_new_plugins().unwrap();
+
+        assert_eq!(Some(2), load_order.index_of("Blank.esp"));
+        assert!(load_order.index_of("Blank - Different.esp").unwrap() > 2);
+    }
+
+    #[test]
+    fn activate_should_error_if_adding_a_master_would_move_a_locked_plugin() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        load_order.lock_plugin("Blank.esp");
+        let existing_filenames = to_owned(load_order.plugin_names());
+
+        match load_order.activate("Blank.esm").unwrap_err() {
+            Error::PluginLocked(ref x) if x == "Blank.esp" => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(existing_filenames, load_order.plugin_names());
+        assert!(!load_order.is_active("Blank.esm"));
+    }
+
+    #[test]
+    fn merge_new_plugins_should_add_unlisted_plugins_without_changing_the_existing_order() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        let filenames = vec![
+            "Skyrim.esm",
+            "Blank.esm",
+            "Blàñk.esp",
+            "Blank.esp",
+            "Blank - Master Dependent.esp",
+            "Blank - Different.esp",
+        ];
+        load_order.set_load_order(&filenames).unwrap();
+
+        copy_to_test_dir("Blank.esm", "New.esm", load_order.game_settings());
+
+        let added = load_order.merge_new_plugins().unwrap();
+
+        assert_eq!(vec!["New.esm".to_string()], added);
+        assert_eq!(
+            vec![
+                "Skyrim.esm",
+                "Blank.esm",
+                "New.esm",
+                "Blàñk.esp",
+                "Blank.esp",
+                "Blank - Master Dependent.esp",
+                "Blank - Different.esp",
+            ],
+            load_order.plugin_names()
+        );
+    }
+
+    #[test]
+    fn apply_order_from_index_should_only_reorder_plugins_from_the_given_index() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        load_order
+            .set_load_order(&[
+                "Skyrim.esm",
+                "Blank.esm",
+                "Blàñk.esp",
+                "Blank.esp",
+                "Blank - Master Dependent.esp",
+                "Blank - Different.esp",
+            ])
+            .unwrap();
+
+        load_order
+            .apply_order_from_index(
+                2,
+                &[
+                    "Blank - Different.esp",
+                    "Blank - Master Dependent.esp",
+                    "Blank.esp",
+                    "Blàñk.esp",
+                ],
+            )
+            .unwrap();
+
+        let expected_filenames = vec![
+            "Skyrim.esm",
+            "Blank.esm",
+            "Blank - Different.esp",
+            "Blank - Master Dependent.esp",
+            "Blank.esp",
+            "Blàñk.esp",
+        ];
+        assert_eq!(expected_filenames, load_order.plugin_names());
+    }
+
+    #[test]
+    fn apply_order_from_index_should_error_if_a_master_would_load_after_a_fixed_non_master() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+        let existing_filenames = to_owned(load_order.plugin_names());
+
+        match load_order
+            .apply_order_from_index(2, &["Blank.esm", "Blank - Different.esp"])
+            .unwrap_err()
+        {
+            Error::NonMasterBeforeMaster => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+
+        assert_eq!(existing_filenames, load_order.plugin_names());
+    }
+
     #[test]
     fn set_load_order_should_not_lose_active_state_of_existing_plugins() {
         let tmp_dir = tempdir().unwrap();
@@ -856,6 +1767,68 @@ mod tests {
         assert!(load_order.is_active("Blank.esp"));
     }
 
+    #[test]
+    fn apply_loot_order_should_skip_and_return_plugins_that_are_not_installed() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        let sorted = vec![
+            "Skyrim.esm",
+            "Blank.esm",
+            "Missing.esm",
+            "Blank - Different.esp",
+            "Blank.esp",
+        ];
+        let skipped = load_order.apply_loot_order(&sorted).unwrap();
+
+        assert_eq!(vec!["Missing.esm".to_string()], skipped);
+        assert_eq!(
+            vec!["Skyrim.esm", "Blank.esm", "Blank - Different.esp", "Blank.esp"],
+            load_order.plugin_names()
+        );
+        assert!(load_order.is_active("Blank.esp"));
+    }
+
+    #[test]
+    fn sort_by_key_should_sort_by_key_then_move_masters_before_non_masters() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        let filenames = vec![
+            "Skyrim.esm",
+            "Blank.esm",
+            "Blank.esp",
+            "Blank - Master Dependent.esp",
+            "Blank - Different.esp",
+        ];
+        load_order.set_load_order(&filenames).unwrap();
+
+        let priorities: HashMap<&str, u32> = [
+            ("Blank - Different.esp", 1),
+            ("Blank.esp", 2),
+            ("Blank - Master Dependent.esp", 3),
+            ("Blank.esm", 4),
+            ("Skyrim.esm", 5),
+        ].iter()
+            .cloned()
+            .collect();
+
+        load_order
+            .sort_by_key(|name| priorities.get(name).cloned())
+            .unwrap();
+
+        let expected_filenames = vec![
+            "Skyrim.esm",
+            "Blank.esm",
+            "Blank - Different.esp",
+            "Blank.esp",
+            "Blank - Master Dependent.esp",
+        ];
+        assert_eq!(expected_filenames, load_order.plugin_names());
+        assert!(load_order.is_active("Blank.esp"));
+        assert!(!load_order.is_active("Blank - Different.esp"));
+    }
+
     #[test]
     fn set_plugin_index_should_error_if_setting_the_game_master_index_to_non_zero_in_bounds() {
         let tmp_dir = tempdir().unwrap();
@@ -887,6 +1860,65 @@ mod tests {
         assert_eq!(num_plugins + 1, load_order.plugins().len());
     }
 
+    fn prepare_tiers(game_dir: &Path) -> TextfileBasedLoadOrder {
+        let mut load_order = prepare(GameId::Skyrim, game_dir);
+
+        copy_to_test_dir(
+            "Blank - Different.esm",
+            "Blank - Different.esm",
+            load_order.game_settings(),
+        );
+        load_order
+            .set_load_order(&[
+                "Skyrim.esm",
+                "Blank.esm",
+                "Blank - Different.esm",
+                "Blank.esp",
+                "Blank - Different.esp",
+                "Blàñk.esp",
+            ])
+            .unwrap();
+
+        load_order
+    }
+
+    #[test]
+    fn move_to_tier_start_should_move_a_master_after_the_game_master() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare_tiers(tmp_dir.path());
+
+        load_order.move_to_tier_start("Blank - Different.esm").unwrap();
+        assert_eq!(Some(1), load_order.index_of("Blank - Different.esm"));
+
+        load_order.move_to_tier_end("Blank - Different.esm").unwrap();
+        assert_eq!(Some(2), load_order.index_of("Blank - Different.esm"));
+        assert_eq!(Some(1), load_order.index_of("Blank.esm"));
+    }
+
+    #[test]
+    fn move_to_tier_start_and_end_should_keep_a_non_master_among_non_masters() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare_tiers(tmp_dir.path());
+
+        load_order.move_to_tier_start("Blàñk.esp").unwrap();
+        assert_eq!(Some(3), load_order.index_of("Blàñk.esp"));
+
+        load_order.move_to_tier_end("Blank.esp").unwrap();
+        assert_eq!(Some(5), load_order.index_of("Blank.esp"));
+        assert!(load_order.is_active("Blank.esp"));
+    }
+
+    #[test]
+    fn move_to_tier_start_should_error_if_the_plugin_is_not_in_the_load_order() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare_tiers(tmp_dir.path());
+
+        match load_order.move_to_tier_start("missing.esp").unwrap_err() {
+            Error::PluginNotFound(_) => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+    }
+
     #[test]
     fn is_self_consistent_should_return_true_when_no_load_order_file_exists() {
         let tmp_dir = tempdir().unwrap();
@@ -17,26 +17,53 @@
  * along with libloadorder. If not, see <http://www.gnu.org/licenses/>.
  */
 use std::cmp::Ordering;
-use std::fs::File;
-use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::collections::{HashMap, HashSet};
+use std::fs::{metadata, File};
+use std::io::{BufRead, BufReader, Write};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use encoding::all::WINDOWS_1252;
-use encoding::{EncoderTrap, Encoding};
 use rayon::prelude::*;
 use regex::Regex;
 
 use super::insertable::InsertableLoadOrder;
-use super::mutable::{load_active_plugins, MutableLoadOrder};
+use super::lock::{lock_for_save, ActivePluginsFileLock};
+use super::mutable::{
+    load_active_plugins, read_active_plugins_limit, read_plugin_names, MutableLoadOrder,
+};
 use super::readable::{
-    active_plugin_names, index_of, is_active, plugin_at, plugin_names, ReadableLoadOrder,
-    ReadableLoadOrderExt,
+    active_count_status, active_index_of, active_master_names, active_non_master_names,
+    active_plugin_names, content_hash, duplicate_content_plugins, effective_load_order,
+    excess_active_plugins, flat_entries, form_version, group_by_archive, index_of, is_active,
+    is_blueprint_master, is_master_plugin, is_ordering_valid, is_override_only, light_count,
+    master_count, master_dependency_cycles, master_file_present, master_indices,
+    mismatched_extension_plugins, missing_implicit_plugins, move_delta, non_master_count,
+    orphaned_plugins, plugin_at, plugin_has_associated_archive, plugin_names, plugin_names_reversed,
+    plugin_status, plugin_tier, read_active_plugins_file_raw, record_types, remaining_slots, report,
+    valid_index_range, validate, write_mo2_modlist, ActiveCountStatus, LoadOrderEntry,
+    ReadableLoadOrder, ReadableLoadOrderExt,
+};
+use super::writable::{
+    activate, activate_many, activate_with_masters, active_plugins_file_content,
+    active_plugins_file_text, active_plugins_file_would_change, apply_loot_order,
+    apply_order_from_index, changed_plugins_since_load, clear, deactivate, deactivate_temporarily,
+    is_in_subset, is_locked, load_and_report_unghosted_plugins, lock_plugin, merge_new_plugins,
+    move_to_tier_end, move_to_tier_start, reorder_masters, restore_active_state, set_active_plugins,
+    set_active_plugins_prioritized, set_active_range, set_complete_load_order,
+    set_load_order_appending_rest, stale_active_entries, state_fingerprint, unlock_plugin,
+    validate_active_plugin_counts, validate_active_plugins_exist, verify_active_file_matches,
+    ActiveStateToken, StateFingerprint, WritableLoadOrder,
+};
+use super::{create_file, ensure_writable, find_first_non_master_position};
+use enums::{
+    Error, GameId, PluginStatus, PluginTier, TimestampSpacing, TimestampTiebreak, ValidationWarning,
 };
-use super::writable::{activate, deactivate, set_active_plugins, WritableLoadOrder};
-use super::{create_parent_dirs, find_first_non_master_position};
-use enums::{Error, GameId};
 use game_settings::GameSettings;
-use plugin::Plugin;
+use ghostable_path::GhostablePath;
+use plugin::{trim_dot_ghost, Plugin};
 
 const GAME_FILES_HEADER: &[u8] = b"[Game Files]";
 
@@ -44,6 +71,11 @@ const GAME_FILES_HEADER: &[u8] = b"[Game Files]";
 pub struct TimestampBasedLoadOrder {
     game_settings: GameSettings,
     plugins: Vec<Plugin>,
+    locked_plugins: Vec<String>,
+    subset_loaded: bool,
+    tiebreak: TimestampTiebreak,
+    spacing: TimestampSpacing,
+    held_lock: Option<Arc<ActivePluginsFileLock>>,
 }
 
 impl TimestampBasedLoadOrder {
@@ -51,7 +83,170 @@ impl TimestampBasedLoadOrder {
         Self {
             game_settings,
             plugins: Vec::new(),
+            locked_plugins: Vec::new(),
+            subset_loaded: false,
+            tiebreak: TimestampTiebreak::Filename,
+            spacing: TimestampSpacing::Minute,
+            held_lock: None,
+        }
+    }
+
+    pub fn tiebreak(&self) -> TimestampTiebreak {
+        self.tiebreak
+    }
+
+    /// Set how plugins with equal timestamps are ordered. This takes effect
+    /// the next time the load order is loaded.
+    pub fn set_tiebreak(&mut self, tiebreak: TimestampTiebreak) {
+        self.tiebreak = tiebreak;
+    }
+
+    pub fn spacing(&self) -> TimestampSpacing {
+        self.spacing
+    }
+
+    /// Set how far apart saving spaces the timestamps that it adds, which
+    /// defaults to a minute. Smaller spacing avoids giving plugins timestamps
+    /// far in the future when many share a timestamp.
+    pub fn set_spacing(&mut self, spacing: TimestampSpacing) {
+        self.spacing = spacing;
+    }
+
+    /// Get the modification times of the loaded plugins' files as they
+    /// currently are on disk, rather than as they were when last loaded.
+    pub fn on_disk_timestamps(&self) -> Result<Vec<(String, SystemTime)>, Error> {
+        self.plugins
+            .iter()
+            .map(|plugin| {
+                let path = self
+                    .game_settings
+                    .plugin_path(plugin.name())
+                    .resolve_path()?;
+                let time = metadata(path)?.modified()?;
+                Ok((plugin.name().to_string(), time))
+            })
+            .collect()
+    }
+
+    /// Get the timestamp that saving would give the given plugin, or `None`
+    /// if it isn't in the load order.
+    pub fn planned_timestamp_for(&self, plugin_name: &str) -> Option<SystemTime> {
+        let index = self.index_of(plugin_name)?;
+
+        self.planned_timestamps().get(index).cloned()
+    }
+
+    /// Write plugin timestamps so that they match the current load order,
+    /// without touching the active plugins file.
+    pub fn rewrite_timestamps(&mut self) -> Result<(), Error> {
+        ensure_writable(self.game_settings())?;
+        if self.subset_loaded {
+            return Err(Error::SubsetLoaded);
+        }
+        let _lock = lock_for_save(self.game_settings(), self.held_lock.as_deref())?;
+
+        self.write_timestamps()
+    }
+
+    fn write_timestamps(&mut self) -> Result<(), Error> {
+        let timestamps = self.planned_timestamps();
+
+        self.plugins_mut()
+            .par_iter_mut()
+            .zip(timestamps.into_par_iter())
+            .map(|(ref mut plugin, timestamp)| plugin.set_modification_time(timestamp))
+            .collect()
+    }
+
+    fn planned_timestamps(&self) -> Vec<SystemTime> {
+        padded_unique_timestamps(self.plugins(), timestamp_increment(self.spacing))
+    }
+
+    fn load_filtered<F: Fn(&str) -> bool>(
+        &mut self,
+        filter: F,
+        progress: &(dyn Fn(usize, usize) + Sync),
+    ) -> Result<Vec<String>, Error> {
+        self.plugins_mut().clear();
+        self.subset_loaded = false;
+        self.game_settings.refresh_additional_plugins_directories();
+
+        self.plugins = load_plugins_from_dir(self, filter, progress)?;
+
+        let regex = Regex::new(r"(?i)GameFile[0-9]{1,3}=(.+\.es(?:m|p))")?;
+        let game_id = self.game_settings().id();
+        let line_mapper = |line: &str| plugin_line_mapper(line, &regex, game_id);
+
+        self.sort_plugins(line_mapper)?;
+
+        load_active_plugins(self, line_mapper)?;
+
+        self.add_implicitly_active_plugins()?;
+
+        let limit = read_active_plugins_limit(self.game_settings().active_plugins_file())?;
+        self.game_settings.set_active_plugins_limit(limit);
+
+        let max_active_normal_plugins = self.game_settings().max_active_normal_plugins();
+        Ok(self.deactivate_excess_plugins(max_active_normal_plugins))
+    }
+
+    fn sort_plugins<F>(&mut self, line_mapper: F) -> Result<(), Error>
+    where
+        F: Fn(&str) -> Option<String> + Send + Sync,
+    {
+        match self.tiebreak {
+            TimestampTiebreak::Filename => {
+                self.plugins
+                    .par_sort_by(|a, b| plugin_sorter(a, b, |_, _| Ordering::Equal));
+            }
+            TimestampTiebreak::ActiveFileOrder => {
+                let active_plugin_names =
+                    read_plugin_names(self.game_settings().active_plugins_file(), line_mapper)?;
+                let mut active_positions: HashMap<String, usize> = HashMap::new();
+                for (index, name) in active_plugin_names.iter().enumerate() {
+                    active_positions
+                        .entry(trim_dot_ghost(name).to_lowercase())
+                        .or_insert(index);
+                }
+                let positions: HashMap<String, usize> = self.plugins
+                    .iter()
+                    .map(|plugin| {
+                        let position = active_positions
+                            .get(&plugin.name().to_lowercase())
+                            .cloned()
+                            .unwrap_or(usize::MAX);
+                        (plugin.name().to_string(), position)
+                    })
+                    .collect();
+
+                self.plugins.par_sort_by(|a, b| {
+                    plugin_sorter(a, b, |a, b| positions[a.name()].cmp(&positions[b.name()]))
+                });
+            }
+            TimestampTiebreak::CreationTime => {
+                let creation_times: HashMap<String, Option<SystemTime>> = self.plugins
+                    .par_iter()
+                    .map(|plugin| {
+                        let time = plugin.creation_time().unwrap_or(None);
+                        (plugin.name().to_string(), time)
+                    })
+                    .collect();
+
+                self.plugins.par_sort_by(|a, b| {
+                    plugin_sorter(a, b, |a, b| {
+                        creation_times[a.name()].cmp(&creation_times[b.name()])
+                    })
+                });
+            }
+        }
+
+        // The game's master file always loads first, whatever its timestamp.
+        let master_file = self.game_settings().master_file();
+        if let Some(index) = self.plugins.iter().position(|p| p.name_matches(master_file)) {
+            self.plugins[..=index].rotate_right(1);
         }
+
+        Ok(())
     }
 }
 
@@ -60,14 +255,38 @@ impl ReadableLoadOrder for TimestampBasedLoadOrder {
         &self.game_settings
     }
 
+    fn plugins_directory(&self) -> &Path {
+        self.game_settings().plugins_directory_path()
+    }
+
     fn plugin_names(&self) -> Vec<&str> {
         plugin_names(self.plugins())
     }
 
+    fn plugins_slice(&self) -> &[Plugin] {
+        self.plugins()
+    }
+
+    fn plugin_names_reversed(&self) -> Vec<&str> {
+        plugin_names_reversed(self.plugins())
+    }
+
+    fn effective_load_order(&self) -> Vec<String> {
+        effective_load_order(self.plugins(), self.game_settings())
+    }
+
     fn index_of(&self, plugin_name: &str) -> Option<usize> {
         index_of(self.plugins(), plugin_name)
     }
 
+    fn active_index_of(&self, plugin_name: &str) -> Option<usize> {
+        active_index_of(self.plugins(), plugin_name)
+    }
+
+    fn move_delta(&self, plugin_name: &str, target: usize) -> Option<isize> {
+        move_delta(&self.plugins, plugin_name, target)
+    }
+
     fn plugin_at(&self, index: usize) -> Option<&str> {
         plugin_at(self.plugins(), index)
     }
@@ -76,9 +295,156 @@ impl ReadableLoadOrder for TimestampBasedLoadOrder {
         active_plugin_names(self.plugins())
     }
 
+    fn active_master_names(&self) -> Vec<&str> {
+        active_master_names(self.plugins())
+    }
+
+    fn active_non_master_names(&self) -> Vec<&str> {
+        active_non_master_names(self.plugins())
+    }
+
+    fn master_count(&self) -> usize {
+        master_count(self.plugins())
+    }
+
+    fn non_master_count(&self) -> usize {
+        non_master_count(self.plugins())
+    }
+
+    fn light_count(&self) -> usize {
+        light_count(self.plugins())
+    }
+
+    fn active_count_status(&self) -> ActiveCountStatus {
+        active_count_status(self.plugins(), self.game_settings())
+    }
+
+    fn remaining_slots(&self) -> (usize, usize) {
+        remaining_slots(self.plugins(), self.game_settings())
+    }
+
+    fn active_plugins_as_written(&self) -> Vec<String> {
+        self.active_plugin_names()
+            .into_iter()
+            .enumerate()
+            .map(|(index, plugin_name)| {
+                if self.game_settings().id() == GameId::Morrowind {
+                    format!("GameFile{}={}", index, plugin_name)
+                } else {
+                    plugin_name.to_string()
+                }
+            })
+            .collect()
+    }
+
+    fn active_plugins_file_content(&self) -> Result<String, Error> {
+        let prelude = get_file_prelude(self.game_settings())?;
+        active_plugins_file_text(self, prelude)
+    }
+
     fn is_active(&self, plugin_name: &str) -> bool {
         is_active(self.plugins(), plugin_name)
     }
+
+    fn plugin_status(&self, plugin_name: &str) -> PluginStatus {
+        plugin_status(self.plugins(), self.game_settings(), plugin_name)
+    }
+
+    fn plugin_tier(&self, plugin_name: &str) -> Option<PluginTier> {
+        plugin_tier(self.plugins(), self.game_settings(), plugin_name)
+    }
+
+    fn flat_entries(&self) -> Vec<LoadOrderEntry<'_>> {
+        flat_entries(self.plugins())
+    }
+
+    fn excess_active_plugins(&self) -> Vec<&str> {
+        excess_active_plugins(self.plugins(), self.game_settings())
+    }
+
+    fn master_file_present(&self) -> bool {
+        master_file_present(self.plugins(), self.game_settings().master_file())
+    }
+
+    fn valid_index_range(&self, plugin_name: &str) -> Result<Range<usize>, Error> {
+        valid_index_range(self.plugins(), plugin_name, self.game_settings())
+    }
+
+    fn is_override_only(&self, plugin_name: &str) -> Result<bool, Error> {
+        is_override_only(self.plugins(), plugin_name)
+    }
+
+    fn master_indices(&self, plugin_name: &str) -> Result<Vec<(String, Option<usize>)>, Error> {
+        master_indices(self.plugins(), plugin_name)
+    }
+
+    fn master_dependency_cycles(&self) -> Vec<Vec<String>> {
+        master_dependency_cycles(self.plugins())
+    }
+
+    fn orphaned_plugins(&self) -> Result<Vec<String>, Error> {
+        orphaned_plugins(self.plugins())
+    }
+
+    fn mismatched_extension_plugins(&self) -> Vec<String> {
+        mismatched_extension_plugins(self.plugins())
+    }
+
+    fn is_ordering_valid(&self) -> bool {
+        is_ordering_valid(self.plugins())
+    }
+
+    fn plugin_has_associated_archive(&self, plugin_name: &str) -> bool {
+        plugin_has_associated_archive(plugin_name, self.game_settings())
+    }
+
+    fn content_hash(&self) -> u64 {
+        content_hash(self.plugins())
+    }
+
+    fn group_by_archive(&self) -> Vec<(String, Vec<String>)> {
+        group_by_archive(self.plugins(), self.game_settings())
+    }
+
+    fn duplicate_content_plugins(&self) -> Result<Vec<Vec<String>>, Error> {
+        duplicate_content_plugins(&self.plugins)
+    }
+
+    fn read_active_plugins_file_raw(&self) -> Result<Vec<u8>, Error> {
+        read_active_plugins_file_raw(self.game_settings())
+    }
+
+    fn write_mo2_modlist(&self, writer: &mut dyn Write) -> Result<(), Error> {
+        write_mo2_modlist(&self.plugins, writer)
+    }
+
+    fn is_master_plugin(&self, plugin_name: &str) -> Option<bool> {
+        is_master_plugin(&self.plugins, self.game_settings(), plugin_name)
+    }
+
+    fn form_version(&self, plugin_name: &str) -> Option<u16> {
+        form_version(&self.plugins, plugin_name)
+    }
+
+    fn is_blueprint_master(&self, plugin_name: &str) -> Option<bool> {
+        is_blueprint_master(self.plugins(), plugin_name)
+    }
+
+    fn record_types(&self, plugin_name: &str) -> Result<HashSet<[u8; 4]>, Error> {
+        record_types(self.plugins(), plugin_name)
+    }
+
+    fn validate(&self) -> Vec<ValidationWarning> {
+        validate(&self.plugins, self.game_settings().id())
+    }
+
+    fn missing_implicit_plugins(&self) -> Vec<String> {
+        missing_implicit_plugins(self.game_settings())
+    }
+
+    fn report(&self) -> String {
+        report(self.plugins(), self.game_settings())
+    }
 }
 
 impl ReadableLoadOrderExt for TimestampBasedLoadOrder {
@@ -91,6 +457,14 @@ impl MutableLoadOrder for TimestampBasedLoadOrder {
     fn plugins_mut(&mut self) -> &mut Vec<Plugin> {
         &mut self.plugins
     }
+
+    fn locked_plugins(&self) -> &[String] {
+        &self.locked_plugins
+    }
+
+    fn locked_plugins_mut(&mut self) -> &mut Vec<String> {
+        &mut self.locked_plugins
+    }
 }
 
 impl InsertableLoadOrder for TimestampBasedLoadOrder {
@@ -104,48 +478,98 @@ impl InsertableLoadOrder for TimestampBasedLoadOrder {
 }
 
 impl WritableLoadOrder for TimestampBasedLoadOrder {
-    fn load(&mut self) -> Result<(), Error> {
-        self.plugins_mut().clear();
+    fn load_and_report_excess_plugins_with_progress(
+        &mut self,
+        progress: &(dyn Fn(usize, usize) + Sync),
+    ) -> Result<Vec<String>, Error> {
+        self.load_filtered(|_| true, progress)
+    }
 
-        self.plugins = load_plugins_from_dir(self);
-        self.plugins.par_sort_by(plugin_sorter);
+    fn load_and_report_unghosted_plugins(&mut self) -> Result<Vec<String>, Error> {
+        load_and_report_unghosted_plugins(self)
+    }
 
-        let regex = Regex::new(r"(?i)GameFile[0-9]{1,3}=(.+\.es(?:m|p))")?;
-        let game_id = self.game_settings().id();
-        let line_mapper = |line: &str| plugin_line_mapper(line, &regex, game_id);
+    fn load_subset(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        let game_settings = self.game_settings().clone();
+        self.load_filtered(
+            |filename| is_in_subset(&game_settings, plugin_names, filename),
+            &|_, _| {},
+        )?;
+        self.subset_loaded = true;
 
-        load_active_plugins(self, line_mapper)?;
+        Ok(())
+    }
 
-        self.add_implicitly_active_plugins()?;
+    fn save(&mut self) -> Result<(), Error> {
+        ensure_writable(self.game_settings())?;
+        if self.subset_loaded {
+            return Err(Error::SubsetLoaded);
+        }
+        let _lock = lock_for_save(self.game_settings(), self.held_lock.as_deref())?;
 
-        self.deactivate_excess_plugins();
+        validate_active_plugin_counts(self)?;
+        validate_active_plugins_exist(self)?;
 
-        Ok(())
+        self.write_timestamps()?;
+
+        save_active_plugins(self)
     }
 
-    fn save(&mut self) -> Result<(), Error> {
-        let timestamps = padded_unique_timestamps(self.plugins());
+    fn save_load_order_only(&mut self) -> Result<(), Error> {
+        self.rewrite_timestamps()
+    }
 
-        let result: Result<Vec<()>, Error> = self.plugins_mut()
-            .par_iter_mut()
-            .zip(timestamps.into_par_iter())
-            .map(|(ref mut plugin, timestamp)| plugin.set_modification_time(timestamp))
-            .collect();
+    fn save_would_change(&self) -> Result<bool, Error> {
+        let prelude = get_file_prelude(self.game_settings())?;
+        let content = active_plugins_file_content(self, prelude)?;
+        active_plugins_file_would_change(self.game_settings(), &content)
+    }
 
-        match result {
-            Ok(_) => save_active_plugins(self),
-            Err(e) => Err(e),
+    fn lock_active_plugins_file(&mut self) -> Result<(), Error> {
+        if self.held_lock.is_none() {
+            let lock = ActivePluginsFileLock::acquire(self.game_settings())?;
+            self.held_lock = Some(Arc::new(lock));
         }
+
+        Ok(())
+    }
+
+    fn unlock_active_plugins_file(&mut self) {
+        self.held_lock = None;
     }
 
     fn set_load_order(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
         self.replace_plugins(plugin_names)
     }
 
+    fn set_complete_load_order(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        set_complete_load_order(self, plugin_names)
+    }
+
+    fn set_load_order_appending_rest(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        set_load_order_appending_rest(self, plugin_names)
+    }
+
+    fn apply_order_from_index(&mut self, start: usize, plugin_names: &[&str]) -> Result<(), Error> {
+        apply_order_from_index(self, start, plugin_names)
+    }
+
+    fn apply_loot_order(&mut self, sorted_plugin_names: &[&str]) -> Result<Vec<String>, Error> {
+        apply_loot_order(self, sorted_plugin_names)
+    }
+
     fn set_plugin_index(&mut self, plugin_name: &str, position: usize) -> Result<(), Error> {
         self.move_or_insert_plugin_with_index(plugin_name, position)
     }
 
+    fn move_to_tier_start(&mut self, plugin_name: &str) -> Result<(), Error> {
+        move_to_tier_start(self, plugin_name)
+    }
+
+    fn move_to_tier_end(&mut self, plugin_name: &str) -> Result<(), Error> {
+        move_to_tier_end(self, plugin_name)
+    }
+
     fn is_self_consistent(&self) -> Result<bool, Error> {
         Ok(true)
     }
@@ -154,29 +578,128 @@ impl WritableLoadOrder for TimestampBasedLoadOrder {
         activate(self, plugin_name)
     }
 
+    fn activate_many(&mut self, plugin_names: &[&str]) -> Result<(), Error> {
+        activate_many(self, plugin_names)
+    }
+
+    fn activate_with_masters(&mut self, plugin_name: &str) -> Result<Vec<String>, Error> {
+        activate_with_masters(self, plugin_name)
+    }
+
     fn deactivate(&mut self, plugin_name: &str) -> Result<(), Error> {
         deactivate(self, plugin_name)
     }
 
+    fn deactivate_temporarily(&mut self, plugin_names: &[&str]) -> Result<ActiveStateToken, Error> {
+        deactivate_temporarily(self, plugin_names)
+    }
+
+    fn restore_active_state(&mut self, token: ActiveStateToken) -> Result<(), Error> {
+        restore_active_state(self, token)
+    }
+
+    fn set_active_range(
+        &mut self,
+        start: usize,
+        end: usize,
+        active: bool,
+    ) -> Result<Vec<String>, Error> {
+        set_active_range(self, start, end, active)
+    }
+
     fn set_active_plugins(&mut self, active_plugin_names: &[&str]) -> Result<(), Error> {
         set_active_plugins(self, active_plugin_names)
     }
+
+    fn set_active_plugins_prioritized(
+        &mut self,
+        active_plugin_names: &[&str],
+    ) -> Result<Vec<String>, Error> {
+        set_active_plugins_prioritized(self, active_plugin_names)
+    }
+
+    fn reorder_masters(&mut self, master_order: &[&str]) -> Result<(), Error> {
+        reorder_masters(self, master_order)
+    }
+
+    fn changed_plugins_since_load(&self) -> Result<Vec<String>, Error> {
+        changed_plugins_since_load(self)
+    }
+
+    fn refresh_implicitly_active(&mut self) -> Result<(), Error> {
+        self.game_settings.refresh_implicitly_active_plugins()?;
+
+        self.add_implicitly_active_plugins()
+    }
+
+    fn state_fingerprint(&self) -> Result<StateFingerprint, Error> {
+        state_fingerprint(self)
+    }
+
+    fn lock_plugin(&mut self, plugin_name: &str) {
+        lock_plugin(self, plugin_name)
+    }
+
+    fn unlock_plugin(&mut self, plugin_name: &str) {
+        unlock_plugin(self, plugin_name)
+    }
+
+    fn is_locked(&self, plugin_name: &str) -> bool {
+        is_locked(self, plugin_name)
+    }
+
+    fn merge_new_plugins(&mut self) -> Result<Vec<String>, Error> {
+        merge_new_plugins(self)
+    }
+
+    fn clear(&mut self) {
+        clear(self)
+    }
+
+    fn stale_active_entries(&self) -> Result<Vec<String>, Error> {
+        let regex = Regex::new(r"(?i)GameFile[0-9]{1,3}=(.+\.es(?:m|p))")?;
+        let game_id = self.game_settings().id();
+
+        stale_active_entries(self, |line| plugin_line_mapper(line, &regex, game_id))
+    }
+
+    fn verify_active_file_matches(&self) -> Result<bool, Error> {
+        let regex = Regex::new(r"(?i)GameFile[0-9]{1,3}=(.+\.es(?:m|p))")?;
+        let game_id = self.game_settings().id();
+
+        verify_active_file_matches(self, |line| plugin_line_mapper(line, &regex, game_id))
+    }
 }
 
-fn load_plugins_from_dir<T: ReadableLoadOrderExt>(load_order: &T) -> Vec<Plugin> {
-    let filenames = load_order.find_plugins_in_dir();
+fn load_plugins_from_dir<T: ReadableLoadOrderExt, F: Fn(&str) -> bool>(
+    load_order: &T,
+    filter: F,
+    progress: &(dyn Fn(usize, usize) + Sync),
+) -> Result<Vec<Plugin>, Error> {
+    let mut filenames = load_order.find_plugins_in_dir()?;
+    filenames.retain(|filename| filter(filename));
     let game_settings = load_order.game_settings();
+    let completed = AtomicUsize::new(0);
 
-    filenames
+    let plugins = filenames
         .par_iter()
-        .filter_map(|f| Plugin::new(&f, game_settings).ok())
-        .collect()
+        .filter_map(|f| {
+            let plugin = Plugin::new(&f, game_settings).ok();
+            progress(completed.fetch_add(1, AtomicOrdering::SeqCst) + 1, filenames.len());
+            plugin
+        })
+        .collect();
+
+    Ok(plugins)
 }
 
-fn plugin_sorter(a: &Plugin, b: &Plugin) -> Ordering {
+fn plugin_sorter<F>(a: &Plugin, b: &Plugin, tiebreaker: F) -> Ordering
+where
+    F: Fn(&Plugin, &Plugin) -> Ordering,
+{
     if a.is_master_file() == b.is_master_file() {
         match a.modification_time().cmp(&b.modification_time()) {
-            Ordering::Equal => a.name().cmp(&b.name()),
+            Ordering::Equal => tiebreaker(a, b).then_with(|| a.name().cmp(&b.name())),
             x => x,
         }
     } else if a.is_master_file() {
@@ -201,14 +724,30 @@ fn plugin_line_mapper(mut line: &str, regex: &Regex, game_id: GameId) -> Option<
     }
 }
 
-fn padded_unique_timestamps(plugins: &[Plugin]) -> Vec<SystemTime> {
+#[cfg(windows)]
+fn timestamp_increment(spacing: TimestampSpacing) -> Duration {
+    match spacing {
+        TimestampSpacing::Minute => Duration::from_secs(60),
+        TimestampSpacing::Minimal => Duration::from_nanos(100),
+    }
+}
+
+#[cfg(not(windows))]
+fn timestamp_increment(spacing: TimestampSpacing) -> Duration {
+    match spacing {
+        TimestampSpacing::Minute => Duration::from_secs(60),
+        TimestampSpacing::Minimal => Duration::from_secs(1),
+    }
+}
+
+fn padded_unique_timestamps(plugins: &[Plugin], increment: Duration) -> Vec<SystemTime> {
     let mut timestamps: Vec<SystemTime> = plugins.iter().map(Plugin::modification_time).collect();
 
     timestamps.sort();
     timestamps.dedup();
 
     while timestamps.len() < plugins.len() {
-        let timestamp = *timestamps.last().unwrap_or(&UNIX_EPOCH) + Duration::from_secs(60);
+        let timestamp = *timestamps.last().unwrap_or(&UNIX_EPOCH) + increment;
         timestamps.push(timestamp);
     }
 
@@ -216,22 +755,10 @@ fn padded_unique_timestamps(plugins: &[Plugin]) -> Vec<SystemTime> {
 }
 
 fn save_active_plugins<T: ReadableLoadOrderExt>(load_order: &mut T) -> Result<(), Error> {
-    create_parent_dirs(load_order.game_settings().active_plugins_file())?;
-
     let prelude = get_file_prelude(load_order.game_settings())?;
+    let content = active_plugins_file_content(load_order, prelude)?;
 
-    let file = File::create(&load_order.game_settings().active_plugins_file())?;
-    let mut writer = BufWriter::new(file);
-    writer.write_all(&prelude)?;
-    for (index, plugin_name) in load_order.active_plugin_names().iter().enumerate() {
-        if load_order.game_settings().id() == GameId::Morrowind {
-            write!(writer, "GameFile{}=", index)?;
-        }
-        writer.write_all(&WINDOWS_1252
-            .encode(plugin_name, EncoderTrap::Strict)
-            .map_err(Error::EncodeError)?)?;
-        writeln!(writer)?;
-    }
+    create_file(load_order.game_settings().active_plugins_file())?.write_all(&content)?;
 
     Ok(())
 }
@@ -260,12 +787,15 @@ fn get_file_prelude(game_settings: &GameSettings) -> Result<Vec<u8>, Error> {
 mod tests {
     use super::*;
 
+    use encoding::all::WINDOWS_1252;
+    use encoding::{DecoderTrap, Encoding};
     use enums::GameId;
     use filetime::{set_file_times, FileTime};
     use load_order::tests::*;
-    use std::fs::{remove_dir_all, File};
+    use std::fs::{remove_dir_all, remove_file, File};
     use std::io::{Read, Write};
     use std::path::Path;
+    use std::thread::sleep;
     use tempfile::tempdir;
     use tests::copy_to_test_dir;
 
@@ -274,7 +804,31 @@ mod tests {
         TimestampBasedLoadOrder {
             game_settings,
             plugins,
+            locked_plugins: Vec::new(),
+            subset_loaded: false,
+            tiebreak: TimestampTiebreak::Filename,
+            spacing: TimestampSpacing::Minute,
+            held_lock: None,
+        }
+    }
+
+    fn prepare_with_tied_plugins(
+        game_dir: &Path,
+        tiebreak: TimestampTiebreak,
+    ) -> TimestampBasedLoadOrder {
+        let mut load_order = prepare(GameId::Oblivion, game_dir);
+        load_order.set_tiebreak(tiebreak);
+
+        copy_to_test_dir("Blank.esp", "B.esp", load_order.game_settings());
+        sleep(Duration::from_millis(10));
+        copy_to_test_dir("Blank.esp", "A.esp", load_order.game_settings());
+
+        for plugin_name in &["A.esp", "B.esp"] {
+            let path = load_order.game_settings().plugins_directory().join(plugin_name);
+            set_file_times(&path, FileTime::zero(), FileTime::zero()).unwrap();
         }
+
+        load_order
     }
 
     fn write_file(path: &Path) {
@@ -383,8 +937,8 @@ mod tests {
         load_order.load().unwrap();
 
         let expected_filenames = vec![
-            "Blank.esm",
             load_order.game_settings().master_file(),
+            "Blank.esm",
             "Blank - Master Dependent.esp",
             "Blank - Different.esp",
             "Blank.esp",
@@ -394,6 +948,96 @@ mod tests {
         assert_eq!(expected_filenames, load_order.plugin_names());
     }
 
+    #[test]
+    fn new_should_default_to_breaking_timestamp_ties_by_filename() {
+        let tmp_dir = tempdir().unwrap();
+        let load_order = prepare(GameId::Oblivion, tmp_dir.path());
+        let settings = load_order.game_settings().clone();
+
+        assert_eq!(
+            TimestampTiebreak::Filename,
+            TimestampBasedLoadOrder::new(settings).tiebreak()
+        );
+    }
+
+    #[test]
+    fn load_should_break_timestamp_ties_by_filename() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare_with_tied_plugins(tmp_dir.path(), TimestampTiebreak::Filename);
+
+        load_order.load().unwrap();
+
+        assert!(load_order.index_of("A.esp").unwrap() < load_order.index_of("B.esp").unwrap());
+    }
+
+    #[test]
+    fn load_should_break_timestamp_ties_by_active_plugins_file_order() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order =
+            prepare_with_tied_plugins(tmp_dir.path(), TimestampTiebreak::ActiveFileOrder);
+
+        write_active_plugins_file(load_order.game_settings(), &["B.esp", "A.esp"]);
+
+        load_order.load().unwrap();
+
+        assert!(load_order.index_of("B.esp").unwrap() < load_order.index_of("A.esp").unwrap());
+        assert!(load_order.is_active("A.esp"));
+        assert!(load_order.is_active("B.esp"));
+    }
+
+    #[test]
+    fn load_should_break_timestamp_ties_by_creation_time() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order =
+            prepare_with_tied_plugins(tmp_dir.path(), TimestampTiebreak::CreationTime);
+
+        // Not all filesystems record creation times, and those that do may not
+        // record them precisely enough to tell the two plugins apart.
+        let plugins_directory = load_order.game_settings().plugins_directory();
+        let created = |name| plugins_directory.join(name).metadata().and_then(|m| m.created());
+        match (created("B.esp"), created("A.esp")) {
+            (Ok(b), Ok(a)) if b < a => {}
+            _ => return,
+        }
+
+        load_order.load().unwrap();
+
+        assert!(load_order.index_of("B.esp").unwrap() < load_order.index_of("A.esp").unwrap());
+    }
+
+    #[test]
+    fn load_should_sort_an_esp_file_with_the_master_flag_set_before_non_master_files() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        copy_to_test_dir(
+            "Blank.esm",
+            "Blank - Master.esp",
+            load_order.game_settings(),
+        );
+        set_timestamps(
+            &load_order.game_settings().plugins_directory(),
+            &[
+                load_order.game_settings().master_file(),
+                "Blank.esp",
+                "Blank - Master.esp",
+                "Blank.esm",
+            ],
+        );
+
+        load_order.load().unwrap();
+
+        let plugin_names = load_order.plugin_names();
+        let master_index = plugin_names
+            .iter()
+            .position(|n| *n == "Blank - Master.esp")
+            .unwrap();
+        let first_non_master_index = plugin_names.iter().position(|n| *n == "Blank.esp").unwrap();
+
+        assert_eq!(1, master_index);
+        assert!(master_index < first_non_master_index);
+    }
+
     #[test]
     fn load_should_empty_the_load_order_if_the_plugins_directory_does_not_exist() {
         let tmp_dir = tempdir().unwrap();
@@ -405,6 +1049,26 @@ mod tests {
         assert!(load_order.plugins().is_empty());
     }
 
+    #[test]
+    fn load_and_report_unghosted_plugins_should_unghost_and_report_active_ghosted_plugins() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        copy_to_test_dir("Blank.esm", "Ghosted.esm.ghost", load_order.game_settings());
+        copy_to_test_dir("Blank.esp", "Inactive.esp.ghost", load_order.game_settings());
+        write_active_plugins_file(load_order.game_settings(), &["Ghosted.esm", "Blank.esp"]);
+
+        let unghosted = load_order.load_and_report_unghosted_plugins().unwrap();
+
+        assert_eq!(vec!["Ghosted.esm"], unghosted);
+        assert!(load_order.is_active("Ghosted.esm"));
+
+        let plugins_dir = load_order.game_settings().plugins_directory();
+        assert!(plugins_dir.join("Ghosted.esm").exists());
+        assert!(!plugins_dir.join("Ghosted.esm.ghost").exists());
+        assert!(plugins_dir.join("Inactive.esp.ghost").exists());
+    }
+
     #[test]
     fn load_should_decode_active_plugins_file_from_windows_1252() {
         let tmp_dir = tempdir().unwrap();
@@ -476,6 +1140,29 @@ mod tests {
         assert_eq!(expected_filenames, load_order.active_plugin_names());
     }
 
+    #[test]
+    fn load_should_leave_out_quarantined_plugins() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        write_active_plugins_file(load_order.game_settings(), &["Blàñk.esp", "Blank.esm"]);
+        load_order
+            .game_settings
+            .set_quarantined_plugins(vec!["blàñk.esp".to_string(), "Blank.esm".to_string()]);
+
+        load_order.load().unwrap();
+
+        assert!(load_order.index_of("Blàñk.esp").is_none());
+        assert!(load_order.index_of("Blank.esm").is_none());
+        assert!(load_order.index_of("Blank.esp").is_some());
+        assert!(load_order.active_plugin_names().is_empty());
+
+        match load_order.activate("Blank.esm").unwrap_err() {
+            Error::InvalidPlugin(_) => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+    }
+
     #[test]
     fn load_should_succeed_when_active_plugins_file_is_missing() {
         let tmp_dir = tempdir().unwrap();
@@ -485,54 +1172,319 @@ mod tests {
         assert!(load_order.active_plugin_names().is_empty());
     }
 
+    #[test]
+    fn load_should_succeed_without_adding_the_game_master_if_it_is_missing() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Morrowind, tmp_dir.path());
+
+        let master_file = load_order.game_settings().master_file().to_string();
+        remove_file(load_order.game_settings().plugins_directory().join(&master_file)).unwrap();
+
+        assert!(load_order.load().is_ok());
+        assert!(!load_order.master_file_present());
+        assert!(load_order.index_of(&master_file).is_none());
+        assert!(load_order.index_of("Blank.esp").is_some());
+    }
+
+    #[test]
+    fn master_file_present_should_be_true_if_the_game_master_is_in_the_load_order() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Morrowind, tmp_dir.path());
+
+        load_order.load().unwrap();
+        assert!(load_order.master_file_present());
+    }
+
     #[test]
     fn load_should_load_plugin_states_from_active_plugins_file_for_morrowind() {
         let tmp_dir = tempdir().unwrap();
-        let mut load_order = prepare(GameId::Morrowind, &tmp_dir.path());
+        let mut load_order = prepare(GameId::Morrowind, &tmp_dir.path());
+
+        write_active_plugins_file(load_order.game_settings(), &["Blàñk.esp", "Blank.esm"]);
+
+        load_order.load().unwrap();
+        let expected_filenames = vec!["Blank.esm", "Blàñk.esp"];
+
+        assert_eq!(expected_filenames, load_order.active_plugin_names());
+    }
+
+    #[test]
+    fn load_should_deactivate_excess_plugins() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, &tmp_dir.path());
+
+        let mut plugins: Vec<String> = Vec::new();
+        plugins.push(load_order.game_settings().master_file().to_string());
+        for i in 0..260 {
+            plugins.push(format!("Blank{}.esm", i));
+            copy_to_test_dir(
+                "Blank.esm",
+                &plugins.last().unwrap(),
+                load_order.game_settings(),
+            );
+        }
+
+        {
+            let plugins_as_ref: Vec<&str> = plugins.iter().map(AsRef::as_ref).collect();
+            write_active_plugins_file(load_order.game_settings(), &plugins_as_ref);
+            set_timestamps(
+                &load_order.game_settings().plugins_directory(),
+                &plugins_as_ref,
+            );
+        }
+
+        plugins = plugins[0..255].to_vec();
+
+        load_order.load().unwrap();
+        let active_plugin_names = load_order.active_plugin_names();
+
+        assert_eq!(255, active_plugin_names.len());
+        for i in 0..255 {
+            assert_eq!(plugins[i], active_plugin_names[i]);
+        }
+        assert_eq!(plugins, active_plugin_names);
+    }
+
+    #[test]
+    fn load_should_deactivate_plugins_in_excess_of_a_max_directive_in_the_active_plugins_file() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        let mut plugins: Vec<String> = Vec::new();
+        plugins.push(load_order.game_settings().master_file().to_string());
+        for i in 0..210 {
+            plugins.push(format!("Blank{}.esm", i));
+            copy_to_test_dir(
+                "Blank.esm",
+                plugins.last().unwrap(),
+                load_order.game_settings(),
+            );
+        }
+
+        {
+            let mut file = File::create(load_order.game_settings().active_plugins_file()).unwrap();
+            writeln!(file, "# max=200").unwrap();
+            writeln!(file, "# unknown=directive").unwrap();
+            for plugin in &plugins {
+                writeln!(file, "{}", plugin).unwrap();
+            }
+
+            let plugins_as_ref: Vec<&str> = plugins.iter().map(AsRef::as_ref).collect();
+            set_timestamps(
+                &load_order.game_settings().plugins_directory(),
+                &plugins_as_ref,
+            );
+        }
+
+        load_order.load().unwrap();
+
+        assert_eq!(plugins[..200].to_vec(), load_order.active_plugin_names());
+    }
+
+    #[test]
+    fn load_should_ignore_a_max_directive_that_is_higher_than_the_game_limit() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        {
+            let mut file = File::create(load_order.game_settings().active_plugins_file()).unwrap();
+            writeln!(file, "# max=1000").unwrap();
+            writeln!(file, "Blank.esp").unwrap();
+        }
+
+        load_order.load().unwrap();
+
+        assert_eq!(vec!["Blank.esp"], load_order.active_plugin_names());
+    }
+
+    #[test]
+    fn activate_should_respect_a_max_directive_read_on_load() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        {
+            let mut file = File::create(load_order.game_settings().active_plugins_file()).unwrap();
+            writeln!(file, "# max=1").unwrap();
+            writeln!(file, "Blank.esp").unwrap();
+        }
+
+        load_order.load().unwrap();
+
+        assert!(load_order.activate("Blank - Different.esp").is_err());
+        assert!(!load_order.is_active("Blank - Different.esp"));
+        assert_eq!(0, load_order.remaining_slots().0);
+        assert_eq!(1, load_order.active_count_status().limit);
+    }
+
+    #[test]
+    fn save_should_write_back_a_max_directive_read_on_load() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        {
+            let mut file = File::create(load_order.game_settings().active_plugins_file()).unwrap();
+            writeln!(file, "# max=100").unwrap();
+            writeln!(file, "Blank.esp").unwrap();
+        }
+
+        load_order.load().unwrap();
+        load_order.save().unwrap();
+        load_order.load().unwrap();
+
+        assert_eq!(Some(100), load_order.game_settings().active_plugins_limit());
+        let mut content = String::new();
+        File::open(load_order.game_settings().active_plugins_file())
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert!(content.starts_with("# max=100"));
+    }
+
+    #[test]
+    fn load_and_report_excess_plugins_should_return_the_deactivated_plugins_in_load_order() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        let mut plugins: Vec<String> = Vec::new();
+        plugins.push(load_order.game_settings().master_file().to_string());
+        for i in 0..260 {
+            plugins.push(format!("Blank{}.esm", i));
+            copy_to_test_dir(
+                "Blank.esm",
+                plugins.last().unwrap(),
+                load_order.game_settings(),
+            );
+        }
+
+        {
+            let plugins_as_ref: Vec<&str> = plugins.iter().map(AsRef::as_ref).collect();
+            write_active_plugins_file(load_order.game_settings(), &plugins_as_ref);
+            set_timestamps(
+                &load_order.game_settings().plugins_directory(),
+                &plugins_as_ref,
+            );
+        }
+
+        let excess_plugins = load_order.load_and_report_excess_plugins().unwrap();
+
+        assert_eq!(plugins[255..].to_vec(), excess_plugins);
+        assert_eq!(255, load_order.active_plugin_names().len());
+    }
+
+    #[test]
+    fn excess_active_plugins_should_return_active_plugins_past_the_limit_in_load_order() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        let mut plugins: Vec<String> = Vec::new();
+        plugins.push(load_order.game_settings().master_file().to_string());
+        for i in 0..260 {
+            plugins.push(format!("Blank{}.esm", i));
+            copy_to_test_dir(
+                "Blank.esm",
+                plugins.last().unwrap(),
+                load_order.game_settings(),
+            );
+        }
+
+        {
+            let plugins_as_ref: Vec<&str> = plugins.iter().map(AsRef::as_ref).collect();
+            set_timestamps(
+                &load_order.game_settings().plugins_directory(),
+                &plugins_as_ref,
+            );
+        }
+
+        load_order.load().unwrap();
+        assert!(load_order.excess_active_plugins().is_empty());
+
+        for plugin in load_order.plugins_mut() {
+            if plugins.iter().any(|p| plugin.name_matches(p)) {
+                plugin.activate().unwrap();
+            }
+        }
+
+        assert_eq!(plugins[255..].to_vec(), load_order.excess_active_plugins());
+    }
+
+    #[test]
+    fn on_disk_timestamps_should_reflect_external_changes_to_plugin_timestamps() {
+        let tmp_dir = tempdir().unwrap();
+        let load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        let plugin_path = load_order.game_settings().plugins_directory().join("Blank.esp");
+        set_file_times(&plugin_path, FileTime::zero(), FileTime::zero()).unwrap();
+
+        let timestamps = load_order.on_disk_timestamps().unwrap();
+        let blank_index = load_order.index_of("Blank.esp").unwrap();
+
+        assert_eq!(load_order.plugin_names().len(), timestamps.len());
+        assert_eq!("Blank.esp", timestamps[blank_index].0);
+        assert_eq!(UNIX_EPOCH, timestamps[blank_index].1);
+        assert_ne!(UNIX_EPOCH, load_order.plugins()[blank_index].modification_time());
+    }
+
+    #[test]
+    fn planned_timestamp_for_should_match_the_timestamp_that_save_writes() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
 
-        write_active_plugins_file(load_order.game_settings(), &["Blàñk.esp", "Blank.esm"]);
+        load_order.set_plugin_index("Blank.esp", 2).unwrap();
+        let planned_timestamp = load_order.planned_timestamp_for("Blank.esp").unwrap();
 
-        load_order.load().unwrap();
-        let expected_filenames = vec!["Blank.esm", "Blàñk.esp"];
+        load_order.save().unwrap();
 
-        assert_eq!(expected_filenames, load_order.active_plugin_names());
+        let on_disk_timestamp = load_order
+            .on_disk_timestamps()
+            .unwrap()
+            .into_iter()
+            .find(|(name, _)| name == "Blank.esp")
+            .map(|(_, time)| time);
+        assert_eq!(Some(planned_timestamp), on_disk_timestamp);
+        assert_eq!(None, load_order.planned_timestamp_for("missing.esp"));
     }
 
     #[test]
-    fn load_should_deactivate_excess_plugins() {
+    fn rewrite_timestamps_should_write_increasing_timestamps_in_load_order() {
         let tmp_dir = tempdir().unwrap();
-        let mut load_order = prepare(GameId::Oblivion, &tmp_dir.path());
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
 
-        let mut plugins: Vec<String> = Vec::new();
-        plugins.push(load_order.game_settings().master_file().to_string());
-        for i in 0..260 {
-            plugins.push(format!("Blank{}.esm", i));
-            copy_to_test_dir(
-                "Blank.esm",
-                &plugins.last().unwrap(),
-                load_order.game_settings(),
-            );
+        let plugin_names = to_owned(load_order.plugin_names());
+        for (i, plugin_name) in plugin_names.iter().rev().enumerate() {
+            let path = load_order.game_settings().plugins_directory().join(plugin_name);
+            let time = FileTime::from_unix_time(i as i64 * 100, 0);
+            set_file_times(&path, time, time).unwrap();
         }
-
-        {
-            let plugins_as_ref: Vec<&str> = plugins.iter().map(AsRef::as_ref).collect();
-            write_active_plugins_file(load_order.game_settings(), &plugins_as_ref);
-            set_timestamps(
-                &load_order.game_settings().plugins_directory(),
-                &plugins_as_ref,
-            );
+        if load_order.game_settings().active_plugins_file().exists() {
+            remove_file(load_order.game_settings().active_plugins_file()).unwrap();
         }
 
-        plugins = plugins[0..255].to_vec();
+        load_order.rewrite_timestamps().unwrap();
 
-        load_order.load().unwrap();
-        let active_plugin_names = load_order.active_plugin_names();
+        let timestamps = load_order.on_disk_timestamps().unwrap();
+        assert!(timestamps.windows(2).all(|w| w[0].1 < w[1].1));
+        assert_eq!(
+            plugin_names,
+            timestamps.into_iter().map(|(n, _)| n).collect::<Vec<_>>()
+        );
+        assert!(!load_order.game_settings().active_plugins_file().exists());
+    }
 
-        assert_eq!(255, active_plugin_names.len());
-        for i in 0..255 {
-            assert_eq!(plugins[i], active_plugin_names[i]);
+    #[test]
+    fn rewrite_timestamps_should_error_without_writing_if_the_lock_is_held() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+        load_order.game_settings.set_lock_on_save(true);
+        load_order.set_plugin_index("Blank - Different.esp", 1).unwrap();
+
+        let timestamps = load_order.on_disk_timestamps().unwrap();
+        let lock = ActivePluginsFileLock::acquire(load_order.game_settings()).unwrap();
+
+        match load_order.rewrite_timestamps().unwrap_err() {
+            Error::LoadOrderLocked(ref p) if p == lock.path() => {}
+            e => panic!("Wrong error type: {:?}", e),
         }
-        assert_eq!(plugins, active_plugin_names);
+        assert_eq!(timestamps, load_order.on_disk_timestamps().unwrap());
     }
 
     #[test]
@@ -616,6 +1568,50 @@ mod tests {
         assert_eq!(old_timestamps, timestamps);
     }
 
+    fn save_with_tied_timestamps(spacing: TimestampSpacing, game_dir: &Path) -> Vec<SystemTime> {
+        let mut load_order = prepare(GameId::Oblivion, game_dir);
+        load_order.set_spacing(spacing);
+
+        let time = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        for plugin in load_order.plugins_mut() {
+            plugin.set_modification_time(time).unwrap();
+        }
+
+        load_order.save().unwrap();
+
+        load_order
+            .on_disk_timestamps()
+            .unwrap()
+            .into_iter()
+            .map(|(_, t)| t)
+            .collect()
+    }
+
+    #[test]
+    fn save_should_space_added_timestamps_by_the_minimal_increment_if_set() {
+        let tmp_dir = tempdir().unwrap();
+        let timestamps = save_with_tied_timestamps(TimestampSpacing::Minimal, tmp_dir.path());
+
+        let increment = timestamp_increment(TimestampSpacing::Minimal);
+        assert!(increment < Duration::from_secs(60));
+        assert_eq!(
+            vec![timestamps[0], timestamps[0] + increment, timestamps[0] + increment * 2],
+            timestamps
+        );
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn save_should_space_added_timestamps_by_100_nanoseconds_on_windows_if_minimal() {
+        let tmp_dir = tempdir().unwrap();
+        let timestamps = save_with_tied_timestamps(TimestampSpacing::Minimal, tmp_dir.path());
+
+        assert_eq!(
+            Duration::from_nanos(100),
+            timestamps[1].duration_since(timestamps[0]).unwrap()
+        );
+    }
+
     #[test]
     fn save_should_create_active_plugins_file_parent_directory_if_it_does_not_exist() {
         let tmp_dir = tempdir().unwrap();
@@ -670,6 +1666,75 @@ mod tests {
             .read_to_string(&mut content)
             .unwrap();
         assert!(content.contains("isrealmorrowindini=false\n[Game Files]\n"));
+        assert!(content.ends_with("GameFile0=Blank.esp\r\n"));
+    }
+
+    #[test]
+    fn active_plugins_file_content_should_match_the_saved_active_plugins_file() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Morrowind, tmp_dir.path());
+
+        write_active_plugins_file(load_order.game_settings(), &["Blank.esp"]);
+        load_order.activate("Blàñk.esp").unwrap();
+
+        let text = load_order.active_plugins_file_content().unwrap();
+
+        load_order.save().unwrap();
+
+        let mut content = Vec::new();
+        File::open(load_order.game_settings().active_plugins_file())
+            .unwrap()
+            .read_to_end(&mut content)
+            .unwrap();
+        let content = WINDOWS_1252.decode(&content, DecoderTrap::Strict).unwrap();
+
+        assert!(text.contains("[Game Files]"));
+        assert!(text.ends_with("GameFile1=Blàñk.esp\r\n"));
+        assert_eq!(content, text);
+    }
+
+    #[test]
+    fn save_would_change_should_compare_the_active_plugins_file_including_its_prelude() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Morrowind, tmp_dir.path());
+
+        write_active_plugins_file(load_order.game_settings(), &["Blank.esp"]);
+        load_order.load().unwrap();
+        assert!(load_order.save_would_change().unwrap());
+
+        load_order.save().unwrap();
+        load_order.load().unwrap();
+        assert!(!load_order.save_would_change().unwrap());
+
+        load_order.activate("Blank - Different.esp").unwrap();
+        assert!(load_order.save_would_change().unwrap());
+    }
+
+    #[test]
+    fn active_plugins_as_written_should_match_the_saved_active_plugins_file_for_morrowind() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Morrowind, tmp_dir.path());
+
+        write_active_plugins_file(load_order.game_settings(), &["Blàñk.esp", "Blank.esm"]);
+        load_order.activate("Blank - Different.esp").unwrap();
+        load_order.save().unwrap();
+
+        let mut content = String::new();
+        File::open(load_order.game_settings().active_plugins_file())
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        let lines: Vec<&str> = content
+            .lines()
+            .skip_while(|l| *l != "[Game Files]")
+            .skip(1)
+            .collect();
+
+        assert_eq!(
+            vec!["GameFile0=Blank.esp", "GameFile1=Blank - Different.esp"],
+            load_order.active_plugins_as_written()
+        );
+        assert_eq!(load_order.active_plugins_as_written(), lines);
     }
 
     #[test]
@@ -694,6 +1759,20 @@ mod tests {
         assert_eq!(existing_filenames, load_order.plugin_names());
     }
 
+    #[test]
+    fn set_load_order_should_error_if_given_a_plugin_name_containing_a_parent_path() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Morrowind, tmp_dir.path());
+
+        let existing_filenames = to_owned(load_order.plugin_names());
+        let filenames = vec!["Blank.esm", "../evil.esp"];
+        match load_order.set_load_order(&filenames).unwrap_err() {
+            Error::InvalidPluginName(ref x) if x == "../evil.esp" => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(existing_filenames, load_order.plugin_names());
+    }
+
     #[test]
     fn set_load_order_should_error_if_given_a_list_with_plugins_before_masters() {
         let tmp_dir = tempdir().unwrap();
@@ -761,6 +1840,96 @@ mod tests {
         assert!(load_order.is_active("Blank.esp"));
     }
 
+    fn prepare_with_locked_plugin(game_dir: &Path) -> TimestampBasedLoadOrder {
+        let mut load_order = prepare(GameId::Morrowind, game_dir);
+
+        load_order
+            .set_load_order(&[
+                "Morrowind.esm",
+                "Blank.esm",
+                "Blank.esp",
+                "Blank - Different.esp",
+                "Blank - Master Dependent.esp",
+            ])
+            .unwrap();
+        load_order.lock_plugin("Blank.esp");
+
+        load_order
+    }
+
+    #[test]
+    fn set_plugin_index_should_error_if_moving_a_locked_plugin() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare_with_locked_plugin(tmp_dir.path());
+
+        let existing_filenames = to_owned(load_order.plugin_names());
+        match load_order.set_plugin_index("Blank.esp", 4).unwrap_err() {
+            Error::PluginLocked(x) => assert_eq!("Blank.esp", x),
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(existing_filenames, load_order.plugin_names());
+    }
+
+    #[test]
+    fn set_plugin_index_should_error_if_moving_a_plugin_would_shift_a_locked_plugin() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare_with_locked_plugin(tmp_dir.path());
+
+        let existing_filenames = to_owned(load_order.plugin_names());
+        match load_order
+            .set_plugin_index("Blank - Master Dependent.esp", 2)
+            .unwrap_err()
+        {
+            Error::PluginLocked(x) => assert_eq!("Blank.esp", x),
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(existing_filenames, load_order.plugin_names());
+    }
+
+    #[test]
+    fn set_plugin_index_should_move_plugins_around_a_locked_plugin() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare_with_locked_plugin(tmp_dir.path());
+
+        load_order
+            .set_plugin_index("Blank - Master Dependent.esp", 3)
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                "Morrowind.esm",
+                "Blank.esm",
+                "Blank.esp",
+                "Blank - Master Dependent.esp",
+                "Blank - Different.esp",
+            ],
+            load_order.plugin_names()
+        );
+    }
+
+    #[test]
+    fn set_load_order_should_error_if_a_locked_plugin_would_move() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare_with_locked_plugin(tmp_dir.path());
+
+        let filenames = vec![
+            "Morrowind.esm",
+            "Blank.esm",
+            "Blank - Different.esp",
+            "Blank.esp",
+            "Blank - Master Dependent.esp",
+        ];
+        match load_order.set_load_order(&filenames).unwrap_err() {
+            Error::PluginLocked(x) => assert_eq!("Blank.esp", x),
+            e => panic!("Wrong error type: {:?}", e),
+        }
+
+        load_order.unlock_plugin("blank.esp");
+        assert!(!load_order.is_locked("Blank.esp"));
+        load_order.set_load_order(&filenames).unwrap();
+        assert_eq!(filenames, load_order.plugin_names());
+    }
+
     #[test]
     fn set_plugin_index_should_error_if_inserting_a_non_master_before_a_master() {
         let tmp_dir = tempdir().unwrap();
@@ -805,6 +1974,25 @@ mod tests {
         assert_eq!(existing_filenames, load_order.plugin_names());
     }
 
+    #[test]
+    fn set_plugin_index_should_error_if_moving_the_game_master_from_the_first_index() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        load_order.set_plugin_index("Blank.esm", 1).unwrap();
+
+        let existing_filenames = to_owned(load_order.plugin_names());
+        match load_order.set_plugin_index("Oblivion.esm", 1).unwrap_err() {
+            Error::GameMasterMustLoadFirst => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        match load_order.set_plugin_index("Blank.esm", 0).unwrap_err() {
+            Error::GameMasterMustLoadFirst => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(existing_filenames, load_order.plugin_names());
+    }
+
     #[test]
     fn set_plugin_index_should_error_if_setting_the_index_of_an_invalid_plugin() {
         let tmp_dir = tempdir().unwrap();
@@ -815,6 +2003,30 @@ mod tests {
         assert_eq!(existing_filenames, load_order.plugin_names());
     }
 
+    #[test]
+    fn set_plugin_index_should_error_if_given_a_plugin_name_containing_a_slash() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Morrowind, tmp_dir.path());
+
+        let existing_filenames = to_owned(load_order.plugin_names());
+        match load_order.set_plugin_index("Blank/evil.esp", 1).unwrap_err() {
+            Error::InvalidPluginName(ref x) if x == "Blank/evil.esp" => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(existing_filenames, load_order.plugin_names());
+    }
+
+    #[test]
+    fn set_plugin_index_should_error_if_given_a_plugin_name_containing_a_null_byte() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Morrowind, tmp_dir.path());
+
+        match load_order.set_plugin_index("Blank\0.esp", 1).unwrap_err() {
+            Error::InvalidPluginName(_) => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+    }
+
     #[test]
     fn set_plugin_index_should_insert_a_new_plugin() {
         let tmp_dir = tempdir().unwrap();
@@ -826,6 +2038,22 @@ mod tests {
         assert_eq!(num_plugins + 1, load_order.plugins().len());
     }
 
+    #[test]
+    fn set_plugin_index_should_keep_a_valid_order_for_every_index_in_the_valid_index_range() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        load_order.set_plugin_index("Blank.esm", 1).unwrap();
+        assert_eq!(1..2, load_order.valid_index_range("Blank.esm").unwrap());
+
+        for plugin_name in &["Blank.esm", "Blank.esp"] {
+            for index in load_order.valid_index_range(plugin_name).unwrap() {
+                load_order.set_plugin_index(plugin_name, index).unwrap();
+                assert!(load_order.is_ordering_valid());
+            }
+        }
+    }
+
     #[test]
     fn set_plugin_index_should_move_an_existing_plugin() {
         let tmp_dir = tempdir().unwrap();
@@ -870,6 +2098,91 @@ mod tests {
         assert!(!load_order.is_active("Blank - Different.esp"));
     }
 
+    #[test]
+    fn reorder_masters_should_move_listed_masters_first_and_leave_non_masters_untouched() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        copy_to_test_dir(
+            "Blank - Different.esm",
+            "Blank - Different.esm",
+            load_order.game_settings(),
+        );
+        load_order.set_plugin_index("Blank.esm", 1).unwrap();
+        load_order
+            .set_plugin_index("Blank - Different.esm", 2)
+            .unwrap();
+
+        let master_order = ["Blank - Different.esm", "Blank.esm"];
+        load_order.reorder_masters(&master_order).unwrap();
+
+        let expected_filenames = vec![
+            "Oblivion.esm",
+            "Blank - Different.esm",
+            "Blank.esm",
+            "Blank.esp",
+            "Blank - Different.esp",
+        ];
+        assert_eq!(expected_filenames, load_order.plugin_names());
+        assert!(load_order.is_active("Blank.esp"));
+    }
+
+    #[test]
+    fn reorder_masters_should_error_if_the_game_master_would_not_load_first() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        load_order.set_plugin_index("Blank.esm", 1).unwrap();
+
+        let existing_filenames = to_owned(load_order.plugin_names());
+        match load_order
+            .reorder_masters(&["Blank.esm", "Oblivion.esm"])
+            .unwrap_err()
+        {
+            Error::GameMasterMustLoadFirst => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(existing_filenames, load_order.plugin_names());
+    }
+
+    #[test]
+    fn set_load_order_should_error_if_the_game_master_would_not_load_first() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        let existing_filenames = to_owned(load_order.plugin_names());
+        let filenames = vec!["Blank.esm", "Oblivion.esm", "Blank.esp"];
+        match load_order.set_load_order(&filenames).unwrap_err() {
+            Error::GameMasterMustLoadFirst => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(existing_filenames, load_order.plugin_names());
+    }
+
+    #[test]
+    fn reorder_masters_should_error_if_given_a_non_master() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        let existing_filenames = to_owned(load_order.plugin_names());
+        match load_order.reorder_masters(&["Blank.esp"]).unwrap_err() {
+            Error::InvalidPlugin(ref x) if x == "Blank.esp" => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert_eq!(existing_filenames, load_order.plugin_names());
+    }
+
+    #[test]
+    fn reorder_masters_should_error_if_given_a_plugin_that_is_not_in_the_load_order() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        match load_order.reorder_masters(&["missing.esm"]).unwrap_err() {
+            Error::PluginNotFound(ref x) if x == "missing.esm" => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+    }
+
     #[test]
     fn is_self_consistent_should_return_true() {
         let tmp_dir = tempdir().unwrap();
@@ -17,30 +17,368 @@
  * along with libloadorder. If not, see <http://www.gnu.org/licenses/>.
  */
 
+use std::fs::metadata;
+use std::path::Path;
+use std::time::SystemTime;
+
+use encoding::all::{UTF_8, WINDOWS_1252};
+use encoding::{DecoderTrap, EncoderTrap, Encoding};
 use unicase::eq;
 
 use super::insertable::InsertableLoadOrder;
-use super::mutable::MutableLoadOrder;
-use super::readable::{ReadableLoadOrder, MAX_ACTIVE_LIGHT_MASTERS, MAX_ACTIVE_NORMAL_PLUGINS};
+use super::mutable::{has_utf8_bom, read_plugin_names, MutableLoadOrder, UTF8_BOM};
+use super::readable::{
+    is_active, read_active_plugins_file_raw, ReadableLoadOrder, ReadableLoadOrderExt,
+    MAX_ACTIVE_LIGHT_MASTERS,
+};
+use super::find_first_non_master_position;
 use enums::Error;
-use plugin::Plugin;
+use game_settings::GameSettings;
+use ghostable_path::GhostablePath;
+use plugin::{trim_dot_ghost, Plugin};
 
 pub trait WritableLoadOrder: ReadableLoadOrder {
-    fn load(&mut self) -> Result<(), Error>;
+    fn load(&mut self) -> Result<(), Error> {
+        self.load_and_report_excess_plugins().map(|_| ())
+    }
+
+    /// Load the load order, calling `progress` with the number of plugins
+    /// loaded so far and the total number of plugins to load as each plugin is
+    /// loaded. Plugins are loaded in parallel, so `progress` may be called
+    /// from multiple threads and the counts it receives may be out of order.
+    fn load_with_progress(
+        &mut self,
+        progress: &(dyn Fn(usize, usize) + Sync),
+    ) -> Result<(), Error> {
+        self.load_and_report_excess_plugins_with_progress(progress)
+            .map(|_| ())
+    }
 
+    fn load_and_report_excess_plugins(&mut self) -> Result<Vec<String>, Error> {
+        self.load_and_report_excess_plugins_with_progress(&|_, _| {})
+    }
+
+    fn load_and_report_excess_plugins_with_progress(
+        &mut self,
+        progress: &(dyn Fn(usize, usize) + Sync),
+    ) -> Result<Vec<String>, Error>;
+
+    /// Load the load order, returning the names of active plugins that were
+    /// only installed as ghosted files. Ghosted plugins can't be active, so
+    /// loading always unghosts them.
+    fn load_and_report_unghosted_plugins(&mut self) -> Result<Vec<String>, Error>;
+
+    /// Load only the given plugins and any implicitly active plugins, instead
+    /// of every plugin that is installed. Other installed plugins are left out
+    /// of the load order, so saving it to the game's files errors with
+    /// `Error::SubsetLoaded` until the load order is fully loaded again.
+    fn load_subset(&mut self, plugin_names: &[&str]) -> Result<(), Error>;
+
+    /// Save the load order and active plugins. If any active plugin's file
+    /// no longer exists, this errors with `Error::InvalidPath` for its path
+    /// and nothing is written. If the game settings enable locking on save,
+    /// the `ActivePluginsFileLock` is held while saving, unless the load order
+    /// already holds it, and this errors with `Error::LoadOrderLocked` if it is
+    /// held by anything else.
     fn save(&mut self) -> Result<(), Error>;
 
+    /// Save the load order without changing which plugins are active. For
+    /// textfile-based games this only writes loadorder.txt, and for
+    /// timestamp-based games it only sets plugin timestamps. Other games store
+    /// load order and active plugins together, so this is the same as `save()`.
+    fn save_load_order_only(&mut self) -> Result<(), Error>;
+
+    /// Check if saving would change the content of the active plugins file.
+    /// Other files that saving may write, such as plugin timestamps or
+    /// loadorder.txt, are not checked.
+    fn save_would_change(&self) -> Result<bool, Error>;
+
+    /// Take the `ActivePluginsFileLock` and hold it until it is unlocked or
+    /// the load order is dropped, so that no cooperating process can save in
+    /// between loading and saving. Clones of the load order share the lock.
+    fn lock_active_plugins_file(&mut self) -> Result<(), Error>;
+
+    fn unlock_active_plugins_file(&mut self);
+
     fn set_load_order(&mut self, plugin_names: &[&str]) -> Result<(), Error>;
 
+    fn set_complete_load_order(&mut self, plugin_names: &[&str]) -> Result<(), Error>;
+
+    /// Set the load order, keeping any installed plugins that aren't given.
+    /// Unlisted masters are placed after the last given master and other
+    /// unlisted plugins at the end, keeping their current relative order.
+    fn set_load_order_appending_rest(&mut self, plugin_names: &[&str]) -> Result<(), Error>;
+
+    fn apply_order_from_index(&mut self, start: usize, plugin_names: &[&str]) -> Result<(), Error>;
+
+    fn apply_loot_order(&mut self, sorted_plugin_names: &[&str]) -> Result<Vec<String>, Error>;
+
+    /// Sort the load order by the keys that `key` gives each plugin name. The
+    /// sort is stable, and the game's master file and other masters are then
+    /// moved before non-masters, keeping their sorted relative order.
+    fn sort_by_key<K: Ord, F: Fn(&str) -> K>(&mut self, key: F) -> Result<(), Error>
+    where
+        Self: Sized,
+    {
+        sort_by_key(self, key)
+    }
+
     fn set_plugin_index(&mut self, plugin_name: &str, position: usize) -> Result<(), Error>;
 
+    /// Move the given plugin to the earliest position it can take among the
+    /// plugins of its tier, i.e. among masters or among non-masters. Masters
+    /// are not moved before the game's master file or any implicitly active
+    /// plugins that load first.
+    fn move_to_tier_start(&mut self, plugin_name: &str) -> Result<(), Error>;
+
+    /// Move the given plugin to the latest position it can take among the
+    /// plugins of its tier, i.e. among masters or among non-masters.
+    fn move_to_tier_end(&mut self, plugin_name: &str) -> Result<(), Error>;
+
     fn is_self_consistent(&self) -> Result<bool, Error>;
 
     fn activate(&mut self, plugin_name: &str) -> Result<(), Error>;
 
+    /// Activate all the given plugins, or none of them if any is not
+    /// installed or activating them all would exceed the active limits.
+    fn activate_many(&mut self, plugin_names: &[&str]) -> Result<(), Error>;
+
+    /// Activate the given plugin and its installed masters, including their
+    /// masters, returning the names of the plugins that were activated.
+    /// Nothing is activated if they would not all fit in the active limits.
+    fn activate_with_masters(&mut self, plugin_name: &str) -> Result<Vec<String>, Error>;
+
     fn deactivate(&mut self, plugin_name: &str) -> Result<(), Error>;
 
+    fn deactivate_temporarily(
+        &mut self,
+        plugin_names: &[&str],
+    ) -> Result<ActiveStateToken, Error>;
+
+    fn restore_active_state(&mut self, token: ActiveStateToken) -> Result<(), Error>;
+
+    fn set_active_range(
+        &mut self,
+        start: usize,
+        end: usize,
+        active: bool,
+    ) -> Result<Vec<String>, Error>;
+
     fn set_active_plugins(&mut self, active_plugin_names: &[&str]) -> Result<(), Error>;
+
+    fn set_active_plugins_prioritized(
+        &mut self,
+        active_plugin_names: &[&str],
+    ) -> Result<Vec<String>, Error>;
+
+    fn reorder_masters(&mut self, master_order: &[&str]) -> Result<(), Error>;
+
+    fn changed_plugins_since_load(&self) -> Result<Vec<String>, Error>;
+
+    fn refresh_implicitly_active(&mut self) -> Result<(), Error>;
+
+    fn state_fingerprint(&self) -> Result<StateFingerprint, Error>;
+
+    fn lock_plugin(&mut self, plugin_name: &str);
+
+    fn unlock_plugin(&mut self, plugin_name: &str);
+
+    fn is_locked(&self, plugin_name: &str) -> bool;
+
+    fn merge_new_plugins(&mut self) -> Result<Vec<String>, Error>;
+
+    fn clear(&mut self);
+
+    fn stale_active_entries(&self) -> Result<Vec<String>, Error>;
+
+    /// Check if the plugins listed as active in the active plugins file are
+    /// the plugins that are currently active, ignoring their order and any
+    /// implicitly active plugins, which some games don't list.
+    fn verify_active_file_matches(&self) -> Result<bool, Error>;
+}
+
+/// The active states that some plugins had before they were temporarily
+/// deactivated, so that they can be restored later.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActiveStateToken {
+    active_states: Vec<(String, bool)>,
+}
+
+/// The modification times of the files that determine the load order state.
+/// A time is `None` if the corresponding file or directory doesn't exist.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StateFingerprint {
+    pub active_plugins_file_time: Option<SystemTime>,
+    pub plugins_directory_time: Option<SystemTime>,
+}
+
+/// Set the load order, but only if the given plugin names include every
+/// installed plugin, so that no plugin is accidentally dropped from it.
+pub fn set_complete_load_order<T: ReadableLoadOrderExt + WritableLoadOrder>(
+    load_order: &mut T,
+    plugin_names: &[&str],
+) -> Result<(), Error> {
+    let missing_plugins: Vec<String> = load_order
+        .find_plugins_in_dir_sorted()?
+        .into_iter()
+        .filter(|f| Plugin::is_valid(f, load_order.game_settings()))
+        .map(|f| trim_dot_ghost(&f).to_string())
+        .filter(|f| !plugin_names.iter().any(|p| eq(trim_dot_ghost(p), f.as_str())))
+        .collect();
+
+    if !missing_plugins.is_empty() {
+        return Err(Error::IncompleteLoadOrder(missing_plugins));
+    }
+
+    load_order.set_load_order(plugin_names)
+}
+
+pub fn set_load_order_appending_rest<T: ReadableLoadOrderExt + WritableLoadOrder>(
+    load_order: &mut T,
+    plugin_names: &[&str],
+) -> Result<(), Error> {
+    let is_listed = |name: &str| {
+        plugin_names
+            .iter()
+            .any(|n| eq(trim_dot_ghost(n), trim_dot_ghost(name)))
+    };
+
+    let mut unlisted_names: Vec<String> = load_order
+        .plugin_names()
+        .into_iter()
+        .filter(|n| !is_listed(n))
+        .map(str::to_string)
+        .collect();
+    for filename in load_order.find_plugins_in_dir_sorted()? {
+        let name = trim_dot_ghost(&filename);
+        if !is_listed(name)
+            && load_order.index_of(name).is_none()
+            && Plugin::is_valid(&filename, load_order.game_settings())
+        {
+            unlisted_names.push(name.to_string());
+        }
+    }
+
+    let is_master = |name: &str| load_order.is_master_plugin(name).unwrap_or(false);
+    let (unlisted_masters, unlisted_non_masters): (Vec<&str>, Vec<&str>) = unlisted_names
+        .iter()
+        .map(String::as_str)
+        .partition(|n| is_master(n));
+
+    let mut names = plugin_names.to_vec();
+    let master_end = names.iter().rposition(|n| is_master(n)).map_or(0, |i| i + 1);
+    names.splice(master_end..master_end, unlisted_masters);
+    names.extend(unlisted_non_masters);
+
+    let master_file = load_order.game_settings().master_file();
+    if !is_listed(master_file) {
+        if let Some(index) = names.iter().position(|n| eq(*n, master_file)) {
+            let name = names.remove(index);
+            names.insert(0, name);
+        }
+    }
+
+    load_order.set_load_order(&names)
+}
+
+/// Set the load order from the given index onwards, keeping the plugins before
+/// it in their current positions. An index past the end of the load order is
+/// treated as the end. The result is validated as for `set_load_order()`, so
+/// masters given can't load after a non-master in the fixed plugins.
+pub fn apply_order_from_index<T: WritableLoadOrder>(
+    load_order: &mut T,
+    start: usize,
+    plugin_names: &[&str],
+) -> Result<(), Error> {
+    let mut new_plugin_names: Vec<String> = {
+        let existing_plugin_names = load_order.plugin_names();
+        let start = start.min(existing_plugin_names.len());
+
+        existing_plugin_names[..start]
+            .iter()
+            .map(|n| n.to_string())
+            .collect()
+    };
+    new_plugin_names.extend(plugin_names.iter().map(|n| n.to_string()));
+
+    let new_plugin_names: Vec<&str> = new_plugin_names.iter().map(String::as_str).collect();
+
+    load_order.set_load_order(&new_plugin_names)
+}
+
+pub fn move_to_tier_start<T: WritableLoadOrder>(
+    load_order: &mut T,
+    plugin_name: &str,
+) -> Result<(), Error> {
+    let (start, _) = tier_bounds(load_order, plugin_name)?;
+
+    load_order.set_plugin_index(plugin_name, start)
+}
+
+pub fn move_to_tier_end<T: WritableLoadOrder>(
+    load_order: &mut T,
+    plugin_name: &str,
+) -> Result<(), Error> {
+    let (_, end) = tier_bounds(load_order, plugin_name)?;
+
+    load_order.set_plugin_index(plugin_name, end)
+}
+
+/// Get the first and last indices that the given plugin could be moved to
+/// while staying in its tier.
+fn tier_bounds<T: ReadableLoadOrder>(
+    load_order: &T,
+    plugin_name: &str,
+) -> Result<(usize, usize), Error> {
+    let is_master = |p: &Plugin| p.is_master_file() || p.is_light_master_file();
+
+    let plugins = load_order.plugins_slice();
+    let plugin = plugins
+        .iter()
+        .find(|p| p.name_matches(plugin_name))
+        .ok_or_else(|| Error::PluginNotFound(plugin_name.to_string()))?;
+
+    let others: Vec<&Plugin> = plugins
+        .iter()
+        .filter(|p| !p.name_matches(plugin_name))
+        .collect();
+    let first_non_master = others
+        .iter()
+        .position(|p| !is_master(p))
+        .unwrap_or(others.len());
+
+    if is_master(plugin) {
+        let game_settings = load_order.game_settings();
+        let start = others
+            .iter()
+            .take_while(|p| {
+                eq(p.name(), game_settings.master_file())
+                    || game_settings.is_implicitly_active(p.name())
+            })
+            .count();
+
+        Ok((start, first_non_master))
+    } else {
+        Ok((first_non_master, others.len()))
+    }
+}
+
+/// Set the load order to a sorted list of plugins, e.g. as produced by LOOT.
+/// Unlike `set_load_order()`, plugins that aren't installed are skipped, and
+/// their names are returned.
+pub fn apply_loot_order<T: WritableLoadOrder>(
+    load_order: &mut T,
+    sorted_plugin_names: &[&str],
+) -> Result<Vec<String>, Error> {
+    let (installed, skipped): (Vec<&str>, Vec<&str>) =
+        sorted_plugin_names.iter().partition(|name| {
+            load_order.index_of(name).is_some()
+                || Plugin::is_valid(name, load_order.game_settings())
+        });
+
+    load_order.set_load_order(&installed)?;
+
+    Ok(skipped.into_iter().map(str::to_string).collect())
 }
 
 pub fn activate<T: InsertableLoadOrder>(
@@ -49,22 +387,163 @@ pub fn activate<T: InsertableLoadOrder>(
 ) -> Result<(), Error> {
     let index = load_order.find_or_add(plugin_name)?;
 
-    let at_max_active_normal_plugins =
-        load_order.count_active_normal_plugins() == MAX_ACTIVE_NORMAL_PLUGINS;
-    let at_max_active_light_masters =
-        load_order.count_active_light_masters() == MAX_ACTIVE_LIGHT_MASTERS;
+    let max_active_normal_plugins = load_order.game_settings().max_active_normal_plugins();
+    let normal_count = load_order.count_active_normal_plugins();
+    let light_count = load_order.count_active_light_masters();
 
     let plugin = &mut load_order.plugins_mut()[index];
-    if !plugin.is_active()
-        && ((!plugin.is_light_master_file() && at_max_active_normal_plugins)
-            || (plugin.is_light_master_file() && at_max_active_light_masters))
-    {
-        Err(Error::TooManyActivePlugins)
+    if plugin.is_active() {
+        return plugin.activate();
+    }
+
+    let (normal_count, light_count) = if plugin.is_light_master_file() {
+        (normal_count, light_count + 1)
+    } else {
+        (normal_count + 1, light_count)
+    };
+
+    if normal_count > max_active_normal_plugins || light_count > MAX_ACTIVE_LIGHT_MASTERS {
+        Err(Error::TooManyActivePlugins {
+            light_count,
+            normal_count,
+        })
     } else {
         plugin.activate()
     }
 }
 
+pub fn activate_many<T: InsertableLoadOrder>(
+    load_order: &mut T,
+    plugin_names: &[&str],
+) -> Result<(), Error> {
+    let max_active_normal_plugins = load_order.game_settings().max_active_normal_plugins();
+    let mut inactive_names: Vec<&str> = Vec::new();
+    let mut light_count = load_order.count_active_light_masters();
+    let mut normal_count = load_order.count_active_normal_plugins();
+
+    for plugin_name in plugin_names {
+        if inactive_names
+            .iter()
+            .any(|n| eq(trim_dot_ghost(n), trim_dot_ghost(plugin_name)))
+        {
+            continue;
+        }
+
+        let is_light_master_file = match load_order.index_of(plugin_name) {
+            Some(index) if load_order.plugins()[index].is_active() => continue,
+            Some(index) => load_order.plugins()[index].is_light_master_file(),
+            None => Plugin::new(plugin_name, load_order.game_settings())
+                .map_err(|_| Error::InvalidPlugin(plugin_name.to_string()))?
+                .is_light_master_file(),
+        };
+
+        if is_light_master_file {
+            light_count += 1;
+        } else {
+            normal_count += 1;
+        }
+        inactive_names.push(plugin_name);
+    }
+
+    if normal_count > max_active_normal_plugins || light_count > MAX_ACTIVE_LIGHT_MASTERS {
+        return Err(Error::TooManyActivePlugins {
+            light_count,
+            normal_count,
+        });
+    }
+
+    for plugin_name in inactive_names {
+        activate(load_order, plugin_name)?;
+    }
+
+    Ok(())
+}
+
+pub fn activate_with_masters<T: InsertableLoadOrder>(
+    load_order: &mut T,
+    plugin_name: &str,
+) -> Result<Vec<String>, Error> {
+    let mut plugin_names: Vec<String> = Vec::new();
+    let mut pending = vec![plugin_name.to_string()];
+
+    while let Some(name) = pending.pop() {
+        if plugin_names.iter().any(|n| eq(n.as_str(), name.as_str())) {
+            continue;
+        }
+
+        let masters = match load_order.index_of(&name) {
+            Some(index) => load_order.plugins()[index].masters().to_vec(),
+            None => match Plugin::new(&name, load_order.game_settings()) {
+                Ok(plugin) => plugin.masters().to_vec(),
+                Err(_) if plugin_names.is_empty() => return Err(Error::InvalidPlugin(name)),
+                Err(_) => continue,
+            },
+        };
+
+        plugin_names.push(name);
+        pending.extend(masters.into_iter().rev());
+    }
+
+    let activated: Vec<String> = plugin_names
+        .into_iter()
+        .filter(|n| !is_active(load_order.plugins(), n))
+        .collect();
+    let activated_refs: Vec<&str> = activated.iter().map(String::as_str).collect();
+
+    activate_many(load_order, &activated_refs)?;
+
+    Ok(activated)
+}
+
+/// Check that saving the load order wouldn't write more active plugins than
+/// the game can load, as it would silently ignore those past the limits.
+pub fn validate_active_plugin_counts<T: ReadableLoadOrderExt>(load_order: &T) -> Result<(), Error> {
+    let max_active_normal_plugins = load_order.game_settings().max_active_normal_plugins();
+    let normal_count = load_order.count_active_normal_plugins();
+    let light_count = load_order.count_active_light_masters();
+
+    if normal_count > max_active_normal_plugins || light_count > MAX_ACTIVE_LIGHT_MASTERS {
+        Err(Error::TooManyActivePlugins {
+            light_count,
+            normal_count,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Check that moving or inserting the given plugin at the given position
+/// wouldn't displace the game's hardcoded first plugin, if it has one.
+pub fn validate_hardcoded_first_plugin_index<T: ReadableLoadOrderExt>(
+    load_order: &T,
+    plugin_name: &str,
+    position: usize,
+) -> Result<(), Error> {
+    if let Some(first_plugin) = load_order.game_settings().hardcoded_first_plugin() {
+        let is_first_plugin = eq(plugin_name, first_plugin);
+        if (position != 0 && is_first_plugin && !load_order.plugins().is_empty())
+            || (position == 0 && !is_first_plugin)
+        {
+            return Err(Error::GameMasterMustLoadFirst);
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that every active plugin is still installed, so that saving doesn't
+/// write entries for plugins that were deleted since the load order was
+/// loaded.
+pub fn validate_active_plugins_exist<T: ReadableLoadOrderExt>(load_order: &T) -> Result<(), Error> {
+    let game_settings = load_order.game_settings();
+
+    for plugin in load_order.plugins().iter().filter(|p| p.is_active()) {
+        game_settings.plugin_path(plugin.name()).resolve_path()?;
+    }
+
+    Ok(())
+}
+
 pub fn deactivate<T: MutableLoadOrder>(load_order: &mut T, plugin_name: &str) -> Result<(), Error> {
     if load_order.game_settings().is_implicitly_active(plugin_name) {
         return Err(Error::ImplicitlyActivePlugin(plugin_name.to_string()));
@@ -78,18 +557,191 @@ pub fn deactivate<T: MutableLoadOrder>(load_order: &mut T, plugin_name: &str) ->
         .map(|p| p.deactivate())
 }
 
+/// Deactivate the given plugins, returning a token that records their previous
+/// active states. No plugins are deactivated if any of them can't be.
+pub fn deactivate_temporarily<T: MutableLoadOrder>(
+    load_order: &mut T,
+    plugin_names: &[&str],
+) -> Result<ActiveStateToken, Error> {
+    let mut active_states = Vec::new();
+    for plugin_name in plugin_names {
+        if load_order.game_settings().is_implicitly_active(plugin_name) {
+            return Err(Error::ImplicitlyActivePlugin(plugin_name.to_string()));
+        }
+
+        let plugin = load_order
+            .plugins()
+            .iter()
+            .find(|p| p.name_matches(plugin_name))
+            .ok_or_else(|| Error::PluginNotFound(plugin_name.to_string()))?;
+
+        active_states.push((plugin.name().to_string(), plugin.is_active()));
+    }
+
+    for (plugin_name, _) in &active_states {
+        deactivate(load_order, plugin_name)?;
+    }
+
+    Ok(ActiveStateToken { active_states })
+}
+
+/// Restore the active states recorded in the given token. Plugins that have
+/// since been removed from the load order are skipped. Nothing is changed if
+/// the restored active plugins would exceed the active limits.
+pub fn restore_active_state<T: MutableLoadOrder>(
+    load_order: &mut T,
+    token: ActiveStateToken,
+) -> Result<(), Error> {
+    let restored_state = |plugin: &Plugin| {
+        token
+            .active_states
+            .iter()
+            .find(|(n, _)| plugin.name_matches(n))
+            .map_or(plugin.is_active(), |(_, active)| *active)
+    };
+
+    let (light_count, normal_count) = {
+        let active_plugins: Vec<&Plugin> = load_order
+            .plugins()
+            .iter()
+            .filter(|p| restored_state(p))
+            .collect();
+        let light_count = active_plugins
+            .iter()
+            .filter(|p| p.is_light_master_file())
+            .count();
+        (light_count, active_plugins.len() - light_count)
+    };
+
+    let max_active_normal_plugins = load_order.game_settings().max_active_normal_plugins();
+    if normal_count > max_active_normal_plugins || light_count > MAX_ACTIVE_LIGHT_MASTERS {
+        return Err(Error::TooManyActivePlugins {
+            light_count,
+            normal_count,
+        });
+    }
+
+    for (plugin_name, active) in token.active_states {
+        if let Some(plugin) = load_order
+            .plugins_mut()
+            .iter_mut()
+            .find(|p| p.name_matches(&plugin_name))
+        {
+            if active {
+                plugin.activate()?;
+            } else {
+                plugin.deactivate();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Set the active state of the plugins at indices `start..end`, returning the
+/// names of the plugins that changed. Implicitly active plugins are left
+/// active, and nothing is activated if it would exceed the active limits.
+pub fn set_active_range<T: MutableLoadOrder>(
+    load_order: &mut T,
+    start: usize,
+    end: usize,
+    active: bool,
+) -> Result<Vec<String>, Error> {
+    if end > load_order.plugins().len() {
+        return Err(Error::IndexOutOfRange(end));
+    } else if start > end {
+        return Err(Error::IndexOutOfRange(start));
+    }
+
+    let indices: Vec<usize> = (start..end)
+        .filter(|i| {
+            let plugin = &load_order.plugins()[*i];
+            plugin.is_active() != active
+                && (active || !load_order.game_settings().is_implicitly_active(plugin.name()))
+        })
+        .collect();
+
+    if active {
+        let light_count = indices
+            .iter()
+            .filter(|i| load_order.plugins()[**i].is_light_master_file())
+            .count();
+        let normal_count = load_order.count_active_normal_plugins() + indices.len() - light_count;
+        let light_count = load_order.count_active_light_masters() + light_count;
+        let max_active_normal_plugins = load_order.game_settings().max_active_normal_plugins();
+
+        if normal_count > max_active_normal_plugins || light_count > MAX_ACTIVE_LIGHT_MASTERS {
+            return Err(Error::TooManyActivePlugins {
+                light_count,
+                normal_count,
+            });
+        }
+    }
+
+    let mut changed = Vec::new();
+    for index in indices {
+        let plugin = &mut load_order.plugins_mut()[index];
+        if active {
+            plugin.activate()?;
+        } else {
+            plugin.deactivate();
+        }
+        changed.push(plugin.name().to_string());
+    }
+
+    Ok(changed)
+}
+
+pub fn load_and_report_unghosted_plugins<T>(load_order: &mut T) -> Result<Vec<String>, Error>
+where
+    T: ReadableLoadOrderExt + WritableLoadOrder,
+{
+    let ghosted_plugin_names: Vec<String> = load_order
+        .find_plugins_in_dir()?
+        .iter()
+        .map(|f| trim_dot_ghost(f))
+        .filter(|f| {
+            load_order
+                .game_settings()
+                .plugin_path(f)
+                .resolve_path()
+                .map(|p| p.is_ghosted())
+                .unwrap_or(false)
+        })
+        .map(str::to_string)
+        .collect();
+
+    load_order.load()?;
+
+    Ok(ghosted_plugin_names
+        .into_iter()
+        .filter(|n| load_order.is_active(n))
+        .collect())
+}
+
+/// Check if the given filename should be loaded when only loading the given
+/// plugins, which always includes the game's implicitly active plugins.
+pub fn is_in_subset(game_settings: &GameSettings, plugin_names: &[&str], filename: &str) -> bool {
+    let filename = trim_dot_ghost(filename);
+
+    game_settings.is_implicitly_active(filename) || plugin_names.iter().any(|n| eq(*n, filename))
+}
+
 pub fn set_active_plugins<T: InsertableLoadOrder>(
     load_order: &mut T,
     active_plugin_names: &[&str],
 ) -> Result<(), Error> {
     let (existing_plugin_indices, new_plugins) = load_order.lookup_plugins(active_plugin_names)?;
 
-    if load_order.count_normal_plugins(&existing_plugin_indices, &new_plugins)
-        > MAX_ACTIVE_NORMAL_PLUGINS
-        || load_order.count_light_masters(&existing_plugin_indices, &new_plugins)
-            > MAX_ACTIVE_LIGHT_MASTERS
-    {
-        return Err(Error::TooManyActivePlugins);
+    let max_active_normal_plugins = load_order.game_settings().max_active_normal_plugins();
+    let normal_count = load_order.count_normal_plugins(&existing_plugin_indices, &new_plugins);
+    let light_count = load_order.count_light_masters(&existing_plugin_indices, &new_plugins);
+
+    if normal_count > max_active_normal_plugins || light_count > MAX_ACTIVE_LIGHT_MASTERS {
+        return Err(Error::TooManyActivePlugins {
+            light_count,
+            normal_count,
+        });
     }
 
     for plugin_name in load_order.game_settings().implicitly_active_plugins() {
@@ -102,6 +754,10 @@ pub fn set_active_plugins<T: InsertableLoadOrder>(
         }
     }
 
+    for plugin in &new_plugins {
+        load_order.unlocked_insert_position(plugin)?;
+    }
+
     load_order.deactivate_all();
 
     for index in existing_plugin_indices {
@@ -110,25 +766,345 @@ pub fn set_active_plugins<T: InsertableLoadOrder>(
 
     for mut plugin in new_plugins {
         plugin.activate()?;
-        load_order.insert(plugin);
+        load_order.insert_unlocked(plugin)?;
     }
 
     Ok(())
 }
 
+pub fn set_active_plugins_prioritized<T: InsertableLoadOrder>(
+    load_order: &mut T,
+    active_plugin_names: &[&str],
+) -> Result<Vec<String>, Error> {
+    let max_active_normal_plugins = load_order.game_settings().max_active_normal_plugins();
+    let mut normal_plugins_count = 0;
+    let mut light_masters_count = 0;
+    let mut plugins_to_activate: Vec<&str> = Vec::new();
+    let mut dropped_plugins: Vec<String> = Vec::new();
+
+    for plugin_name in active_plugin_names {
+        let is_light_master = match load_order.index_of(plugin_name) {
+            Some(x) => load_order.plugins()[x].is_light_master_file(),
+            None => Plugin::new(plugin_name, load_order.game_settings())
+                .map_err(|_| Error::InvalidPlugin(plugin_name.to_string()))?
+                .is_light_master_file(),
+        };
+
+        if is_light_master && light_masters_count < MAX_ACTIVE_LIGHT_MASTERS {
+            light_masters_count += 1;
+            plugins_to_activate.push(plugin_name);
+        } else if !is_light_master && normal_plugins_count < max_active_normal_plugins {
+            normal_plugins_count += 1;
+            plugins_to_activate.push(plugin_name);
+        } else {
+            dropped_plugins.push(plugin_name.to_string());
+        }
+    }
+
+    set_active_plugins(load_order, &plugins_to_activate)?;
+
+    Ok(dropped_plugins)
+}
+
+pub fn reorder_masters<T: MutableLoadOrder + WritableLoadOrder>(
+    load_order: &mut T,
+    master_order: &[&str],
+) -> Result<(), Error> {
+    let mut master_indices: Vec<usize> = Vec::new();
+    for plugin_name in master_order {
+        let index = load_order
+            .index_of(plugin_name)
+            .ok_or_else(|| Error::PluginNotFound(plugin_name.to_string()))?;
+
+        let plugin = &load_order.plugins()[index];
+        if !plugin.is_master_file() && !plugin.is_light_master_file() {
+            return Err(Error::InvalidPlugin(plugin.name().to_string()));
+        }
+
+        if master_indices.contains(&index) {
+            return Err(Error::DuplicatePlugin);
+        }
+
+        master_indices.push(index);
+    }
+
+    // The game's master file stays pinned first unless it's listed.
+    if let Some(index) = load_order.index_of(load_order.game_settings().master_file()) {
+        if !master_indices.contains(&index) {
+            master_indices.insert(0, index);
+        }
+    }
+
+    let plugin_names: Vec<String> = {
+        let plugins = load_order.plugins();
+        let first_non_master_position =
+            find_first_non_master_position(plugins).unwrap_or(plugins.len());
+
+        let unlisted_master_indices =
+            (0..first_non_master_position).filter(|i| !master_indices.contains(i));
+
+        master_indices
+            .iter()
+            .cloned()
+            .chain(unlisted_master_indices)
+            .chain(first_non_master_position..plugins.len())
+            .map(|i| plugins[i].name().to_string())
+            .collect()
+    };
+
+    let plugin_names: Vec<&str> = plugin_names.iter().map(AsRef::as_ref).collect();
+
+    load_order.set_load_order(&plugin_names)
+}
+
+pub fn changed_plugins_since_load<T: ReadableLoadOrderExt>(
+    load_order: &T,
+) -> Result<Vec<String>, Error> {
+    let mut plugin_names = Vec::new();
+    for plugin in load_order.plugins() {
+        if plugin.has_changed_on_disk()? {
+            plugin_names.push(plugin.name().to_string());
+        }
+    }
+
+    Ok(plugin_names)
+}
+
+/// Add installed plugins that aren't in the load order without changing the
+/// positions of the plugins that are, inserting new masters after the last
+/// master and new non-masters at the end.
+pub fn merge_new_plugins<T: InsertableLoadOrder>(load_order: &mut T) -> Result<Vec<String>, Error> {
+    let new_plugins: Vec<Plugin> = load_order
+        .find_plugins_in_dir_sorted()?
+        .into_iter()
+        .filter(|f| load_order.index_of(f).is_none())
+        .filter_map(|f| Plugin::new(&f, load_order.game_settings()).ok())
+        .collect();
+
+    let mut plugin_names = Vec::new();
+    for plugin in new_plugins {
+        plugin_names.push(plugin.name().to_string());
+        load_order.insert_unlocked(plugin)?;
+    }
+
+    Ok(plugin_names)
+}
+
+/// Get the plugins listed as active in the active plugins file that aren't
+/// installed, and so would be dropped when the load order is next loaded.
+pub fn stale_active_entries<T, F>(load_order: &T, line_mapper: F) -> Result<Vec<String>, Error>
+where
+    T: ReadableLoadOrderExt,
+    F: Fn(&str) -> Option<String> + Send + Sync,
+{
+    let installed_filenames = load_order.find_plugins_in_dir()?;
+    let active_plugin_names =
+        read_plugin_names(load_order.game_settings().active_plugins_file(), line_mapper)?;
+
+    Ok(active_plugin_names
+        .into_iter()
+        .filter(|name| {
+            !installed_filenames
+                .iter()
+                .any(|f| eq(trim_dot_ghost(f), trim_dot_ghost(name)))
+        })
+        .collect())
+}
+
+pub fn verify_active_file_matches<T, F>(load_order: &T, line_mapper: F) -> Result<bool, Error>
+where
+    T: ReadableLoadOrderExt,
+    F: Fn(&str) -> Option<String> + Send + Sync,
+{
+    let listed_plugin_names =
+        read_plugin_names(load_order.game_settings().active_plugins_file(), line_mapper)?;
+
+    Ok(active_plugins_match(load_order, &listed_plugin_names))
+}
+
+/// Check if the given plugin names are the active plugins, ignoring order,
+/// case, ghost extensions and implicitly active plugins.
+pub fn active_plugins_match<T: ReadableLoadOrderExt>(
+    load_order: &T,
+    plugin_names: &[String],
+) -> bool {
+    let normalise = |names: Vec<&str>| {
+        let mut names: Vec<String> = names
+            .into_iter()
+            .map(trim_dot_ghost)
+            .filter(|n| !load_order.game_settings().is_implicitly_active(n))
+            .map(str::to_lowercase)
+            .collect();
+        names.sort();
+        names
+    };
+
+    normalise(plugin_names.iter().map(String::as_str).collect())
+        == normalise(load_order.active_plugin_names())
+}
+
+pub fn sort_by_key<T, K, F>(load_order: &mut T, key: F) -> Result<(), Error>
+where
+    T: WritableLoadOrder,
+    K: Ord,
+    F: Fn(&str) -> K,
+{
+    let mut plugin_names = load_order.plugin_names();
+    plugin_names.sort_by_key(|name| key(name));
+
+    let master_file = load_order.game_settings().master_file();
+    let group = |name: &str| {
+        if eq(name, master_file) {
+            0
+        } else if load_order.is_master_plugin(name).unwrap_or(false) {
+            1
+        } else {
+            2
+        }
+    };
+    plugin_names.sort_by_key(|name| group(name));
+
+    let plugin_names: Vec<String> = plugin_names.into_iter().map(str::to_string).collect();
+    let plugin_names: Vec<&str> = plugin_names.iter().map(String::as_str).collect();
+
+    load_order.set_load_order(&plugin_names)
+}
+
+/// Get the content that saving would write to the active plugins file, after
+/// the given prelude. If the existing file starts with a UTF-8 byte order
+/// mark, the content is written as UTF-8 with a byte order mark to match,
+/// otherwise it is written as Windows-1252.
+pub fn active_plugins_file_content<T: ReadableLoadOrderExt>(
+    load_order: &T,
+    prelude: Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    let game_settings = load_order.game_settings();
+    let is_utf8 = has_utf8_bom(game_settings.active_plugins_file())?;
+
+    let mut content = prelude;
+    if is_utf8 && !content.starts_with(UTF8_BOM) {
+        content.splice(0..0, UTF8_BOM.iter().cloned());
+    }
+
+    // Write back any limit directive read from the file, so that saving
+    // doesn't lift the limit.
+    if let Some(limit) = game_settings.active_plugins_limit() {
+        content.extend(format!("# max={}", limit).as_bytes());
+        content.extend(game_settings.line_ending().as_bytes());
+    }
+
+    for line in load_order.active_plugins_as_written() {
+        if is_utf8 {
+            content.extend(line.as_bytes());
+        } else {
+            content.extend(WINDOWS_1252
+                .encode(&line, EncoderTrap::Strict)
+                .map_err(Error::EncodeError)?);
+        }
+        content.extend(game_settings.line_ending().as_bytes());
+    }
+
+    Ok(content)
+}
+
+pub fn active_plugins_file_text<T: ReadableLoadOrderExt>(
+    load_order: &T,
+    prelude: Vec<u8>,
+) -> Result<String, Error> {
+    let content = active_plugins_file_content(load_order, prelude)?;
+
+    if content.starts_with(UTF8_BOM) {
+        UTF_8.decode(&content[UTF8_BOM.len()..], DecoderTrap::Replace)
+    } else {
+        WINDOWS_1252.decode(&content, DecoderTrap::Replace)
+    }.map_err(Error::DecodeError)
+}
+
+pub fn active_plugins_file_would_change(
+    game_settings: &GameSettings,
+    content: &[u8],
+) -> Result<bool, Error> {
+    if !game_settings.active_plugins_file().exists() {
+        return Ok(true);
+    }
+
+    Ok(read_active_plugins_file_raw(game_settings)? != content)
+}
+
+/// Remove all plugins from the load order in memory, leaving the game settings
+/// and the files on disk unchanged.
+pub fn clear<T: MutableLoadOrder>(load_order: &mut T) {
+    load_order.plugins_mut().clear();
+}
+
+/// Lock a plugin so that it can't be moved from its current position. Locks
+/// are held in memory only, and don't affect what is saved.
+pub fn lock_plugin<T: MutableLoadOrder>(load_order: &mut T, plugin_name: &str) {
+    if !is_locked(load_order, plugin_name) {
+        load_order
+            .locked_plugins_mut()
+            .push(trim_dot_ghost(plugin_name).to_string());
+    }
+}
+
+pub fn unlock_plugin<T: MutableLoadOrder>(load_order: &mut T, plugin_name: &str) {
+    let plugin_name = trim_dot_ghost(plugin_name);
+    load_order
+        .locked_plugins_mut()
+        .retain(|p| !eq(p.as_str(), plugin_name));
+}
+
+pub fn is_locked<T: MutableLoadOrder>(load_order: &T, plugin_name: &str) -> bool {
+    let plugin_name = trim_dot_ghost(plugin_name);
+    load_order
+        .locked_plugins()
+        .iter()
+        .any(|p| eq(p.as_str(), plugin_name))
+}
+
+pub fn state_fingerprint<T: ReadableLoadOrder>(load_order: &T) -> Result<StateFingerprint, Error> {
+    let game_settings = load_order.game_settings();
+
+    Ok(StateFingerprint {
+        active_plugins_file_time: modification_time(game_settings.active_plugins_file())?,
+        plugins_directory_time: modification_time(game_settings.plugins_directory_path())?,
+    })
+}
+
+fn modification_time(path: &Path) -> Result<Option<SystemTime>, Error> {
+    if path.exists() {
+        Ok(Some(metadata(path)?.modified()?))
+    } else {
+        Ok(None)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use std::collections::HashSet;
+    use std::fs::{remove_file, rename, OpenOptions};
+    use std::io::Write;
+    use std::ops::Range;
     use std::path::Path;
 
+    use filetime::{set_file_times, FileTime};
     use tempfile::tempdir;
 
-    use enums::GameId;
-    use game_settings::GameSettings;
+    use enums::{GameId, PluginStatus, PluginTier, ValidationWarning};
     use load_order::readable::{
-        active_plugin_names, index_of, is_active, plugin_at, plugin_names, ReadableLoadOrder,
-        ReadableLoadOrderExt,
+        active_count_status, active_index_of, active_master_names, active_non_master_names,
+        active_plugin_names, active_plugins_as_written, content_hash, duplicate_content_plugins,
+        effective_load_order, excess_active_plugins, flat_entries, form_version, group_by_archive,
+        index_of, is_active, is_blueprint_master, is_master_plugin, is_ordering_valid,
+        is_override_only, light_count, master_count, master_dependency_cycles, master_file_present,
+        master_indices, mismatched_extension_plugins, missing_implicit_plugins, move_delta,
+        non_master_count, orphaned_plugins, plugin_at, plugin_has_associated_archive, plugin_names,
+        plugin_names_reversed, plugin_status, plugin_tier, read_active_plugins_file_raw,
+        record_types, remaining_slots, report, valid_index_range, validate, write_mo2_modlist,
+        ActiveCountStatus, LoadOrderEntry, ReadableLoadOrder, ReadableLoadOrderExt,
+        MAX_ACTIVE_NORMAL_PLUGINS,
     };
     use load_order::tests::mock_game_files;
     use tests::copy_to_test_dir;
@@ -136,6 +1112,7 @@ mod tests {
     struct TestLoadOrder {
         game_settings: GameSettings,
         plugins: Vec<Plugin>,
+        locked_plugins: Vec<String>,
     }
 
     impl ReadableLoadOrder for TestLoadOrder {
@@ -143,14 +1120,38 @@ mod tests {
             &self.game_settings
         }
 
+        fn plugins_directory(&self) -> &Path {
+            self.game_settings().plugins_directory_path()
+        }
+
         fn plugin_names(&self) -> Vec<&str> {
             plugin_names(&self.plugins)
         }
 
+        fn plugins_slice(&self) -> &[Plugin] {
+            &self.plugins
+        }
+
+        fn plugin_names_reversed(&self) -> Vec<&str> {
+            plugin_names_reversed(&self.plugins)
+        }
+
+        fn effective_load_order(&self) -> Vec<String> {
+            effective_load_order(&self.plugins, self.game_settings())
+        }
+
         fn index_of(&self, plugin_name: &str) -> Option<usize> {
             index_of(&self.plugins, plugin_name)
         }
 
+        fn active_index_of(&self, plugin_name: &str) -> Option<usize> {
+            active_index_of(&self.plugins, plugin_name)
+        }
+
+        fn move_delta(&self, plugin_name: &str, target: usize) -> Option<isize> {
+            move_delta(&self.plugins, plugin_name, target)
+        }
+
         fn plugin_at(&self, index: usize) -> Option<&str> {
             plugin_at(&self.plugins, index)
         }
@@ -159,9 +1160,145 @@ mod tests {
             active_plugin_names(&self.plugins)
         }
 
+        fn active_master_names(&self) -> Vec<&str> {
+            active_master_names(&self.plugins)
+        }
+
+        fn active_non_master_names(&self) -> Vec<&str> {
+            active_non_master_names(&self.plugins)
+        }
+
+        fn master_count(&self) -> usize {
+            master_count(&self.plugins)
+        }
+
+        fn non_master_count(&self) -> usize {
+            non_master_count(&self.plugins)
+        }
+
+        fn light_count(&self) -> usize {
+            light_count(&self.plugins)
+        }
+
+        fn active_count_status(&self) -> ActiveCountStatus {
+            active_count_status(&self.plugins, self.game_settings())
+        }
+
+        fn remaining_slots(&self) -> (usize, usize) {
+            remaining_slots(&self.plugins, self.game_settings())
+        }
+
+        fn active_plugins_as_written(&self) -> Vec<String> {
+            active_plugins_as_written(&self.plugins)
+        }
+
+        fn active_plugins_file_content(&self) -> Result<String, Error> {
+            active_plugins_file_text(self, Vec::new())
+        }
+
         fn is_active(&self, plugin_name: &str) -> bool {
             is_active(&self.plugins, plugin_name)
         }
+
+        fn plugin_status(&self, plugin_name: &str) -> PluginStatus {
+            plugin_status(&self.plugins, self.game_settings(), plugin_name)
+        }
+
+        fn plugin_tier(&self, plugin_name: &str) -> Option<PluginTier> {
+            plugin_tier(&self.plugins, self.game_settings(), plugin_name)
+        }
+
+        fn flat_entries(&self) -> Vec<LoadOrderEntry<'_>> {
+            flat_entries(&self.plugins)
+        }
+
+        fn excess_active_plugins(&self) -> Vec<&str> {
+            excess_active_plugins(&self.plugins, self.game_settings())
+        }
+
+        fn master_file_present(&self) -> bool {
+            master_file_present(&self.plugins, self.game_settings().master_file())
+        }
+
+        fn valid_index_range(&self, plugin_name: &str) -> Result<Range<usize>, Error> {
+            valid_index_range(&self.plugins, plugin_name, self.game_settings())
+        }
+
+        fn is_override_only(&self, plugin_name: &str) -> Result<bool, Error> {
+            is_override_only(&self.plugins, plugin_name)
+        }
+
+        fn master_indices(&self, plugin_name: &str) -> Result<Vec<(String, Option<usize>)>, Error> {
+            master_indices(&self.plugins, plugin_name)
+        }
+
+        fn master_dependency_cycles(&self) -> Vec<Vec<String>> {
+            master_dependency_cycles(&self.plugins)
+        }
+
+        fn orphaned_plugins(&self) -> Result<Vec<String>, Error> {
+            orphaned_plugins(&self.plugins)
+        }
+
+        fn mismatched_extension_plugins(&self) -> Vec<String> {
+            mismatched_extension_plugins(&self.plugins)
+        }
+
+        fn is_ordering_valid(&self) -> bool {
+            is_ordering_valid(&self.plugins)
+        }
+
+        fn plugin_has_associated_archive(&self, plugin_name: &str) -> bool {
+            plugin_has_associated_archive(plugin_name, self.game_settings())
+        }
+
+        fn content_hash(&self) -> u64 {
+            content_hash(&self.plugins)
+        }
+
+        fn group_by_archive(&self) -> Vec<(String, Vec<String>)> {
+            group_by_archive(&self.plugins, self.game_settings())
+        }
+
+        fn duplicate_content_plugins(&self) -> Result<Vec<Vec<String>>, Error> {
+            duplicate_content_plugins(&self.plugins)
+        }
+
+        fn read_active_plugins_file_raw(&self) -> Result<Vec<u8>, Error> {
+            read_active_plugins_file_raw(self.game_settings())
+        }
+
+        fn write_mo2_modlist(&self, writer: &mut dyn Write) -> Result<(), Error> {
+            write_mo2_modlist(&self.plugins, writer)
+        }
+
+        fn is_master_plugin(&self, plugin_name: &str) -> Option<bool> {
+            is_master_plugin(&self.plugins, self.game_settings(), plugin_name)
+        }
+
+        fn form_version(&self, plugin_name: &str) -> Option<u16> {
+            form_version(&self.plugins, plugin_name)
+        }
+
+        fn is_blueprint_master(&self, plugin_name: &str) -> Option<bool> {
+            is_blueprint_master(&self.plugins, plugin_name)
+        }
+
+        fn record_types(&self, plugin_name: &str) -> Result<HashSet<[u8; 4]>, Error> {
+            record_types(&self.plugins, plugin_name)
+        }
+
+        fn validate(&self) -> Vec<ValidationWarning> {
+            validate(&self.plugins, self.game_settings().id())
+        }
+
+        fn missing_implicit_plugins(&self) -> Vec<String> {
+            missing_implicit_plugins(self.game_settings())
+        }
+
+        fn report(&self) -> String {
+            report(&self.plugins, self.game_settings())
+        }
     }
 
     impl ReadableLoadOrderExt for TestLoadOrder {
@@ -174,6 +1311,14 @@ mod tests {
         fn plugins_mut(&mut self) -> &mut Vec<Plugin> {
             &mut self.plugins
         }
+
+        fn locked_plugins(&self) -> &[String] {
+            &self.locked_plugins
+        }
+
+        fn locked_plugins_mut(&mut self) -> &mut Vec<String> {
+            &mut self.locked_plugins
+        }
     }
 
     impl InsertableLoadOrder for TestLoadOrder {
@@ -191,6 +1336,7 @@ mod tests {
         TestLoadOrder {
             game_settings,
             plugins,
+            locked_plugins: Vec::new(),
         }
     }
 
@@ -299,7 +1445,10 @@ mod tests {
         let mut load_order = prepare(GameId::Skyrim, &tmp_dir.path());
 
         assert!(activate(&mut load_order, "Skyrim.esm").is_ok());
-        assert!(deactivate(&mut load_order, "Skyrim.esm").is_err());
+        match deactivate(&mut load_order, "Skyrim.esm").unwrap_err() {
+            Error::ImplicitlyActivePlugin(name) => assert_eq!("Skyrim.esm", name),
+            e => panic!("Wrong error type: {:?}", e),
+        }
         assert!(load_order.is_active("Skyrim.esm"));
     }
 
@@ -308,7 +1457,10 @@ mod tests {
         let tmp_dir = tempdir().unwrap();
         let mut load_order = prepare(GameId::Skyrim, &tmp_dir.path());
 
-        assert!(deactivate(&mut load_order, "Update.esm").is_err());
+        match deactivate(&mut load_order, "Update.esm").unwrap_err() {
+            Error::ImplicitlyActivePlugin(name) => assert_eq!("Update.esm", name),
+            e => panic!("Wrong error type: {:?}", e),
+        }
         assert!(load_order.index_of("Update.esm").is_none());
     }
 
@@ -322,6 +1474,48 @@ mod tests {
         assert!(!load_order.is_active("Blank - Different.esp"));
     }
 
+    #[test]
+    fn set_active_range_should_toggle_plugins_in_the_range_and_return_those_changed() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        for name in &["Blank - Master Dependent.esp", "Blàñk.esp"] {
+            let plugin = Plugin::new(name, load_order.game_settings()).unwrap();
+            load_order.plugins.push(plugin);
+        }
+
+        let changed = set_active_range(&mut load_order, 1, 4, true).unwrap();
+
+        assert_eq!(
+            vec!["Blank - Different.esp", "Blank - Master Dependent.esp"],
+            changed
+        );
+        assert!(!load_order.is_active("Blàñk.esp"));
+
+        activate(&mut load_order, "Skyrim.esm").unwrap();
+        let changed = set_active_range(&mut load_order, 0, 2, false).unwrap();
+
+        assert_eq!(vec!["Blank.esp"], changed);
+        assert!(load_order.is_active("Skyrim.esm"));
+        assert!(load_order.is_active("Blank - Different.esp"));
+    }
+
+    #[test]
+    fn set_active_range_should_error_if_the_range_is_out_of_bounds() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Skyrim, tmp_dir.path());
+
+        match set_active_range(&mut load_order, 1, 4, true).unwrap_err() {
+            Error::IndexOutOfRange(4) => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        match set_active_range(&mut load_order, 2, 1, true).unwrap_err() {
+            Error::IndexOutOfRange(2) => {}
+            e => panic!("Wrong error type: {:?}", e),
+        }
+        assert!(!load_order.is_active("Blank - Different.esp"));
+    }
+
     #[test]
     fn set_active_plugins_should_error_if_given_more_plugins_than_the_max_limit() {
         let tmp_dir = tempdir().unwrap();
@@ -400,4 +1594,108 @@ mod tests {
         assert!(load_order.is_active("Blàñk.esp"));
         assert_eq!(4, load_order.index_of("Blàñk.esp").unwrap());
     }
+
+    #[test]
+    fn set_active_plugins_prioritized_should_drop_the_lowest_priority_plugins_over_the_limit() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        let plugin_names: Vec<String> = (0..260).map(|i| format!("Blank{}.esp", i)).collect();
+        for plugin_name in &plugin_names {
+            copy_to_test_dir("Blank.esp", plugin_name, load_order.game_settings());
+        }
+
+        let active_plugins: Vec<&str> = plugin_names.iter().map(AsRef::as_ref).collect();
+        let dropped_plugins =
+            set_active_plugins_prioritized(&mut load_order, &active_plugins).unwrap();
+
+        assert_eq!(plugin_names[255..].to_vec(), dropped_plugins);
+        assert_eq!(255, load_order.active_plugin_names().len());
+        assert!(load_order.is_active("Blank0.esp"));
+        assert!(load_order.is_active("Blank254.esp"));
+        assert!(!load_order.is_active("Blank255.esp"));
+    }
+
+    #[test]
+    fn set_active_plugins_prioritized_should_drop_nothing_if_under_the_limit() {
+        let tmp_dir = tempdir().unwrap();
+        let mut load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        let active_plugins = ["Blank - Different.esp", "Blank.esp"];
+        let dropped_plugins =
+            set_active_plugins_prioritized(&mut load_order, &active_plugins).unwrap();
+
+        assert!(dropped_plugins.is_empty());
+        assert_eq!(
+            vec!["Blank.esp", "Blank - Different.esp"],
+            load_order.active_plugin_names()
+        );
+    }
+
+    #[test]
+    fn changed_plugins_since_load_should_be_empty_if_no_plugins_have_changed() {
+        let tmp_dir = tempdir().unwrap();
+        let load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        assert!(changed_plugins_since_load(&load_order).unwrap().is_empty());
+    }
+
+    #[test]
+    fn changed_plugins_since_load_should_include_modified_and_deleted_plugins() {
+        let tmp_dir = tempdir().unwrap();
+        let load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        let plugins_directory = load_order.game_settings().plugins_directory();
+        OpenOptions::new()
+            .append(true)
+            .open(plugins_directory.join("Blank.esp"))
+            .unwrap()
+            .write_all(b"extra")
+            .unwrap();
+        remove_file(plugins_directory.join("Blank - Different.esp")).unwrap();
+
+        assert_eq!(
+            vec!["Blank.esp", "Blank - Different.esp"],
+            changed_plugins_since_load(&load_order).unwrap()
+        );
+    }
+
+    #[test]
+    fn changed_plugins_since_load_should_not_include_a_plugin_that_was_only_ghosted() {
+        let tmp_dir = tempdir().unwrap();
+        let load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        let plugin_path = load_order
+            .game_settings()
+            .plugins_directory()
+            .join("Blank - Different.esp");
+        rename(&plugin_path, plugin_path.with_extension("esp.ghost")).unwrap();
+
+        assert!(changed_plugins_since_load(&load_order).unwrap().is_empty());
+    }
+
+    #[test]
+    fn state_fingerprint_should_have_no_active_plugins_file_time_if_it_does_not_exist() {
+        let tmp_dir = tempdir().unwrap();
+        let load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        let fingerprint = state_fingerprint(&load_order).unwrap();
+
+        assert!(fingerprint.active_plugins_file_time.is_none());
+        assert!(fingerprint.plugins_directory_time.is_some());
+    }
+
+    #[test]
+    fn state_fingerprint_should_change_when_a_file_is_added_to_the_plugins_directory() {
+        let tmp_dir = tempdir().unwrap();
+        let load_order = prepare(GameId::Oblivion, tmp_dir.path());
+
+        let plugins_directory = load_order.game_settings().plugins_directory();
+        set_file_times(plugins_directory, FileTime::zero(), FileTime::zero()).unwrap();
+        let fingerprint = state_fingerprint(&load_order).unwrap();
+
+        copy_to_test_dir("Blank.esm", "New.esm", load_order.game_settings());
+
+        assert_ne!(fingerprint, state_fingerprint(&load_order).unwrap());
+    }
 }
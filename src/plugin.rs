@@ -16,8 +16,11 @@
  * You should have received a copy of the GNU General Public License
  * along with libloadorder. If not, see <http://www.gnu.org/licenses/>.
  */
+use std::collections::HashSet;
 use std::fs::File;
-use std::time::SystemTime;
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use esplugin;
 use filetime::{set_file_times, FileTime};
@@ -27,23 +30,25 @@ use enums::{Error, GameId};
 use game_settings::GameSettings;
 use ghostable_path::GhostablePath;
 
-const VALID_EXTENSIONS: &[&str] = &[".esp", ".esm", ".esp.ghost", ".esm.ghost"];
+const FORM_VERSION_OFFSET: usize = 20;
 
-const VALID_EXTENSIONS_WITH_ESL: &[&str] = &[
-    ".esp",
-    ".esm",
-    ".esp.ghost",
-    ".esm.ghost",
-    ".esl",
-    ".esl.ghost",
-];
+const MASTER_FLAG: u32 = 0x1;
+
+const BLUEPRINT_MASTER_FLAG: u32 = 0x800;
 
 #[derive(Clone, Debug)]
 pub struct Plugin {
     game: GameId,
+    supports_light_masters: bool,
+    read_only: bool,
     active: bool,
     modification_time: SystemTime,
-    data: esplugin::Plugin,
+    size: u64,
+    path: PathBuf,
+    is_master_file: bool,
+    is_light_master_file: bool,
+    masters: Vec<String>,
+    description: Option<String>,
     name: String,
 }
 
@@ -57,33 +62,84 @@ impl Plugin {
         game_settings: &GameSettings,
         active: bool,
     ) -> Result<Plugin, Error> {
-        if !has_valid_extension(filename, game_settings.id()) {
+        if !has_valid_extension(filename, game_settings) || game_settings.is_quarantined(filename)
+        {
             return Err(Error::InvalidPlugin(filename.to_owned()));
         }
 
-        let filepath = game_settings.plugins_directory().join(filename);
+        let filepath = game_settings.plugin_path(filename);
 
-        let filepath = if active {
+        // Active plugins are unghosted, unless the game settings forbid
+        // writing to the game's files.
+        let filepath = if active && !game_settings.is_read_only() {
             filepath.unghost()?
         } else {
             filepath.resolve_path()?
         };
 
+        // Modification times are handled as SystemTime values, which measure
+        // time since the Unix epoch in UTC, so the host's timezone never
+        // affects how plugins are ordered.
         let file = File::open(&filepath)?;
-        let modification_time = file.metadata()?.modified()?;
+        let metadata = file.metadata()?;
+        let modification_time = metadata.modified()?;
 
+        // Only the header record is parsed, as it holds everything needed to
+        // classify the plugin and its masters, and its description. Record
+        // data is only read by the methods that need it.
         let mut data = esplugin::Plugin::new(game_settings.id().to_esplugin_id(), &filepath);
         data.parse_open_file(file, true)?;
 
         Ok(Plugin {
             game: game_settings.id(),
+            supports_light_masters: game_settings.supports_light_masters(),
+            read_only: game_settings.is_read_only(),
             active,
             modification_time,
-            data,
+            size: metadata.len(),
+            is_master_file: data.is_master_file(),
+            is_light_master_file: data.is_light_master_file(),
+            masters: data.masters()?,
+            description: data.description()?,
+            path: filepath,
             name: trim_dot_ghost(filename).to_string(),
         })
     }
 
+    /// Create an inactive plugin from metadata that is already known, without
+    /// reading anything from the filesystem. Methods that need the plugin's
+    /// record data still read it from the plugin's path in the plugins
+    /// directory, and error if it doesn't exist.
+    pub fn from_metadata(
+        filename: &str,
+        game_settings: &GameSettings,
+        is_master_file: bool,
+        is_light_master_file: bool,
+        masters: Vec<String>,
+    ) -> Result<Plugin, Error> {
+        if !has_valid_extension(filename, game_settings) || game_settings.is_quarantined(filename)
+        {
+            return Err(Error::InvalidPlugin(filename.to_owned()));
+        }
+
+        let name = trim_dot_ghost(filename).to_string();
+
+        Ok(Plugin {
+            game: game_settings.id(),
+            supports_light_masters: game_settings.supports_light_masters(),
+            read_only: game_settings.is_read_only(),
+            active: false,
+            modification_time: UNIX_EPOCH,
+            size: 0,
+            path: game_settings.plugins_directory().join(&name),
+            is_master_file,
+            is_light_master_file,
+            masters,
+            description: None,
+            name,
+        })
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -96,16 +152,34 @@ impl Plugin {
         self.modification_time
     }
 
+    /// Get the plugin file's creation time, or `None` if the platform or
+    /// filesystem doesn't record it.
+    pub fn creation_time(&self) -> Result<Option<SystemTime>, Error> {
+        let metadata = self.path.resolve_path()?.metadata()?;
+
+        Ok(metadata.created().ok())
+    }
+
     pub fn is_active(&self) -> bool {
         self.active
     }
 
     pub fn is_master_file(&self) -> bool {
-        self.data.is_master_file()
+        self.is_master_file
     }
 
     pub fn is_light_master_file(&self) -> bool {
-        self.data.is_light_master_file()
+        self.supports_light_masters && self.is_light_master_file
+    }
+
+    pub fn masters(&self) -> &[String] {
+        &self.masters
+    }
+
+    /// Get the description from the plugin's header, if it has one. Plugins
+    /// created from metadata have no description.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
     }
 
     pub fn set_modification_time(&mut self, time: SystemTime) -> Result<(), Error> {
@@ -114,8 +188,13 @@ impl Plugin {
         // timestamps between calls to WritableLoadOrder::load() and
         // WritableLoadOrder::save() could lead to libloadorder not setting all
         // the timestamps it needs to and producing an incorrect load order.
+        // The time is converted to UTC epoch seconds, never local time.
+        if self.read_only {
+            return Err(Error::ReadOnly);
+        }
+
         set_file_times(
-            &self.data.path(),
+            &self.path,
             FileTime::from_system_time(SystemTime::now()),
             FileTime::from_system_time(time),
         )?;
@@ -126,11 +205,9 @@ impl Plugin {
 
     pub fn activate(&mut self) -> Result<(), Error> {
         if !self.is_active() {
-            if self.data.path().is_ghosted() {
-                let new_path = self.data.path().unghost()?;
+            if !self.read_only && self.path.is_ghosted() {
+                self.path = self.path.unghost()?;
 
-                self.data = esplugin::Plugin::new(*self.data.game_id(), &new_path);
-                self.data.parse_file(true)?;
                 let modification_time = self.modification_time();
                 self.set_modification_time(modification_time)?;
             }
@@ -144,34 +221,253 @@ impl Plugin {
         self.active = false;
     }
 
+    pub fn is_override_only(&self) -> Result<bool, Error> {
+        // Morrowind records are identified by editor IDs rather than FormIDs,
+        // so there's no cheap way to tell overrides apart from new records.
+        if self.game == GameId::Morrowind {
+            return Ok(false);
+        }
+
+        let masters_count = self.masters.len();
+
+        let mut content: Vec<u8> = Vec::new();
+        File::open(&self.path)?.read_to_end(&mut content)?;
+
+        let form_ids = read_form_ids(&content, record_header_length(self.game))?;
+
+        Ok(form_ids
+            .into_iter()
+            .all(|form_id| ((form_id >> 24) as usize) < masters_count))
+    }
+
+    /// Get the types of the top-level records in the plugin, i.e. the labels
+    /// of its top-level groups, or for Morrowind the types of its records.
+    /// The plugin's header record is not included.
+    pub fn record_types(&self) -> Result<HashSet<[u8; 4]>, Error> {
+        let mut content: Vec<u8> = Vec::new();
+        File::open(self.path.resolve_path()?)?.read_to_end(&mut content)?;
+
+        read_record_types(&content, record_header_length(self.game))
+    }
+
+    pub fn has_changed_on_disk(&self) -> Result<bool, Error> {
+        let path = match self.path.resolve_path() {
+            Ok(x) => x,
+            Err(_) => return Ok(true),
+        };
+
+        let metadata = path.metadata()?;
+
+        Ok(metadata.modified()? != self.modification_time || metadata.len() != self.size)
+    }
+
+    /// Get the form version from the plugin's header record. Morrowind and
+    /// Oblivion record headers don't have a form version field, so `None` is
+    /// returned for them.
+    pub fn form_version(&self) -> Option<u16> {
+        if record_header_length(self.game) < FORM_VERSION_OFFSET + 2 {
+            return None;
+        }
+
+        let mut content: Vec<u8> = Vec::new();
+        File::open(self.path.resolve_path().ok()?)
+            .ok()?
+            .take(FORM_VERSION_OFFSET as u64 + 2)
+            .read_to_end(&mut content)
+            .ok()?;
+
+        content
+            .get(FORM_VERSION_OFFSET..FORM_VERSION_OFFSET + 2)
+            .map(|bytes| u16::from(bytes[0]) | u16::from(bytes[1]) << 8)
+    }
+
+    pub fn is_blueprint_master(&self) -> bool {
+        self.game.supports_blueprint_masters()
+            && self
+                .header_flags()
+                .map(|flags| flags & BLUEPRINT_MASTER_FLAG != 0)
+                .unwrap_or(false)
+    }
+
+    /// Check if the plugin's header has the master flag set, whatever its file
+    /// extension. This is `None` for Morrowind plugins, which are masters only
+    /// because of their extension, or if the header can't be read.
+    pub fn master_flag(&self) -> Option<bool> {
+        if self.game == GameId::Morrowind {
+            return None;
+        }
+
+        self.header_flags().ok().map(|flags| flags & MASTER_FLAG != 0)
+    }
+
+    fn header_flags(&self) -> Result<u32, Error> {
+        let mut content: Vec<u8> = Vec::new();
+        File::open(self.path.resolve_path()?)?
+            .take(12)
+            .read_to_end(&mut content)?;
+
+        read_u32(&content, 8)
+    }
+
+    /// Get the current size of the plugin's file, in bytes.
+    pub fn file_size(&self) -> Result<u64, Error> {
+        Ok(self.path.resolve_path()?.metadata()?.len())
+    }
+
+    pub fn crc(&self) -> Result<u32, Error> {
+        let mut reader = BufReader::new(File::open(self.path.resolve_path()?)?);
+
+        let mut crc = 0xFFFF_FFFF;
+        loop {
+            let length = {
+                let buffer = reader.fill_buf()?;
+                if buffer.is_empty() {
+                    break;
+                }
+                crc = update_crc32(crc, buffer);
+                buffer.len()
+            };
+            reader.consume(length);
+        }
+
+        Ok(!crc)
+    }
+
     pub fn is_valid(filename: &str, game_settings: &GameSettings) -> bool {
-        if !has_valid_extension(filename, game_settings.id()) {
+        if !has_valid_extension(filename, game_settings) {
             return false;
         }
 
-        match game_settings
-            .plugins_directory()
-            .join(filename)
-            .resolve_path()
-        {
+        match game_settings.plugin_path(filename).resolve_path() {
             Err(_) => false,
             Ok(ref x) => esplugin::Plugin::is_valid(game_settings.id().to_esplugin_id(), x, true),
         }
     }
 }
 
-fn has_valid_extension(filename: &str, game: GameId) -> bool {
-    let valid_extensions = if game.supports_light_masters() {
-        VALID_EXTENSIONS_WITH_ESL
-    } else {
-        VALID_EXTENSIONS
-    };
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    const POLYNOMIAL: u32 = 0xEDB8_8320;
+
+    let mut table = [0; 256];
+    let mut index = 0;
+    while index < table.len() {
+        let mut crc = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+
+    table
+}
+
+fn update_crc32(crc: u32, bytes: &[u8]) -> u32 {
+    bytes.iter().fold(crc, |crc, byte| {
+        (crc >> 8) ^ CRC32_TABLE[((crc ^ u32::from(*byte)) & 0xFF) as usize]
+    })
+}
+
+#[cfg(test)]
+fn crc32(bytes: &[u8]) -> u32 {
+    !update_crc32(0xFFFF_FFFF, bytes)
+}
+
+fn record_header_length(game: GameId) -> usize {
+    match game {
+        GameId::Morrowind => 16,
+        GameId::Oblivion | GameId::Nehrim => 20,
+        _ => 24,
+    }
+}
+
+fn read_u32(content: &[u8], offset: usize) -> Result<u32, Error> {
+    if offset + 4 > content.len() {
+        return Err(Error::PluginParsingError);
+    }
 
-    valid_extensions
+    let bytes = &content[offset..offset + 4];
+    Ok(u32::from(bytes[0])
+        | u32::from(bytes[1]) << 8
+        | u32::from(bytes[2]) << 16
+        | u32::from(bytes[3]) << 24)
+}
+
+fn read_form_ids(content: &[u8], header_length: usize) -> Result<Vec<u32>, Error> {
+    // Skip the plugin header record, then walk the records and groups that
+    // follow. Groups are entered rather than skipped, so that the records they
+    // contain are visited in turn.
+    let mut position = header_length + read_u32(content, 4)? as usize;
+    let mut form_ids = Vec::new();
+
+    while position < content.len() {
+        if content.len() < position + header_length {
+            return Err(Error::PluginParsingError);
+        }
+
+        if &content[position..position + 4] == b"GRUP" {
+            position += header_length;
+        } else {
+            form_ids.push(read_u32(content, position + 12)?);
+            position += header_length + read_u32(content, position + 4)? as usize;
+        }
+    }
+
+    Ok(form_ids)
+}
+
+fn read_record_types(content: &[u8], header_length: usize) -> Result<HashSet<[u8; 4]>, Error> {
+    let mut position = header_length + read_u32(content, 4)? as usize;
+    let mut record_types = HashSet::new();
+
+    while position < content.len() {
+        if content.len() < position + header_length {
+            return Err(Error::PluginParsingError);
+        }
+
+        let mut record_type = [0; 4];
+        if &content[position..position + 4] == b"GRUP" {
+            // Unlike record sizes, group sizes include the group header.
+            if read_u32(content, position + 12)? == 0 {
+                record_type.copy_from_slice(&content[position + 8..position + 12]);
+                record_types.insert(record_type);
+            }
+            position += (read_u32(content, position + 4)? as usize).max(header_length);
+        } else {
+            record_type.copy_from_slice(&content[position..position + 4]);
+            record_types.insert(record_type);
+            position += header_length + read_u32(content, position + 4)? as usize;
+        }
+    }
+
+    Ok(record_types)
+}
+
+pub fn has_valid_extension(filename: &str, game_settings: &GameSettings) -> bool {
+    let filename = trim_dot_ghost(filename);
+
+    game_settings
+        .plugin_extensions()
         .iter()
         .any(|e| iends_with_ascii(filename, e))
 }
 
+/// Check if the filename contains a path separator or control character, as
+/// plugin filenames must not refer to files outside the plugins directory.
+pub fn has_invalid_characters(filename: &str) -> bool {
+    filename
+        .chars()
+        .any(|c| c == '/' || c == '\\' || c.is_control())
+}
+
 fn iends_with_ascii(string: &str, suffix: &str) -> bool {
     // as_bytes().into_iter() is faster than bytes().
     string.len() >= suffix.len()
@@ -195,10 +491,11 @@ pub fn trim_dot_ghost(string: &str) -> &str {
 mod tests {
     use super::*;
 
+    use std::fs::remove_file;
     use std::path::PathBuf;
     use std::time::{Duration, UNIX_EPOCH};
     use tempfile::tempdir;
-    use tests::copy_to_test_dir;
+    use tests::{copy_to_test_dir, set_form_version, write_plugin};
 
     #[test]
     fn name_should_return_the_plugin_filename_without_any_ghost_extension() {
@@ -263,6 +560,39 @@ mod tests {
         assert_eq!(mtime, plugin.modification_time());
     }
 
+    #[test]
+    fn creation_time_should_match_the_file_creation_time_if_it_is_available() {
+        let tmp_dir = tempdir().unwrap();
+        let game_dir = tmp_dir.path();
+
+        let settings =
+            GameSettings::with_local_path(GameId::Oblivion, game_dir, &PathBuf::default())
+                .unwrap();
+
+        copy_to_test_dir("Blank.esp", "Blank.esp", &settings);
+        let plugin_path = game_dir.join("Data").join("Blank.esp");
+        let ctime = plugin_path.metadata().unwrap().created().ok();
+
+        let plugin = Plugin::new("Blank.esp", &settings).unwrap();
+        assert_eq!(ctime, plugin.creation_time().unwrap());
+    }
+
+    #[test]
+    fn creation_time_should_error_if_the_plugin_file_has_been_removed() {
+        let tmp_dir = tempdir().unwrap();
+        let game_dir = tmp_dir.path();
+
+        let settings =
+            GameSettings::with_local_path(GameId::Oblivion, game_dir, &PathBuf::default())
+                .unwrap();
+
+        copy_to_test_dir("Blank.esp", "Blank.esp", &settings);
+        let plugin = Plugin::new("Blank.esp", &settings).unwrap();
+        remove_file(game_dir.join("Data").join("Blank.esp")).unwrap();
+
+        assert!(plugin.creation_time().is_err());
+    }
+
     #[test]
     fn is_active_should_be_false() {
         let tmp_dir = tempdir().unwrap();
@@ -293,6 +623,22 @@ mod tests {
         assert!(plugin.is_master_file());
     }
 
+    #[test]
+    fn is_master_file_should_be_true_for_an_esp_file_with_the_master_flag_set() {
+        let tmp_dir = tempdir().unwrap();
+        let game_dir = tmp_dir.path();
+
+        for &game_id in &[GameId::Oblivion, GameId::Skyrim, GameId::Fallout4] {
+            let settings =
+                GameSettings::with_local_path(game_id, game_dir, &PathBuf::default()).unwrap();
+
+            copy_to_test_dir("Blank.esm", "Blank - Master.esp", &settings);
+            let plugin = Plugin::new("Blank - Master.esp", &settings).unwrap();
+
+            assert!(plugin.is_master_file());
+        }
+    }
+
     #[test]
     fn is_master_file_should_be_false_if_the_plugin_is_not_a_master_file() {
         let tmp_dir = tempdir().unwrap();
@@ -308,6 +654,133 @@ mod tests {
         assert!(!plugin.is_master_file());
     }
 
+    #[test]
+    fn light_master_support_should_depend_on_the_skyrim_se_game_version() {
+        let tmp_dir = tempdir().unwrap();
+        let game_dir = tmp_dir.path();
+
+        let mut settings =
+            GameSettings::with_local_path(GameId::SkyrimSE, game_dir, &PathBuf::default())
+                .unwrap();
+        copy_to_test_dir("Blank.esl", "Blank.esl", &settings);
+
+        settings.set_game_version(Some((1, 4, 2)));
+        assert!(Plugin::new("Blank.esl", &settings).is_err());
+        assert!(!Plugin::is_valid("Blank.esl", &settings));
+
+        settings.set_game_version(Some((1, 5, 97)));
+        let plugin = Plugin::new("Blank.esl", &settings).unwrap();
+        assert!(plugin.is_light_master_file());
+    }
+
+    #[test]
+    fn masters_should_be_read_from_the_header_parsed_on_construction() {
+        let tmp_dir = tempdir().unwrap();
+        let settings =
+            GameSettings::with_local_path(GameId::Skyrim, tmp_dir.path(), &PathBuf::default())
+                .unwrap();
+
+        write_plugin("Test.esp", &["Blank.esm", "Update.esm"], &[0x0100_0001], &settings);
+        let plugin = Plugin::new("Test.esp", &settings).unwrap();
+
+        assert!(!plugin.is_master_file());
+        assert_eq!(vec!["Blank.esm", "Update.esm"], plugin.masters());
+    }
+
+    #[test]
+    fn description_should_be_read_from_the_header_parsed_on_construction() {
+        let tmp_dir = tempdir().unwrap();
+        let settings =
+            GameSettings::with_local_path(GameId::Skyrim, tmp_dir.path(), &PathBuf::default())
+                .unwrap();
+
+        copy_to_test_dir("Blank.esm", "Blank.esm", &settings);
+        let plugin = Plugin::new("Blank.esm", &settings).unwrap();
+        assert_eq!(Some("v5.0"), plugin.description());
+
+        write_plugin("Test.esp", &["Blank.esm"], &[0x0100_0001], &settings);
+        let plugin = Plugin::new("Test.esp", &settings).unwrap();
+        assert_eq!(None, plugin.description());
+    }
+
+    #[test]
+    fn is_override_only_should_be_true_if_all_records_belong_to_masters() {
+        let tmp_dir = tempdir().unwrap();
+        let game_dir = tmp_dir.path();
+
+        for &game_id in &[GameId::Oblivion, GameId::Skyrim] {
+            let settings =
+                GameSettings::with_local_path(game_id, game_dir, &PathBuf::default()).unwrap();
+
+            write_plugin(
+                "Patch.esp",
+                &["Blank.esm", "Blank - Different.esm"],
+                &[0x0000_0CEC, 0x0100_0CED],
+                &settings,
+            );
+            let plugin = Plugin::new("Patch.esp", &settings).unwrap();
+
+            assert!(plugin.is_override_only().unwrap());
+        }
+    }
+
+    #[test]
+    fn record_types_should_return_the_labels_of_top_level_groups() {
+        let tmp_dir = tempdir().unwrap();
+        let game_dir = tmp_dir.path();
+
+        for &game_id in &[GameId::Oblivion, GameId::Skyrim] {
+            let settings =
+                GameSettings::with_local_path(game_id, game_dir, &PathBuf::default()).unwrap();
+
+            write_plugin("Content.esp", &["Blank.esm"], &[0x0100_0CEC], &settings);
+            let plugin = Plugin::new("Content.esp", &settings).unwrap();
+
+            let expected: HashSet<[u8; 4]> = [*b"GLOB"].iter().cloned().collect();
+            assert_eq!(expected, plugin.record_types().unwrap());
+
+            copy_to_test_dir("Blank.esp", "Blank.esp", &settings);
+            let plugin = Plugin::new("Blank.esp", &settings).unwrap();
+
+            assert!(plugin.record_types().unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn is_override_only_should_be_false_if_the_plugin_adds_new_records() {
+        let tmp_dir = tempdir().unwrap();
+        let game_dir = tmp_dir.path();
+
+        for &game_id in &[GameId::Oblivion, GameId::Skyrim] {
+            let settings =
+                GameSettings::with_local_path(game_id, game_dir, &PathBuf::default()).unwrap();
+
+            write_plugin(
+                "Content.esp",
+                &["Blank.esm"],
+                &[0x0000_0CEC, 0x0100_0CED],
+                &settings,
+            );
+            let plugin = Plugin::new("Content.esp", &settings).unwrap();
+
+            assert!(!plugin.is_override_only().unwrap());
+        }
+    }
+
+    #[test]
+    fn is_override_only_should_be_false_for_a_plugin_with_no_masters_and_records() {
+        let tmp_dir = tempdir().unwrap();
+        let game_dir = tmp_dir.path();
+
+        let settings =
+            GameSettings::with_local_path(GameId::Skyrim, game_dir, &PathBuf::default()).unwrap();
+
+        write_plugin("Content.esp", &[], &[0x0000_0800], &settings);
+        let plugin = Plugin::new("Content.esp", &settings).unwrap();
+
+        assert!(!plugin.is_override_only().unwrap());
+    }
+
     #[test]
     fn is_light_master_file_should_be_true_for_esl_files_only() {
         let tmp_dir = tempdir().unwrap();
@@ -338,6 +811,51 @@ mod tests {
         assert!(plugin.is_light_master_file());
     }
 
+    #[test]
+    fn modification_time_should_be_the_utc_epoch_time_written_to_the_file() {
+        let tmp_dir = tempdir().unwrap();
+        let game_dir = tmp_dir.path();
+
+        let settings =
+            GameSettings::with_local_path(GameId::Oblivion, game_dir, &PathBuf::default())
+                .unwrap();
+
+        copy_to_test_dir("Blank.esp", "Blank.esp", &settings);
+        set_file_times(
+            game_dir.join("Data").join("Blank.esp"),
+            FileTime::zero(),
+            FileTime::from_unix_time(1_500_000_000, 0),
+        ).unwrap();
+
+        let plugin = Plugin::new("Blank.esp", &settings).unwrap();
+
+        assert_eq!(
+            UNIX_EPOCH + Duration::from_secs(1_500_000_000),
+            plugin.modification_time()
+        );
+    }
+
+    #[test]
+    fn set_modification_time_should_write_utc_epoch_seconds() {
+        let tmp_dir = tempdir().unwrap();
+        let game_dir = tmp_dir.path();
+
+        let settings =
+            GameSettings::with_local_path(GameId::Oblivion, game_dir, &PathBuf::default())
+                .unwrap();
+
+        copy_to_test_dir("Blank.esp", "Blank.esp", &settings);
+        let mut plugin = Plugin::new("Blank.esp", &settings).unwrap();
+
+        plugin
+            .set_modification_time(UNIX_EPOCH + Duration::from_secs(1_500_000_000))
+            .unwrap();
+
+        let metadata = game_dir.join("Data").join("Blank.esp").metadata().unwrap();
+        let mtime = FileTime::from_last_modification_time(&metadata);
+        assert_eq!(1_500_000_000, mtime.unix_seconds());
+    }
+
     #[test]
     fn set_modification_time_should_update_the_file_modification_time() {
         let tmp_dir = tempdir().unwrap();
@@ -428,6 +946,127 @@ mod tests {
         assert!(game_dir.join("Data").join("Blank.esp").exists());
     }
 
+    #[test]
+    fn is_blueprint_master_should_be_false_for_all_supported_games() {
+        let tmp_dir = tempdir().unwrap();
+
+        for &game_id in &[GameId::Morrowind, GameId::Skyrim, GameId::SkyrimSE, GameId::Fallout4] {
+            let settings =
+                GameSettings::with_local_path(game_id, tmp_dir.path(), &PathBuf::default())
+                    .unwrap();
+
+            for plugin_name in &["Blank.esm", "Blank.esp"] {
+                copy_to_test_dir(plugin_name, plugin_name, &settings);
+                let plugin = Plugin::new(plugin_name, &settings).unwrap();
+
+                assert!(!plugin.is_blueprint_master());
+            }
+        }
+    }
+
+    #[test]
+    fn master_flag_should_ignore_the_file_extension() {
+        let tmp_dir = tempdir().unwrap();
+        let settings =
+            GameSettings::with_local_path(GameId::SkyrimSE, tmp_dir.path(), &PathBuf::default())
+                .unwrap();
+
+        copy_to_test_dir("Blank.esm", "Blank.esp", &settings);
+        copy_to_test_dir("Blank.esp", "Blank.esm", &settings);
+
+        assert_eq!(Some(true), Plugin::new("Blank.esp", &settings).unwrap().master_flag());
+        assert_eq!(Some(false), Plugin::new("Blank.esm", &settings).unwrap().master_flag());
+    }
+
+    #[test]
+    fn master_flag_should_be_none_for_morrowind() {
+        let tmp_dir = tempdir().unwrap();
+        let settings =
+            GameSettings::with_local_path(GameId::Morrowind, tmp_dir.path(), &PathBuf::default())
+                .unwrap();
+
+        copy_to_test_dir("Blank.esm", "Blank.esm", &settings);
+
+        assert_eq!(None, Plugin::new("Blank.esm", &settings).unwrap().master_flag());
+    }
+
+    #[test]
+    fn header_flags_should_read_the_header_record_flags() {
+        let tmp_dir = tempdir().unwrap();
+        let settings =
+            GameSettings::with_local_path(GameId::Skyrim, tmp_dir.path(), &PathBuf::default())
+                .unwrap();
+
+        copy_to_test_dir("Blank.esm", "Blank.esm", &settings);
+        let plugin = Plugin::new("Blank.esm", &settings).unwrap();
+
+        assert_eq!(1, plugin.header_flags().unwrap() & 1);
+        assert_eq!(0, plugin.header_flags().unwrap() & BLUEPRINT_MASTER_FLAG);
+    }
+
+    #[test]
+    fn form_version_should_be_none_for_games_without_a_form_version_field() {
+        let tmp_dir = tempdir().unwrap();
+
+        for &game_id in &[GameId::Morrowind, GameId::Oblivion] {
+            let settings =
+                GameSettings::with_local_path(game_id, tmp_dir.path(), &PathBuf::default())
+                    .unwrap();
+
+            copy_to_test_dir("Blank.esp", "Blank.esp", &settings);
+            let plugin = Plugin::new("Blank.esp", &settings).unwrap();
+
+            assert_eq!(None, plugin.form_version());
+        }
+    }
+
+    #[test]
+    fn form_version_should_read_the_header_record_form_version() {
+        let tmp_dir = tempdir().unwrap();
+
+        for &(game_id, form_version) in &[
+            (GameId::Skyrim, 43),
+            (GameId::SkyrimSE, 44),
+            (GameId::Fallout4, 131),
+        ] {
+            let settings =
+                GameSettings::with_local_path(game_id, tmp_dir.path(), &PathBuf::default())
+                    .unwrap();
+
+            write_plugin("Blank.esp", &[], &[], &settings);
+            set_form_version("Blank.esp", form_version, &settings);
+            let plugin = Plugin::new("Blank.esp", &settings).unwrap();
+
+            assert_eq!(Some(form_version), plugin.form_version());
+        }
+    }
+
+    #[test]
+    fn crc32_should_return_the_standard_crc_32_checksum() {
+        assert_eq!(0, crc32(&[]));
+        assert_eq!(0xCBF4_3926, crc32(b"123456789"));
+    }
+
+    #[test]
+    fn crc_should_be_equal_for_plugins_with_identical_content() {
+        let tmp_dir = tempdir().unwrap();
+        let game_dir = tmp_dir.path();
+
+        let settings =
+            GameSettings::with_local_path(GameId::Oblivion, game_dir, &PathBuf::default())
+                .unwrap();
+
+        copy_to_test_dir("Blank.esp", "Blank.esp", &settings);
+        copy_to_test_dir("Blank.esp", "Blank - Copy.esp", &settings);
+        copy_to_test_dir("Blank - Different.esp", "Blank - Different.esp", &settings);
+        let plugin = Plugin::new("Blank.esp", &settings).unwrap();
+        let copy = Plugin::new("Blank - Copy.esp", &settings).unwrap();
+        let different = Plugin::new("Blank - Different.esp", &settings).unwrap();
+
+        assert_eq!(plugin.crc().unwrap(), copy.crc().unwrap());
+        assert_ne!(plugin.crc().unwrap(), different.crc().unwrap());
+    }
+
     #[test]
     fn is_valid_should_return_true_for_a_valid_plugin() {
         let tmp_dir = tempdir().unwrap();
@@ -17,7 +17,8 @@
  * along with libloadorder. If not, see <http://www.gnu.org/licenses/>.
  */
 
-use std::fs::{copy, create_dir};
+use std::fs::{copy, create_dir, File, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 
 use enums::GameId;
@@ -32,11 +33,103 @@ pub fn copy_to_test_dir(from_path: &str, to_file: &str, game_settings: &GameSett
     copy(testing_plugins_dir.join(from_path), data_dir.join(to_file)).unwrap();
 }
 
+pub fn write_plugin(
+    filename: &str,
+    masters: &[&str],
+    form_ids: &[u32],
+    game_settings: &GameSettings,
+) {
+    let header_length = match game_settings.id() {
+        GameId::Oblivion => 20,
+        _ => 24,
+    };
+
+    let mut header_data: Vec<u8> = Vec::new();
+    write_subrecord(&mut header_data, b"HEDR", &[0; 12]);
+    for master in masters {
+        let mut master_data = master.as_bytes().to_vec();
+        master_data.push(0);
+        write_subrecord(&mut header_data, b"MAST", &master_data);
+        write_subrecord(&mut header_data, b"DATA", &[0; 8]);
+    }
+
+    let mut records: Vec<u8> = Vec::new();
+    for form_id in form_ids {
+        write_record_header(&mut records, b"GLOB", 0, *form_id, header_length);
+    }
+
+    let mut content: Vec<u8> = Vec::new();
+    write_record_header(&mut content, b"TES4", header_data.len(), 0, header_length);
+    content.extend(header_data);
+    if !records.is_empty() {
+        let group_size = header_length + records.len();
+        write_group_header(&mut content, b"GLOB", group_size, header_length);
+        content.extend(records);
+    }
+
+    let data_dir = game_settings.plugins_directory();
+    if !data_dir.exists() {
+        create_dir(&data_dir).unwrap();
+    }
+    File::create(data_dir.join(filename))
+        .unwrap()
+        .write_all(&content)
+        .unwrap();
+}
+
+pub fn set_form_version(filename: &str, form_version: u16, game_settings: &GameSettings) {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .open(game_settings.plugins_directory().join(filename))
+        .unwrap();
+
+    file.seek(SeekFrom::Start(20)).unwrap();
+    file.write_all(&[form_version as u8, (form_version >> 8) as u8])
+        .unwrap();
+}
+
+fn write_subrecord(buffer: &mut Vec<u8>, subrecord_type: &[u8], data: &[u8]) {
+    buffer.extend(subrecord_type);
+    buffer.extend(&[data.len() as u8, (data.len() >> 8) as u8]);
+    buffer.extend(data);
+}
+
+fn write_group_header(buffer: &mut Vec<u8>, label: &[u8], size: usize, header_length: usize) {
+    buffer.extend(b"GRUP");
+    buffer.extend(&to_le_bytes(size as u32));
+    buffer.extend(label);
+    // A group type of 0 marks a top-level group.
+    buffer.extend(vec![0; header_length - 12]);
+}
+
+fn write_record_header(
+    buffer: &mut Vec<u8>,
+    record_type: &[u8],
+    size: usize,
+    form_id: u32,
+    header_length: usize,
+) {
+    buffer.extend(record_type);
+    buffer.extend(&to_le_bytes(size as u32));
+    buffer.extend(&[0; 4]);
+    buffer.extend(&to_le_bytes(form_id));
+    buffer.extend(vec![0; header_length - 16]);
+}
+
+fn to_le_bytes(value: u32) -> [u8; 4] {
+    [
+        value as u8,
+        (value >> 8) as u8,
+        (value >> 16) as u8,
+        (value >> 24) as u8,
+    ]
+}
+
 fn testing_plugins_dir(game_id: GameId) -> PathBuf {
     use GameId::*;
     let game_folder = match game_id {
         Morrowind => "Morrowind",
-        Oblivion => "Oblivion",
+        Oblivion | Nehrim => "Oblivion",
         Fallout4 | Fallout4VR | SkyrimSE | SkyrimVR => "SkyrimSE",
         _ => "Skyrim",
     };
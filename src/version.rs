@@ -0,0 +1,79 @@
+/*
+ * This file is part of libloadorder
+ *
+ * Copyright (C) 2017 Oliver Hamlet
+ *
+ * libloadorder is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * libloadorder is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with libloadorder. If not, see <http://www.gnu.org/licenses/>.
+ */
+
+/// Returns the (major, minor, patch) version numbers of the compiled library.
+pub fn version() -> (u32, u32, u32) {
+    (
+        parse_version_number(env!("CARGO_PKG_VERSION_MAJOR")),
+        parse_version_number(env!("CARGO_PKG_VERSION_MINOR")),
+        parse_version_number(env!("CARGO_PKG_VERSION_PATCH")),
+    )
+}
+
+/// Checks whether the compiled library is compatible with the given version.
+///
+/// Versions with the same non-zero major version are compatible. Before 1.0.0,
+/// minor versions are treated as breaking, so the minor version must also match.
+pub fn is_compatible(major: u32, minor: u32) -> bool {
+    let (library_major, library_minor, _) = version();
+
+    if library_major > 0 {
+        major == library_major
+    } else {
+        major == 0 && minor == library_minor
+    }
+}
+
+fn parse_version_number(number: &str) -> u32 {
+    number
+        .parse()
+        .expect("Cargo package version numbers should be valid integers")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_should_match_the_cargo_package_version() {
+        let (major, minor, patch) = version();
+
+        assert_eq!(
+            env!("CARGO_PKG_VERSION"),
+            format!("{}.{}.{}", major, minor, patch)
+        );
+    }
+
+    #[test]
+    fn is_compatible_should_be_true_for_the_same_major_version() {
+        let (major, minor, _) = version();
+
+        assert!(is_compatible(major, minor));
+        assert!(is_compatible(major, minor + 1));
+        assert!(is_compatible(major, 0));
+    }
+
+    #[test]
+    fn is_compatible_should_be_false_for_a_different_major_version() {
+        let (major, minor, _) = version();
+
+        assert!(!is_compatible(major + 1, minor));
+        assert!(!is_compatible(major - 1, minor));
+    }
+}